@@ -1,17 +1,16 @@
 use std::env;
 use std::path::PathBuf;
 
-fn main() {
-    let include_path =
-        env::var("CJSON_INCLUDE_PATH").expect("Environment variable CJSON_INCLUDE_PATH not found");
-    let lib_path =
-        env::var("CJSON_LIB_PATH").expect("Environment variable CJSON_LIB_PATH not found");
+// cJSON release that the sources under vendor/cJSON are pinned to
+#[cfg(feature = "vendored")]
+const VENDORED_CJSON_VERSION: (u32, u32, u32) = (1, 7, 18);
 
-    println!("cargo:rustc-link-search={}", lib_path);
-    println!("cargo:rustc-link-lib=cjson");
+fn main() {
+    let include_path = find_cjson();
 
     let bindings = bindgen::Builder::default()
-        .header(format!("{}/cJSON.h", include_path))
+        .header(include_path.join("cJSON.h").to_string_lossy())
+        .clang_arg(format!("-I{}", include_path.display()))
         .generate()
         .expect("Unable to generate bindings");
 
@@ -20,3 +19,100 @@ fn main() {
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Couldn't write bindings");
 }
+
+// compile the vendored cJSON sources and return the directory holding cJSON.h
+#[cfg(feature = "vendored")]
+fn find_cjson() -> PathBuf {
+    let vendor_path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("vendor/cJSON");
+    let source = vendor_path.join("cJSON.c");
+    let header = vendor_path.join("cJSON.h");
+    if !source.exists() || !header.exists() {
+        panic!(
+            "The `vendored` feature expects the cJSON {}.{}.{} sources (cJSON.c and cJSON.h) in {}",
+            VENDORED_CJSON_VERSION.0,
+            VENDORED_CJSON_VERSION.1,
+            VENDORED_CJSON_VERSION.2,
+            vendor_path.display()
+        );
+    }
+    println!("cargo:rerun-if-changed={}", source.display());
+    println!("cargo:rerun-if-changed={}", header.display());
+
+    // make sure the CJSON_VERSION_* constants generated from the header match the pinned release
+    let header_contents = std::fs::read_to_string(&header).expect("Couldn't read cJSON.h");
+    let header_version = |name: &str| -> u32 {
+        header_contents
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(&format!("#define {} ", name)))
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or_else(|| panic!("{} not found in {}", name, header.display()))
+    };
+    let version = (
+        header_version("CJSON_VERSION_MAJOR"),
+        header_version("CJSON_VERSION_MINOR"),
+        header_version("CJSON_VERSION_PATCH"),
+    );
+    assert_eq!(
+        version, VENDORED_CJSON_VERSION,
+        "The vendored cJSON.h does not match the pinned cJSON version"
+    );
+
+    cc::Build::new()
+        .file(&source)
+        .include(&vendor_path)
+        .warnings(false)
+        .compile("cjson");
+
+    vendor_path
+}
+
+// locate a system-wide cJSON and return the directory holding cJSON.h
+//
+// The CJSON_INCLUDE_PATH and CJSON_LIB_PATH environment variables take precedence when both are
+// set, otherwise pkg-config is asked for `libcjson`.
+#[cfg(not(feature = "vendored"))]
+fn find_cjson() -> PathBuf {
+    println!("cargo:rerun-if-env-changed=CJSON_INCLUDE_PATH");
+    println!("cargo:rerun-if-env-changed=CJSON_LIB_PATH");
+
+    let env_error = match (env::var("CJSON_INCLUDE_PATH"), env::var("CJSON_LIB_PATH")) {
+        (Ok(include_path), Ok(lib_path)) => {
+            println!("cargo:rustc-link-search={}", lib_path);
+            println!("cargo:rustc-link-lib=cjson");
+            return PathBuf::from(include_path);
+        }
+        (Err(_), Err(_)) => "CJSON_INCLUDE_PATH and CJSON_LIB_PATH are not set".to_string(),
+        (Err(_), Ok(_)) => "CJSON_INCLUDE_PATH is not set".to_string(),
+        (Ok(_), Err(_)) => "CJSON_LIB_PATH is not set".to_string(),
+    };
+
+    // pkg-config emits the cargo:rustc-link-* directives itself
+    let pkg_config_error = match pkg_config::Config::new().probe("libcjson") {
+        Ok(library) => {
+            // pkg-config leaves out system include directories, so /usr/include is checked too
+            let include_path = library
+                .include_paths
+                .iter()
+                .chain([PathBuf::from("/usr/include")].iter())
+                .flat_map(|path| [path.clone(), path.join("cjson")])
+                .find(|path| path.join("cJSON.h").exists());
+            match include_path {
+                Some(include_path) => return include_path,
+                None => format!(
+                    "cJSON.h not found in the reported include paths {:?}",
+                    library.include_paths
+                ),
+            }
+        }
+        Err(err) => err.to_string(),
+    };
+
+    panic!(
+        "Unable to locate the cJSON library. Attempted:\n\
+         - environment variables: {}\n\
+         - pkg-config (libcjson): {}\n\
+         Set CJSON_INCLUDE_PATH and CJSON_LIB_PATH, install cJSON with a libcjson.pc file, or enable \
+         the `vendored` feature.",
+        env_error, pkg_config_error
+    );
+}
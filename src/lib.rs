@@ -1,6 +1,10 @@
 mod bindings;
 use bindings::*;
+use std::borrow::Cow;
 use std::ffi::{c_char, c_void, CStr, CString, NulError};
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::Arc;
 
 pub const CJSON_VERSION_MAJOR: u32 = bindings::CJSON_VERSION_MAJOR;
 pub const CJSON_VERSION_MINOR: u32 = bindings::CJSON_VERSION_MINOR;
@@ -30,6 +34,89 @@ pub fn cjson_version() -> String {
     }
 }
 
+/// Get the version of the cJSON library the bindings were generated from as a
+/// `(major, minor, patch)` tuple.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let parsed: Vec<u32> = cjson_version().split('.').map(|part| part.parse().unwrap()).collect();
+///     let (major, minor, patch) = cjson_version_tuple();
+///     assert_eq!(vec![major, minor, patch], parsed);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_version_tuple() -> (u32, u32, u32) {
+    (
+        CJSON_VERSION_MAJOR,
+        CJSON_VERSION_MINOR,
+        CJSON_VERSION_PATCH,
+    )
+}
+
+/// Check whether the version of the cJSON library is at least the given version.
+///
+/// Args:
+/// - `major: u32` - Minimum major version.
+/// - `minor: u32` - Minimum minor version.
+/// - `patch: u32` - Minimum patch version.
+///
+/// Returns:
+/// - `bool` - true if the cJSON version is greater than or equal to `major.minor.patch`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let (major, minor, patch) = cjson_version_tuple();
+///     assert_eq!(cjson_version_at_least(major, minor, patch), true);
+///     assert_eq!(cjson_version_at_least(major, minor, patch + 1), false);
+///     assert_eq!(cjson_version_at_least(major, minor + 1, 0), false);
+///     assert_eq!(cjson_version_at_least(major + 1, 0, 0), false);
+///     assert_eq!(cjson_version_at_least(0, 0, 0), true);
+///     if patch > 0 {
+///         assert_eq!(cjson_version_at_least(major, minor, patch - 1), true);
+///     }
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_version_at_least(major: u32, minor: u32, patch: u32) -> bool {
+    cjson_version_tuple() >= (major, minor, patch)
+}
+
+/// Trait for stateful custom memory allocators (eg. arenas or counting allocators) that cJSON can
+/// be told to use through `Hooks::from_allocator`.
+///
+/// Both methods get called from C, so they must not panic. The allocator is shared by every thread
+/// that uses cJSON, hence the `Send + Sync` bounds.
+pub trait Allocator: Send + Sync {
+    /// Allocate `size` bytes, returning a null pointer on failure.
+    fn alloc(&self, size: usize) -> *mut libc::c_void;
+    /// Free memory previously returned by `alloc`.
+    fn free(&self, ptr: *mut libc::c_void);
+}
+
+// allocator installed by Hooks::init (boxed since an AtomicPtr cannot hold a trait object pointer)
+static ALLOCATOR: AtomicPtr<Arc<dyn Allocator>> = AtomicPtr::new(std::ptr::null_mut());
+
+// dispatch an allocation to the allocator installed by Hooks::from_allocator
+fn allocator_malloc(size: usize) -> *mut libc::c_void {
+    match unsafe { ALLOCATOR.load(Ordering::Acquire).as_ref() } {
+        Some(allocator) => allocator.alloc(size),
+        None => std::ptr::null_mut(),
+    }
+}
+
+// dispatch a deallocation to the allocator installed by Hooks::from_allocator
+fn allocator_free(ptr: *mut libc::c_void) {
+    if let Some(allocator) = unsafe { ALLOCATOR.load(Ordering::Acquire).as_ref() } {
+        allocator.free(ptr)
+    }
+}
+
 /// Struct for managing custom memory allocation and deallocation functions.
 ///
 /// Fields:
@@ -38,6 +125,7 @@ pub fn cjson_version() -> String {
 pub struct Hooks {
     pub malloc_fn: Option<fn(sz: usize) -> *mut libc::c_void>,
     pub free_fn: Option<fn(*mut libc::c_void)>,
+    allocator: Option<Arc<dyn Allocator>>,
 }
 
 impl Hooks {
@@ -70,7 +158,76 @@ impl Hooks {
         malloc_fn: Option<fn(usize) -> *mut libc::c_void>,
         free_fn: Option<fn(*mut libc::c_void)>,
     ) -> Hooks {
-        Hooks { malloc_fn, free_fn }
+        Hooks {
+            malloc_fn,
+            free_fn,
+            allocator: None,
+        }
+    }
+
+    /// Create new instance of the Hooks struct that dispatches to a (possibly stateful) allocator.
+    ///
+    /// The allocator is kept in the returned Hooks and only installed by `init()`, which replaces
+    /// (and drops) any allocator installed before, so only one allocator is active at a time.
+    ///
+    /// NOTE: As with any hooks, memory allocated through one allocator must be freed through that
+    /// same allocator, so the hooks must not change while trees it allocated are still alive.
+    ///
+    /// Args:
+    /// - `allocator: A` - The allocator that cJSON should use once the hooks are initialized.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// struct CountingAllocator {
+    ///     allocs: Arc<AtomicUsize>,
+    ///     frees: Arc<AtomicUsize>,
+    /// }
+    ///
+    /// impl Allocator for CountingAllocator {
+    ///     fn alloc(&self, size: usize) -> *mut libc::c_void {
+    ///         self.allocs.fetch_add(1, Ordering::SeqCst);
+    ///         unsafe { libc::malloc(size) }
+    ///     }
+    ///
+    ///     fn free(&self, ptr: *mut libc::c_void) {
+    ///         self.frees.fetch_add(1, Ordering::SeqCst);
+    ///         unsafe { libc::free(ptr) }
+    ///     }
+    /// }
+    ///
+    /// fn main() {
+    ///     let allocs = Arc::new(AtomicUsize::new(0));
+    ///     let frees = Arc::new(AtomicUsize::new(0));
+    ///     let hooks = Hooks::from_allocator(CountingAllocator {
+    ///         allocs: allocs.clone(),
+    ///         frees: frees.clone(),
+    ///     });
+    ///
+    ///     // nothing changes until the hooks are initialized
+    ///     let mut before = cjson_create_array();
+    ///     cjson_delete(&mut before);
+    ///     assert_eq!(allocs.load(Ordering::SeqCst), 0);
+    ///     hooks.init();
+    ///
+    ///     let mut json = cjson_parse_json(r#"{"name":"Nemuel","tags":[1,2]}"#.to_string()).unwrap();
+    ///     assert!(allocs.load(Ordering::SeqCst) > 0);
+    ///     cjson_delete(&mut json);
+    ///     assert_eq!(allocs.load(Ordering::SeqCst), frees.load(Ordering::SeqCst));
+    ///
+    ///     Hooks::new(None, None).init(); // back to the default C functions
+    ///     println!("Test passed"); // output: Test passed
+    /// }
+    /// ```
+    pub fn from_allocator<A: Allocator + 'static>(allocator: A) -> Hooks {
+        Hooks {
+            malloc_fn: Some(allocator_malloc),
+            free_fn: Some(allocator_free),
+            allocator: Some(Arc::new(allocator)),
+        }
     }
 
     // map Hooks instance to cJSON_Hooks instance
@@ -82,8 +239,7 @@ impl Hooks {
 
                 unsafe extern "C" fn c_malloc(sz: usize) -> *mut libc::c_void {
                     if let Some(f) = RUST_MALLOC_FN {
-                        let boxed = Box::new(f(sz));
-                        Box::into_raw(boxed) as *mut libc::c_void
+                        f(sz)
                     } else {
                         std::ptr::null_mut()
                     }
@@ -110,6 +266,9 @@ impl Hooks {
 
     /// Initialize the custom memory management hooks.
     ///
+    /// This replaces the hooks (and allocator) installed before, so it must not be called while
+    /// other threads use cJSON or while trees allocated through the previous hooks are still alive.
+    ///
     /// Usage:
     /// ```rust
     /// use cjson_rs::Hooks;
@@ -131,9 +290,18 @@ impl Hooks {
     /// }
     /// ```
     pub fn init(&self) {
+        let allocator = match &self.allocator {
+            Some(allocator) => Box::into_raw(Box::new(Arc::clone(allocator))),
+            None => std::ptr::null_mut(),
+        };
+        let previous = ALLOCATOR.swap(allocator, Ordering::AcqRel);
         unsafe {
             cJSON_InitHooks(&mut self.to_cjson_hooks());
         }
+        // the previous allocator is no longer reachable from cJSON
+        if !previous.is_null() {
+            drop(unsafe { Box::from_raw(previous) });
+        }
     }
 }
 
@@ -166,23 +334,60 @@ pub struct Json {
 #[derive(Debug)]
 pub enum JsonError {
     CStringError(NulError),
+    CycleDetected,
+    DuplicateKey(String),
     EmptyStringError,
+    ExtendArrayError(usize),
+    FmtError(std::fmt::Error),
+    IndexOutOfRange(i32),
+    InvalidPatchError(String),
+    InvalidPointerError(String),
     InvalidTypeError(String),
+    NonFiniteNumber,
     NullPointer,
+    NumberOutOfRange,
     ParseError,
+    ParseErrorAt { line: usize, column: usize },
+    PatchTestFailed(String),
     PrintError,
     PrintBufferedError,
     PrintPreallocatedError,
+    SetStringFailed,
+    TrailingData(usize),
+    Utf8Error(std::str::Utf8Error),
 }
 
 impl std::fmt::Display for JsonError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             JsonError::CStringError(err) => write!(f, "CString error: {}", err),
+            JsonError::CycleDetected => write!(f, "the item would end up containing itself"),
+            JsonError::DuplicateKey(key) => write!(f, "the key \"{}\" appears more than once", key),
             JsonError::EmptyStringError => write!(f, "you provided an empty string"),
+            JsonError::ExtendArrayError(index) => {
+                write!(f, "failed to add the item at index {} to the array", index)
+            }
+            JsonError::FmtError(err) => write!(f, "Fmt error: {}", err),
+            JsonError::IndexOutOfRange(index) => write!(f, "there is no item at index {}", index),
+            JsonError::InvalidPatchError(err) => write!(f, "InvalidPatch error: {}", err),
+            JsonError::InvalidPointerError(err) => write!(f, "InvalidPointer error: {}", err),
             JsonError::InvalidTypeError(err) => write!(f, "InvalidType error: {}", err),
+            JsonError::NonFiniteNumber => {
+                write!(f, "NaN and infinite numbers cannot be represented in JSON")
+            }
             JsonError::NullPointer => write!(f, "the JSON pointer is null"),
+            JsonError::NumberOutOfRange => {
+                write!(f, "the number cannot be represented by the target type")
+            }
             JsonError::ParseError => write!(f, "failed to parse the JSON string"),
+            JsonError::ParseErrorAt { line, column } => write!(
+                f,
+                "failed to parse the JSON string at line {}, column {}",
+                line, column
+            ),
+            JsonError::PatchTestFailed(path) => {
+                write!(f, "the patch test operation at \"{}\" failed", path)
+            }
             JsonError::PrintError => write!(f, "failed to print the JSON object"),
             JsonError::PrintBufferedError => {
                 write!(f, "failed to print the JSON object to allocated buffer")
@@ -190,6 +395,11 @@ impl std::fmt::Display for JsonError {
             JsonError::PrintPreallocatedError => {
                 write!(f, "failed to print the JSON object to preallocated buffer")
             }
+            JsonError::SetStringFailed => write!(f, "failed to set the string value"),
+            JsonError::TrailingData(offset) => {
+                write!(f, "unexpected data after the JSON value at byte {}", offset)
+            }
+            JsonError::Utf8Error(err) => write!(f, "Utf8 error: {}", err),
         }
     }
 }
@@ -247,12 +457,23 @@ impl Json {
         unsafe { cJSON_IsRaw(self as *const Json as *const cJSON) == 1 }
     }
 
+    // copy a string generated by one of the cJSON printers and free the C buffer
+    fn take_printed(c_str: *mut c_char) -> String {
+        String::from_utf8(Json::take_printed_bytes(c_str)).unwrap_or_default()
+    }
+
+    // copy the bytes generated by one of the cJSON printers and free the C buffer
+    fn take_printed_bytes(c_str: *mut c_char) -> Vec<u8> {
+        let printed = unsafe { CStr::from_ptr(c_str) }.to_bytes().to_vec();
+        unsafe { cJSON_free(c_str as *mut c_void) };
+        printed
+    }
+
     // generate a string representation of the JSON object
     fn print(&self) -> Result<String, JsonError> {
         let c_str = unsafe { cJSON_Print(self as *const Json as *const cJSON) };
         if !c_str.is_null() {
-            let c_str_ref = unsafe { CStr::from_ptr(c_str) };
-            Ok(c_str_ref.to_str().unwrap_or_default().to_string())
+            Ok(Json::take_printed(c_str))
         } else {
             Err(JsonError::PrintError)
         }
@@ -268,8 +489,7 @@ impl Json {
             )
         };
         if !c_str.is_null() {
-            let c_str_ref = unsafe { CStr::from_ptr(c_str) };
-            Ok(c_str_ref.to_str().unwrap_or_default().to_string())
+            Ok(Json::take_printed(c_str))
         } else {
             Err(JsonError::PrintBufferedError)
         }
@@ -291,8 +511,7 @@ impl Json {
     fn print_unformatted(&self) -> Result<String, JsonError> {
         let c_str = unsafe { cJSON_PrintUnformatted(self as *const Json as *const cJSON) };
         if !c_str.is_null() {
-            let c_str_ref = unsafe { CStr::from_ptr(c_str) };
-            Ok(c_str_ref.to_str().unwrap_or_default().to_string())
+            Ok(Json::take_printed(c_str))
         } else {
             Err(JsonError::PrintError)
         }
@@ -302,159 +521,2451 @@ impl Json {
     fn delete(&self) {
         unsafe { cJSON_Delete(self as *const Json as *mut cJSON) };
     }
-}
 
-pub trait JsonPtrExt {
-    fn is_type_invalid(&self) -> bool;
-    fn is_type_false(&self) -> bool;
-    fn is_type_true(&self) -> bool;
-    fn is_type_bool(&self) -> bool;
-    fn is_type_null(&self) -> bool;
-    fn is_type_number(&self) -> bool;
-    fn is_type_string(&self) -> bool;
-    fn is_type_array(&self) -> bool;
-    fn is_type_object(&self) -> bool;
-    fn is_type_raw(&self) -> bool;
-    fn print(&self) -> Result<String, JsonError>;
-    fn print_buffered(&self, prebuffer: i32, fmt: bool) -> Result<String, JsonError>;
-    fn print_preallocated(
-        &self,
-        buffer: *mut i8,
-        length: i32,
-        format: bool,
-    ) -> Result<(), JsonError>;
-    fn print_unformatted(&self) -> Result<String, JsonError>;
-    fn delete(&self);
-}
+    /// Get the value of a Json item of type `Bool` (`None` for any other type).
+    pub fn as_bool(&self) -> Option<bool> {
+        if self.is_type_bool() {
+            Some(self.is_type_true())
+        } else {
+            None
+        }
+    }
 
-impl JsonPtrExt for *mut Json {
-    /// Check whether the Json object is of type `Invalid`.
-    ///
-    /// Returns:
-    /// - `bool` - indicating whether or not the Json object is of type `Invalid`.
-    fn is_type_invalid(&self) -> bool {
-        match unsafe { self.as_mut() } {
-            Some(json) => json.is_type_invalid(),
-            None => false,
+    /// Get the value of a Json item of type `Number` (`None` for any other type).
+    pub fn as_f64(&self) -> Option<f64> {
+        if self.is_type_number() {
+            Some(self.valuedouble)
+        } else {
+            None
         }
     }
 
-    /// Check whether the Json object is of type `False`.
-    ///
-    /// Returns:
-    /// - `bool` - indicating whether or not the Json object is of type `False`.
-    fn is_type_false(&self) -> bool {
-        match unsafe { self.as_mut() } {
-            Some(json) => json.is_type_false(),
-            None => false,
+    /// Get the value of a Json item of type `String` (`None` for any other type or if the value is
+    /// not valid UTF-8).
+    pub fn as_str(&self) -> Option<&str> {
+        if self.is_type_string() && !self.valuestring.is_null() {
+            unsafe { CStr::from_ptr(self.valuestring) }.to_str().ok()
+        } else {
+            None
         }
     }
+}
 
-    /// Check whether the Json object is of type `True`.
-    ///
-    /// Returns:
-    /// - `bool` - indicating whether or not the Json object is of type `True`.
-    fn is_type_true(&self) -> bool {
-        match unsafe { self.as_mut() } {
-            Some(json) => json.is_type_true(),
-            None => false,
+// collect the direct children of a Json item (in order) by walking its child chain
+fn child_items(parent: *mut Json) -> Vec<*mut Json> {
+    let mut items = Vec::new();
+    if parent.is_null() {
+        return items;
+    }
+
+    let mut current = unsafe { (*parent).child };
+    while !current.is_null() {
+        items.push(current);
+        current = unsafe { (*current).next };
+    }
+    items
+}
+
+// check whether `target` is `root` itself or nested anywhere inside it
+fn subtree_contains(root: *mut Json, target: *mut Json) -> bool {
+    let mut pending = vec![root];
+    while let Some(item) = pending.pop() {
+        if item == target {
+            return true;
         }
+        pending.extend(child_items(item));
     }
+    false
+}
 
-    /// Check whether the Json object is of type `Bool`.
-    ///
-    /// Returns:
-    /// - `bool` - indicating whether or not the Json object is of type `Bool`.
-    fn is_type_bool(&self) -> bool {
-        match unsafe { self.as_mut() } {
-            Some(json) => json.is_type_bool(),
-            None => false,
+// relink the children of a Json item so that they follow the order of `items`
+//
+// cJSON expects the first child's `prev` to point to the last child and the last child's `next`
+// to be null, so both ends of the chain are fixed up here.
+fn relink_child_items(parent: *mut Json, items: &[*mut Json]) {
+    let (first, last) = match (items.first(), items.last()) {
+        (Some(&first), Some(&last)) => (first, last),
+        _ => {
+            unsafe { (*parent).child = std::ptr::null_mut() };
+            return;
+        }
+    };
+
+    for (index, &item) in items.iter().enumerate() {
+        unsafe {
+            (*item).prev = if index == 0 { last } else { items[index - 1] };
+            (*item).next = items
+                .get(index + 1)
+                .copied()
+                .unwrap_or(std::ptr::null_mut());
         }
     }
+    unsafe { (*parent).child = first };
+}
 
-    /// Check whether the Json object is of type `Null`.
-    ///
-    /// Returns:
-    /// - `bool` - indicating whether or not the Json object is of type `Null`.
-    fn is_type_null(&self) -> bool {
-        match unsafe { self.as_mut() } {
-            Some(json) => json.is_type_null(),
-            None => false,
+// compare two doubles the same way cJSON does (relative epsilon)
+fn compare_double(a: f64, b: f64) -> bool {
+    let max_val = if a.abs() > b.abs() { a.abs() } else { b.abs() };
+    (a - b).abs() <= max_val * f64::EPSILON
+}
+
+// format a Number item exactly like cJSON's unformatted printer does
+fn format_number(item: &Json) -> String {
+    let d = item.valuedouble;
+    if d.is_nan() || d.is_infinite() {
+        return "null".to_string();
+    }
+    if d == item.valueint as f64 {
+        return item.valueint.to_string();
+    }
+
+    let print_with = |format: &[u8]| -> String {
+        let mut buffer = [0 as c_char; 26];
+        unsafe {
+            libc::snprintf(
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                format.as_ptr() as *const c_char,
+                d,
+            );
+            CStr::from_ptr(buffer.as_ptr())
+                .to_string_lossy()
+                .into_owned()
         }
+    };
+
+    let short = print_with(b"%1.15g\0");
+    match short.parse::<f64>() {
+        Ok(test) if compare_double(test, d) => short,
+        _ => print_with(b"%1.17g\0"),
     }
+}
 
-    /// Check whether the Json object is of type `Number`.
-    ///
-    /// Returns:
-    /// - `bool` - indicating whether or not the Json object is of type `Number`.
-    fn is_type_number(&self) -> bool {
-        match unsafe { self.as_mut() } {
-            Some(json) => json.is_type_number(),
-            None => false,
+// escape a string the way cJSON does for the content of a JSON string literal
+fn escape_into(value: &str, out: &mut String) {
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 32 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
         }
     }
+}
 
-    /// Check whether the Json object is of type `String`.
-    ///
-    /// Returns:
-    /// - `bool` - indicating whether or not the Json object is of type `String`.
-    fn is_type_string(&self) -> bool {
-        match unsafe { self.as_mut() } {
-            Some(json) => json.is_type_string(),
-            None => false,
+// write a string as a quoted JSON string literal, escaping it the same way cJSON does
+fn write_escaped_string(value: *const c_char, out: &mut String) {
+    out.push('"');
+    if !value.is_null() {
+        escape_into(&unsafe { CStr::from_ptr(value) }.to_string_lossy(), out);
+    }
+    out.push('"');
+}
+
+// write the canonical form of a Json item into `out`: no insignificant whitespace and object
+// members sorted by key (byte-wise), recursing through the whole tree
+fn write_canonical(item: *mut Json, out: &mut String) -> Result<(), JsonError> {
+    let json = match unsafe { item.as_ref() } {
+        Some(json) => json,
+        None => return Err(JsonError::NullPointer),
+    };
+
+    if json.is_type_null() {
+        out.push_str("null");
+    } else if json.is_type_false() {
+        out.push_str("false");
+    } else if json.is_type_true() {
+        out.push_str("true");
+    } else if json.is_type_number() {
+        out.push_str(&format_number(json));
+    } else if json.is_type_raw() {
+        if json.valuestring.is_null() {
+            return Err(JsonError::PrintError);
+        }
+        out.push_str(&unsafe { CStr::from_ptr(json.valuestring) }.to_string_lossy());
+    } else if json.is_type_string() {
+        if json.valuestring.is_null() {
+            return Err(JsonError::PrintError);
+        }
+        write_escaped_string(json.valuestring, out);
+    } else if json.is_type_array() {
+        out.push('[');
+        for (index, child) in child_items(item).into_iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            write_canonical(child, out)?;
+        }
+        out.push(']');
+    } else if json.is_type_object() {
+        let mut members = child_items(item);
+        members.sort_by(|a, b| {
+            let key = |member: &*mut Json| unsafe {
+                let string = (**member).string;
+                if string.is_null() {
+                    &[][..]
+                } else {
+                    CStr::from_ptr(string).to_bytes()
+                }
+            };
+            key(a).cmp(key(b))
+        });
+
+        out.push('{');
+        for (index, member) in members.into_iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            write_escaped_string(unsafe { (*member).string }, out);
+            out.push(':');
+            write_canonical(member, out)?;
         }
+        out.push('}');
+    } else {
+        return Err(JsonError::PrintError);
     }
+    Ok(())
+}
 
-    /// Check whether the Json object is of type `Array`.
-    ///
-    /// Returns:
-    /// - `bool` - indicating whether or not the Json object is of type `Array`.
-    fn is_type_array(&self) -> bool {
-        match unsafe { self.as_mut() } {
-            Some(json) => json.is_type_array(),
-            None => false,
+// write a Json item `depth` levels below the root into `out` with cJSON's formatted layout,
+// printing non-empty containers at `max_depth` or deeper as "..."
+fn write_truncated(
+    item: *mut Json,
+    depth: usize,
+    max_depth: usize,
+    out: &mut String,
+) -> Result<(), JsonError> {
+    if item.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+    if !item.is_type_array() && !item.is_type_object() {
+        return write_canonical(item, out);
+    }
+
+    let children = child_items(item);
+    if !children.is_empty() && depth >= max_depth {
+        out.push_str("\"...\"");
+        return Ok(());
+    }
+
+    if item.is_type_array() {
+        out.push('[');
+        for (index, child) in children.into_iter().enumerate() {
+            if index > 0 {
+                out.push_str(", ");
+            }
+            write_truncated(child, depth + 1, max_depth, out)?;
+        }
+        out.push(']');
+    } else {
+        out.push_str("{\n");
+        let count = children.len();
+        for (index, member) in children.into_iter().enumerate() {
+            out.push_str(&"\t".repeat(depth + 1));
+            write_escaped_string(unsafe { (*member).string }, out);
+            out.push_str(":\t");
+            write_truncated(member, depth + 1, max_depth, out)?;
+            if index + 1 < count {
+                out.push(',');
+            }
+            out.push('\n');
         }
+        out.push_str(&"\t".repeat(depth));
+        out.push('}');
     }
+    Ok(())
+}
 
-    /// Check whether the Json object is of type `Object`.
-    ///
-    /// Returns:
-    /// - `bool` - indicating whether or not the Json object is of type `Object`.
-    fn is_type_object(&self) -> bool {
-        match unsafe { self.as_mut() } {
-            Some(json) => json.is_type_object(),
-            None => false,
+// percent-encode bytes for a URL query string, keeping only unreserved characters (RFC 3986)
+fn percent_encode(bytes: &[u8], out: &mut String) {
+    for &b in bytes {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
         }
     }
+}
 
-    /// Check whether the Json object is of type `Raw`.
-    ///
-    /// Returns:
-    /// - `bool` - indicating whether or not the Json object is of type `Raw`.
-    fn is_type_raw(&self) -> bool {
-        match unsafe { self.as_mut() } {
-            Some(json) => json.is_type_raw(),
-            None => false,
+// decode a percent-encoded URL query string component, turning `+` into a space and keeping
+// malformed escapes as they are
+fn percent_decode(component: &str) -> Vec<u8> {
+    let bytes = component.as_bytes();
+    let hex = |index: usize| bytes.get(index).and_then(|&b| (b as char).to_digit(16));
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], hex(i + 1), hex(i + 2)) {
+            (b'%', Some(high), Some(low)) => {
+                decoded.push((high * 16 + low) as u8);
+                i += 3;
+                continue;
+            }
+            (b'+', _, _) => decoded.push(b' '),
+            (b, _, _) => decoded.push(b),
         }
+        i += 1;
     }
+    decoded
+}
 
-    /// Generate a string representation of the JSON object eg.
-    /// ```json
-    /// {
-    ///     "name": "Nemuel",
-    ///     "age": 20
-    /// }
-    /// ```
-    ///
-    /// Returns:
-    /// - `Ok(String)` - if the JSON object's string representation is successfully generated.
-    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
-    /// - `Err(JsonError::PrintError)` - if the string generation fails.
-    ///
-    /// Example:
-    /// ```rust
-    /// use cjson_rs::*;
-    ///
+// find the first member of a Json item of type Object whose key is exactly `key`
+fn object_member(object: *mut Json, key: &str) -> Option<*mut Json> {
+    child_items(object).into_iter().find(|&member| {
+        let string = unsafe { (*member).string };
+        !string.is_null() && unsafe { CStr::from_ptr(string) }.to_bytes() == key.as_bytes()
+    })
+}
+
+// split an RFC 6901 JSON Pointer into its unescaped reference tokens ("" refers to the whole
+// document)
+fn parse_json_pointer(pointer: &str) -> Result<Vec<String>, JsonError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return Err(JsonError::InvalidPointerError(format!(
+            "\"{}\" does not start with '/'",
+            pointer
+        )));
+    }
+
+    pointer[1..]
+        .split('/')
+        .map(|token| {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                if c != '~' {
+                    unescaped.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('0') => unescaped.push('~'),
+                    Some('1') => unescaped.push('/'),
+                    _ => {
+                        return Err(JsonError::InvalidPointerError(format!(
+                            "\"{}\" contains an invalid '~' escape",
+                            pointer
+                        )))
+                    }
+                }
+            }
+            Ok(unescaped)
+        })
+        .collect()
+}
+
+// join reference tokens back into an RFC 6901 JSON Pointer
+fn format_json_pointer(tokens: &[String]) -> String {
+    tokens
+        .iter()
+        .map(|token| format!("/{}", token.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+// parse a reference token as an array index (digits only, no leading zeros)
+fn parse_array_index(token: &str) -> Option<usize> {
+    let digits_only = !token.is_empty() && token.bytes().all(|b| b.is_ascii_digit());
+    if !digits_only || (token.len() > 1 && token.starts_with('0')) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+// walk the reference tokens from `root`, returning the item they refer to if it exists
+fn resolve_pointer(root: *mut Json, tokens: &[String]) -> Option<*mut Json> {
+    let mut current = root;
+    for token in tokens {
+        current = if current.is_type_array() {
+            child_items(current)
+                .get(parse_array_index(token)?)
+                .copied()?
+        } else if current.is_type_object() {
+            object_member(current, token)?
+        } else {
+            return None;
+        };
+    }
+    if current.is_null() {
+        None
+    } else {
+        Some(current)
+    }
+}
+
+// resolve the parent of the location referred to by `tokens`, returning it with the last token
+fn resolve_pointer_parent(
+    root: *mut Json,
+    tokens: &[String],
+) -> Result<(*mut Json, &String), JsonError> {
+    let (last, parent_tokens) = match tokens.split_last() {
+        Some(split) => split,
+        None => {
+            return Err(JsonError::InvalidPointerError(
+                "the whole document has no parent".to_string(),
+            ))
+        }
+    };
+    match resolve_pointer(root, parent_tokens) {
+        Some(parent) if parent.is_type_array() || parent.is_type_object() => Ok((parent, last)),
+        _ => Err(JsonError::InvalidPointerError(format!(
+            "\"{}\" does not refer to an array or object",
+            format_json_pointer(parent_tokens)
+        ))),
+    }
+}
+
+// add `value` at the location referred to by `tokens` (RFC 6902 "add" semantics): insert into
+// arrays ("-" appends), add or replace object members, or replace the whole document
+//
+// Ownership of `value` is always taken: it is deleted if it cannot be added.
+fn add_at_pointer(
+    root: &mut *mut Json,
+    tokens: &[String],
+    value: *mut Json,
+) -> Result<(), JsonError> {
+    if tokens.is_empty() {
+        unsafe { cJSON_Delete(*root as *mut cJSON) };
+        *root = value;
+        return Ok(());
+    }
+
+    let added = resolve_pointer_parent(*root, tokens).and_then(|(parent, last)| {
+        if parent.is_type_array() {
+            let size = child_items(parent).len();
+            let index = match (last.as_str(), parse_array_index(last)) {
+                ("-", _) => size,
+                (_, Some(index)) if index <= size => index,
+                _ => {
+                    return Err(JsonError::InvalidPointerError(format!(
+                        "\"{}\" is not a valid array position",
+                        format_json_pointer(tokens)
+                    )))
+                }
+            };
+            let inserted = unsafe {
+                if index == size {
+                    cJSON_AddItemToArray(parent as *mut cJSON, value as *mut cJSON)
+                } else {
+                    cJSON_InsertItemInArray(parent as *mut cJSON, index as i32, value as *mut cJSON)
+                }
+            };
+            Ok(inserted == 1)
+        } else {
+            match CString::new(last.as_str()) {
+                Ok(c_str) => Ok(unsafe {
+                    if object_member(parent, last).is_some() {
+                        cJSON_ReplaceItemInObjectCaseSensitive(
+                            parent as *mut cJSON,
+                            c_str.as_ptr(),
+                            value as *mut cJSON,
+                        ) == 1
+                    } else {
+                        cJSON_AddItemToObject(
+                            parent as *mut cJSON,
+                            c_str.as_ptr(),
+                            value as *mut cJSON,
+                        ) == 1
+                    }
+                }),
+                Err(err) => Err(JsonError::CStringError(err)),
+            }
+        }
+    });
+
+    match added {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            unsafe { cJSON_Delete(value as *mut cJSON) };
+            Err(JsonError::InvalidPointerError(format!(
+                "failed to add an item at \"{}\"",
+                format_json_pointer(tokens)
+            )))
+        }
+        Err(err) => {
+            unsafe { cJSON_Delete(value as *mut cJSON) };
+            Err(err)
+        }
+    }
+}
+
+// replace the existing item referred to by `tokens` with `value`, deleting the previous item
+//
+// Ownership of `value` is always taken: it is deleted if the replacement fails.
+fn replace_at_pointer(
+    root: &mut *mut Json,
+    tokens: &[String],
+    value: *mut Json,
+) -> Result<(), JsonError> {
+    let target = match resolve_pointer(*root, tokens) {
+        Some(target) => target,
+        None => {
+            unsafe { cJSON_Delete(value as *mut cJSON) };
+            return Err(JsonError::InvalidPointerError(format!(
+                "\"{}\" does not exist",
+                format_json_pointer(tokens)
+            )));
+        }
+    };
+
+    // array items are swapped in place, while the root and object members (which need their key
+    // set) go through the "add" path, which already replaces existing items
+    match resolve_pointer_parent(*root, tokens) {
+        Ok((parent, _)) if parent.is_type_array() => {
+            unsafe {
+                cJSON_ReplaceItemViaPointer(
+                    parent as *mut cJSON,
+                    target as *mut cJSON,
+                    value as *mut cJSON,
+                )
+            };
+            Ok(())
+        }
+        _ => add_at_pointer(root, tokens, value),
+    }
+}
+
+// detach the existing item referred to by `tokens` and hand it over to the caller
+fn detach_at_pointer(root: *mut Json, tokens: &[String]) -> Result<*mut Json, JsonError> {
+    let (parent, _) = resolve_pointer_parent(root, tokens)?;
+    match resolve_pointer(root, tokens) {
+        Some(target) => Ok(unsafe {
+            cJSON_DetachItemViaPointer(parent as *mut cJSON, target as *mut cJSON) as *mut Json
+        }),
+        None => Err(JsonError::InvalidPointerError(format!(
+            "\"{}\" does not exist",
+            format_json_pointer(tokens)
+        ))),
+    }
+}
+
+// read a string member of a JSON Patch operation
+fn patch_operation_string(operation: *mut Json, key: &str) -> Result<String, JsonError> {
+    match object_member(operation, key) {
+        Some(member) if member.is_type_string() => Ok(unsafe {
+            CStr::from_ptr((*member).valuestring)
+                .to_string_lossy()
+                .into_owned()
+        }),
+        _ => Err(JsonError::InvalidPatchError(format!(
+            "operation is missing a string \"{}\" member",
+            key
+        ))),
+    }
+}
+
+// apply a single JSON Patch operation to the document owned by `root`
+fn apply_patch_operation(root: &mut *mut Json, operation: *mut Json) -> Result<(), JsonError> {
+    if !operation.is_type_object() {
+        return Err(JsonError::InvalidPatchError(
+            "operation is not an object".to_string(),
+        ));
+    }
+    let op = patch_operation_string(operation, "op")?;
+    let path = parse_json_pointer(&patch_operation_string(operation, "path")?)?;
+
+    let value = || match object_member(operation, "value") {
+        Some(value) => Ok(value),
+        None => Err(JsonError::InvalidPatchError(format!(
+            "\"{}\" operation is missing a \"value\" member",
+            op
+        ))),
+    };
+    let duplicate = |item: *mut Json| {
+        let copy = unsafe { cJSON_Duplicate(item as *const cJSON, 1) as *mut Json };
+        if copy.is_null() {
+            Err(JsonError::NullPointer)
+        } else {
+            Ok(copy)
+        }
+    };
+
+    match op.as_str() {
+        "add" => add_at_pointer(root, &path, duplicate(value()?)?),
+        "remove" => {
+            let removed = detach_at_pointer(*root, &path)?;
+            unsafe { cJSON_Delete(removed as *mut cJSON) };
+            Ok(())
+        }
+        "replace" => replace_at_pointer(root, &path, duplicate(value()?)?),
+        "move" => {
+            let from = parse_json_pointer(&patch_operation_string(operation, "from")?)?;
+            if from == path {
+                return resolve_pointer(*root, &from).map(|_| ()).ok_or_else(|| {
+                    JsonError::InvalidPointerError(format!(
+                        "\"{}\" does not exist",
+                        format_json_pointer(&from)
+                    ))
+                });
+            }
+            if path.starts_with(&from) {
+                return Err(JsonError::InvalidPatchError(format!(
+                    "cannot move \"{}\" into one of its own children",
+                    format_json_pointer(&from)
+                )));
+            }
+            let moved = detach_at_pointer(*root, &from)?;
+            add_at_pointer(root, &path, moved)
+        }
+        "copy" => {
+            let from = parse_json_pointer(&patch_operation_string(operation, "from")?)?;
+            match resolve_pointer(*root, &from) {
+                Some(source) => add_at_pointer(root, &path, duplicate(source)?),
+                None => Err(JsonError::InvalidPointerError(format!(
+                    "\"{}\" does not exist",
+                    format_json_pointer(&from)
+                ))),
+            }
+        }
+        "test" => {
+            let expected = value()?;
+            match resolve_pointer(*root, &path) {
+                Some(actual)
+                    if unsafe {
+                        cJSON_Compare(actual as *const cJSON, expected as *const cJSON, 1) == 1
+                    } =>
+                {
+                    Ok(())
+                }
+                _ => Err(JsonError::PatchTestFailed(format_json_pointer(&path))),
+            }
+        }
+        other => Err(JsonError::InvalidPatchError(format!(
+            "unknown operation \"{}\"",
+            other
+        ))),
+    }
+}
+
+// append a JSON Patch operation to `patch`, attaching a recursive copy of `value` if given
+fn push_patch_operation(
+    patch: *mut Json,
+    op: &str,
+    tokens: &[String],
+    value: Option<*mut Json>,
+) -> Result<(), JsonError> {
+    let operation = unsafe { cJSON_CreateObject() };
+    if operation.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+    unsafe { cJSON_AddItemToArray(patch as *mut cJSON, operation) };
+
+    let fields = [
+        ("op", op.to_string()),
+        ("path", format_json_pointer(tokens)),
+    ];
+    for (key, field) in fields {
+        let added = match (CString::new(key), CString::new(field)) {
+            (Ok(c_key), Ok(c_field)) => unsafe {
+                cJSON_AddStringToObject(operation, c_key.as_ptr(), c_field.as_ptr())
+            },
+            (Err(err), _) | (_, Err(err)) => return Err(JsonError::CStringError(err)),
+        };
+        if added.is_null() {
+            return Err(JsonError::NullPointer);
+        }
+    }
+
+    if let Some(value) = value {
+        let c_key = match CString::new("value") {
+            Ok(c_key) => c_key,
+            Err(err) => return Err(JsonError::CStringError(err)),
+        };
+        let copy = unsafe { cJSON_Duplicate(value as *const cJSON, 1) };
+        if copy.is_null() {
+            return Err(JsonError::NullPointer);
+        }
+        unsafe { cJSON_AddItemToObject(operation, c_key.as_ptr(), copy) };
+    }
+    Ok(())
+}
+
+// append the operations turning `from` into `to` (both located at `tokens`) to `patch`
+fn diff_items(
+    from: *mut Json,
+    to: *mut Json,
+    tokens: &mut Vec<String>,
+    patch: *mut Json,
+) -> Result<(), JsonError> {
+    if unsafe { cJSON_Compare(from as *const cJSON, to as *const cJSON, 1) } == 1 {
+        return Ok(());
+    }
+
+    if from.is_type_object() && to.is_type_object() {
+        let key = |member: *mut Json| match unsafe { (*member).string.as_ref() } {
+            Some(string) => unsafe { CStr::from_ptr(string) }
+                .to_string_lossy()
+                .into_owned(),
+            None => String::new(),
+        };
+        for member in child_items(from) {
+            tokens.push(key(member));
+            match object_member(to, &key(member)) {
+                Some(target) => diff_items(member, target, tokens, patch)?,
+                None => push_patch_operation(patch, "remove", tokens, None)?,
+            }
+            tokens.pop();
+        }
+        for member in child_items(to) {
+            if object_member(from, &key(member)).is_none() {
+                tokens.push(key(member));
+                push_patch_operation(patch, "add", tokens, Some(member))?;
+                tokens.pop();
+            }
+        }
+    } else if from.is_type_array() && to.is_type_array() {
+        let (from_items, to_items) = (child_items(from), child_items(to));
+        for (index, (&old, &new)) in from_items.iter().zip(to_items.iter()).enumerate() {
+            tokens.push(index.to_string());
+            diff_items(old, new, tokens, patch)?;
+            tokens.pop();
+        }
+        // remove surplus items from the back so that the remaining indices stay valid
+        for index in (to_items.len()..from_items.len()).rev() {
+            tokens.push(index.to_string());
+            push_patch_operation(patch, "remove", tokens, None)?;
+            tokens.pop();
+        }
+        for &item in to_items.iter().skip(from_items.len()) {
+            tokens.push("-".to_string());
+            push_patch_operation(patch, "add", tokens, Some(item))?;
+            tokens.pop();
+        }
+    } else {
+        push_patch_operation(patch, "replace", tokens, Some(to))?;
+    }
+    Ok(())
+}
+
+// compare 2 Json items like cJSON_Compare (case-sensitive), except that numbers are compared with
+// an absolute tolerance
+fn compare_items_approx(a: *mut Json, b: *mut Json, epsilon: f64) -> bool {
+    let (a_json, b_json) = match unsafe { (a.as_ref(), b.as_ref()) } {
+        (Some(a_json), Some(b_json)) => (a_json, b_json),
+        _ => return false,
+    };
+    if a_json.type_ & 0xFF != b_json.type_ & 0xFF {
+        return false;
+    }
+
+    if a_json.is_type_number() {
+        (a_json.valuedouble - b_json.valuedouble).abs() <= epsilon
+    } else if a_json.is_type_string() || a_json.is_type_raw() {
+        !a_json.valuestring.is_null()
+            && !b_json.valuestring.is_null()
+            && unsafe { CStr::from_ptr(a_json.valuestring) == CStr::from_ptr(b_json.valuestring) }
+    } else if a_json.is_type_array() {
+        let (a_items, b_items) = (child_items(a), child_items(b));
+        a_items.len() == b_items.len()
+            && a_items
+                .iter()
+                .zip(b_items.iter())
+                .all(|(&a_item, &b_item)| compare_items_approx(a_item, b_item, epsilon))
+    } else if a_json.is_type_object() {
+        let matches = |from: *mut Json, to: *mut Json| {
+            child_items(from).into_iter().all(|member| {
+                let key = unsafe { (*member).string };
+                !key.is_null()
+                    && match object_member(to, &unsafe { CStr::from_ptr(key) }.to_string_lossy()) {
+                        Some(other) => compare_items_approx(member, other, epsilon),
+                        None => false,
+                    }
+            })
+        };
+        matches(a, b) && matches(b, a)
+    } else {
+        a_json.is_type_null() || a_json.is_type_false() || a_json.is_type_true()
+    }
+}
+
+// write a key for a Json item that does not depend on the order of array items (nor on the order
+// of object members), used to line up array items before comparing them
+fn write_unordered_key(item: *mut Json, out: &mut String) -> Result<(), JsonError> {
+    if item.is_type_array() {
+        let mut keys = Vec::new();
+        for child in child_items(item) {
+            let mut key = String::new();
+            write_unordered_key(child, &mut key)?;
+            keys.push(key);
+        }
+        keys.sort();
+        out.push('[');
+        out.push_str(&keys.join(","));
+        out.push(']');
+    } else if item.is_type_object() {
+        let mut members = child_items(item);
+        members.sort_by_key(|&member| {
+            unsafe { (*member).string.as_ref() }
+                .map(|key| unsafe { CStr::from_ptr(key) }.to_bytes())
+        });
+        out.push('{');
+        for (index, member) in members.into_iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            write_escaped_string(unsafe { (*member).string }, out);
+            out.push(':');
+            write_unordered_key(member, out)?;
+        }
+        out.push('}');
+    } else {
+        write_canonical(item, out)?;
+    }
+    Ok(())
+}
+
+// compare 2 Json items like cJSON_Compare (case-sensitive), except that arrays are compared as
+// multisets
+fn compare_items_unordered(a: *mut Json, b: *mut Json) -> bool {
+    let (a_json, b_json) = match unsafe { (a.as_ref(), b.as_ref()) } {
+        (Some(a_json), Some(b_json)) => (a_json, b_json),
+        _ => return false,
+    };
+    if a_json.type_ & 0xFF != b_json.type_ & 0xFF {
+        return false;
+    }
+
+    if a_json.is_type_array() {
+        let sorted = |array: *mut Json| -> Option<Vec<*mut Json>> {
+            let mut keyed = Vec::new();
+            for child in child_items(array) {
+                let mut key = String::new();
+                write_unordered_key(child, &mut key).ok()?;
+                keyed.push((key, child));
+            }
+            keyed.sort_by(|x, y| x.0.cmp(&y.0));
+            Some(keyed.into_iter().map(|(_, child)| child).collect())
+        };
+        match (sorted(a), sorted(b)) {
+            (Some(a_items), Some(b_items)) => {
+                a_items.len() == b_items.len()
+                    && a_items
+                        .iter()
+                        .zip(b_items.iter())
+                        .all(|(&a_item, &b_item)| compare_items_unordered(a_item, b_item))
+            }
+            _ => false,
+        }
+    } else if a_json.is_type_object() {
+        let matches = |from: *mut Json, to: *mut Json| {
+            child_items(from).into_iter().all(|member| {
+                let key = unsafe { (*member).string };
+                !key.is_null()
+                    && match object_member(to, &unsafe { CStr::from_ptr(key) }.to_string_lossy()) {
+                        Some(other) => compare_items_unordered(member, other),
+                        None => false,
+                    }
+            })
+        };
+        matches(a, b) && matches(b, a)
+    } else {
+        unsafe { cJSON_Compare(a as *const cJSON, b as *const cJSON, 1) == 1 }
+    }
+}
+
+/// Json items compare equal when `cJSON_Compare` (case-sensitive) considers them equivalent in
+/// structure and value, so object member order does not matter.
+///
+/// NOTE: cJSON never considers items of type Invalid equal, so an item containing one is only
+/// equal to itself (the very same Json item).
+impl PartialEq for Json {
+    fn eq(&self, other: &Json) -> bool {
+        if std::ptr::eq(self, other) {
+            return true;
+        }
+        unsafe {
+            cJSON_Compare(
+                self as *const Json as *const cJSON,
+                other as *const Json as *const cJSON,
+                1,
+            ) == 1
+        }
+    }
+}
+
+impl Eq for Json {}
+
+/// Json items are hashed consistently with `PartialEq`: object members are hashed in key order, so
+/// items that compare equal hash equally regardless of member insertion order.
+///
+/// NOTE: `cJSON_Compare` considers numbers equal when they are within a relative `f64::EPSILON` of
+/// each other, so numbers only contribute their type to the hash. Items that differ only in their
+/// numbers therefore collide (and are told apart by `PartialEq`).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+/// use std::collections::HashSet;
+///
+/// fn main() {
+///     let mut first = cjson_create_object();
+///     cjson_add_number_to_object(first, "id", 1.0).unwrap();
+///     cjson_add_string_to_object(first, "name", "Nemuel").unwrap();
+///
+///     let mut second = cjson_create_object();
+///     cjson_add_string_to_object(second, "name", "Nemuel").unwrap();
+///     cjson_add_number_to_object(second, "id", 1.0).unwrap();
+///
+///     let mut set = HashSet::new();
+///     set.insert(unsafe { &*first });
+///     set.insert(unsafe { &*second });
+///     assert_eq!(set.len(), 1);
+///
+///     let mut sum = cjson_create_number(0.1 + 0.2);
+///     let mut third = cjson_create_number(0.3);
+///     assert!(unsafe { *sum == *third });
+///     set.insert(unsafe { &*sum });
+///     set.insert(unsafe { &*third });
+///     assert_eq!(set.len(), 2);
+///
+///     cjson_delete(&mut first);
+///     cjson_delete(&mut second);
+///     cjson_delete(&mut sum);
+///     cjson_delete(&mut third);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+impl std::hash::Hash for Json {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        hash_item(self as *const Json as *mut Json, state);
+    }
+}
+
+// feed `item` to `state` so that items cJSON_Compare considers equal hash equally
+fn hash_item<H: std::hash::Hasher>(item: *mut Json, state: &mut H) {
+    use std::hash::Hash;
+
+    let json = match unsafe { item.as_ref() } {
+        Some(json) => json,
+        None => return,
+    };
+    (json.type_ & 0xFF).hash(state);
+
+    if json.is_type_string() || json.is_type_raw() {
+        if !json.valuestring.is_null() {
+            unsafe { CStr::from_ptr(json.valuestring) }.hash(state);
+        }
+    } else if json.is_type_array() {
+        let items = child_items(item);
+        items.len().hash(state);
+        for child in items {
+            hash_item(child, state);
+        }
+    } else if json.is_type_object() {
+        // cJSON_Compare looks members up by key, so only the first member with a given key counts
+        // (the sort is stable, so dedup keeps that first member)
+        let mut members: Vec<(&CStr, *mut Json)> = child_items(item)
+            .into_iter()
+            .filter(|&member| unsafe { !(*member).string.is_null() })
+            .map(|member| (unsafe { CStr::from_ptr((*member).string) }, member))
+            .collect();
+        members.sort_by(|a, b| a.0.cmp(b.0));
+        members.dedup_by(|later, earlier| later.0 == earlier.0);
+        members.len().hash(state);
+        for (key, member) in members {
+            key.hash(state);
+            hash_item(member, state);
+        }
+    }
+}
+
+/// Convert a Json item of type `Number` into an `f64`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let number = cjson_create_number(2.5);
+///     let value: f64 = unsafe { &*number }.try_into().unwrap();
+///     assert_eq!(value, 2.5);
+///
+///     let string = cjson_create_string("2.5".to_string()).unwrap();
+///     let result: Result<f64, JsonError> = unsafe { &*string }.try_into();
+///     assert!(matches!(result, Err(JsonError::InvalidTypeError(_))));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+impl TryFrom<&Json> for f64 {
+    type Error = JsonError;
+
+    fn try_from(json: &Json) -> Result<f64, JsonError> {
+        if !json.is_type_number() {
+            return Err(JsonError::InvalidTypeError(
+                "cannot convert a non-number Json item to f64".to_string(),
+            ));
+        }
+        Ok(json.valuedouble)
+    }
+}
+
+/// Convert a Json item of type `Number` into an `i64`.
+///
+/// The conversion fails with `JsonError::NumberOutOfRange` if the number has a fractional part or
+/// does not fit in an `i64`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let number = cjson_parse_json("4294967296".to_string()).unwrap();
+///     let value: i64 = unsafe { &*number }.try_into().unwrap();
+///     assert_eq!(value, 4294967296);
+///
+///     let fraction = cjson_create_number(1.5);
+///     let result: Result<i64, JsonError> = unsafe { &*fraction }.try_into();
+///     assert!(matches!(result, Err(JsonError::NumberOutOfRange)));
+///
+///     let huge = cjson_create_number(1e20);
+///     let result: Result<i64, JsonError> = unsafe { &*huge }.try_into();
+///     assert!(matches!(result, Err(JsonError::NumberOutOfRange)));
+///
+///     let boolean = cjson_create_true();
+///     let result: Result<i64, JsonError> = unsafe { &*boolean }.try_into();
+///     assert!(matches!(result, Err(JsonError::InvalidTypeError(_))));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+impl TryFrom<&Json> for i64 {
+    type Error = JsonError;
+
+    fn try_from(json: &Json) -> Result<i64, JsonError> {
+        let number = f64::try_from(json)?;
+        // 2^63 is exactly representable, i64::MAX is not
+        if number.fract() != 0.0
+            || !(-9223372036854775808.0..9223372036854775808.0).contains(&number)
+        {
+            return Err(JsonError::NumberOutOfRange);
+        }
+        Ok(number as i64)
+    }
+}
+
+/// Convert a Json item of type `Bool` (`True` or `False`) into a `bool`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let boolean = cjson_create_false();
+///     let value: bool = unsafe { &*boolean }.try_into().unwrap();
+///     assert_eq!(value, false);
+///
+///     let null = cjson_create_null();
+///     let result: Result<bool, JsonError> = unsafe { &*null }.try_into();
+///     assert!(matches!(result, Err(JsonError::InvalidTypeError(_))));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+impl TryFrom<&Json> for bool {
+    type Error = JsonError;
+
+    fn try_from(json: &Json) -> Result<bool, JsonError> {
+        if !json.is_type_bool() {
+            return Err(JsonError::InvalidTypeError(
+                "cannot convert a non-bool Json item to bool".to_string(),
+            ));
+        }
+        Ok(json.is_type_true())
+    }
+}
+
+/// Convert a Json item of type `String` into a `String`.
+///
+/// The conversion fails with `JsonError::Utf8Error` if the string is not valid UTF-8.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let string = cjson_create_string("Nemuel".to_string()).unwrap();
+///     let value: String = unsafe { &*string }.try_into().unwrap();
+///     assert_eq!(value, "Nemuel");
+///
+///     let invalid = cjson_create_string("?".to_string()).unwrap();
+///     unsafe { *(*invalid).valuestring = 0xff_u8 as i8 }; // not valid UTF-8 on its own
+///     let result: Result<String, JsonError> = unsafe { &*invalid }.try_into();
+///     assert!(matches!(result, Err(JsonError::Utf8Error(_))));
+///
+///     let number = cjson_create_number(1.0);
+///     let result: Result<String, JsonError> = unsafe { &*number }.try_into();
+///     assert!(matches!(result, Err(JsonError::InvalidTypeError(_))));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+impl TryFrom<&Json> for String {
+    type Error = JsonError;
+
+    fn try_from(json: &Json) -> Result<String, JsonError> {
+        if !json.is_type_string() || json.valuestring.is_null() {
+            return Err(JsonError::InvalidTypeError(
+                "cannot convert a non-string Json item to String".to_string(),
+            ));
+        }
+        match unsafe { CStr::from_ptr(json.valuestring) }.to_str() {
+            Ok(string) => Ok(string.to_string()),
+            Err(err) => Err(JsonError::Utf8Error(err)),
+        }
+    }
+}
+
+/// Handle for moving a Json tree to another thread.
+///
+/// Raw `*mut Json` pointers are not `Send`. This wrapper is, on the condition that it is the
+/// single owner of the tree: no other pointer into the tree (to the root or any nested item) may
+/// be used while the handle exists or after it has been sent, until `into_inner` gives the
+/// pointer back on the receiving side. cJSON itself keeps no per-tree global state, so moving an
+/// exclusively owned tree between threads is sound.
+///
+/// A handle dropped without calling `into_inner` deletes the tree.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+/// use std::thread;
+///
+/// fn main() {
+///     let json = cjson_parse_json(r#"{"name":"Nemuel"}"#.to_string()).unwrap();
+///     let handle = SendJson::new(json).unwrap();
+///
+///     let printed = thread::spawn(move || {
+///         let mut json = handle.into_inner();
+///         let printed = json.print_unformatted().unwrap();
+///         println!("{}", printed);
+///         cjson_delete(&mut json);
+///         printed
+///     })
+///     .join()
+///     .unwrap();
+///     assert_eq!(printed, r#"{"name":"Nemuel"}"#);
+///
+///     // an unused handle deletes the tree when dropped
+///     drop(SendJson::new(cjson_create_array()).unwrap());
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub struct SendJson(OwnedJson);
+
+unsafe impl Send for SendJson {}
+
+impl SendJson {
+    /// Wrap the root of a Json tree that the caller owns exclusively.
+    ///
+    /// Args:
+    /// - `item: *mut Json` - Mutable pointer to the root of the tree to be moved.
+    ///
+    /// Returns:
+    /// - `Ok(SendJson)` - the handle owning the tree.
+    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
+    pub fn new(item: *mut Json) -> Result<SendJson, JsonError> {
+        OwnedJson::from_raw(item).map(SendJson)
+    }
+
+    /// Give back the pointer to the root of the tree (to be deleted by the caller).
+    pub fn into_inner(self) -> *mut Json {
+        self.0.into_raw()
+    }
+}
+
+/// Owner of the root of a Json tree, deleting the whole tree when dropped.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = OwnedJson::from_raw(cjson_parse_json(r#"{"name":"Nemuel"}"#.to_string()).unwrap()).unwrap();
+///     assert_eq!(json.as_ptr().is_type_object(), true);
+///     assert_eq!(json.as_ptr().print_unformatted().unwrap(), r#"{"name":"Nemuel"}"#);
+///
+///     let mut raw = json.into_raw(); // the tree is no longer deleted automatically
+///     cjson_delete(&mut raw);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub struct OwnedJson(NonNull<Json>);
+
+impl OwnedJson {
+    /// Take ownership of the root of a Json tree (which must not belong to another item).
+    ///
+    /// Args:
+    /// - `item: *mut Json` - Mutable pointer to the root of the tree.
+    ///
+    /// Returns:
+    /// - `Ok(OwnedJson)` - the owner of the tree.
+    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
+    pub fn from_raw(item: *mut Json) -> Result<OwnedJson, JsonError> {
+        match NonNull::new(item) {
+            Some(item) => Ok(OwnedJson(item)),
+            None => Err(JsonError::NullPointer),
+        }
+    }
+
+    /// Get a mutable pointer to the root of the tree (still owned by the OwnedJson).
+    pub fn as_ptr(&self) -> *mut Json {
+        self.0.as_ptr()
+    }
+
+    /// Release ownership of the tree without deleting it (to be deleted by the caller).
+    pub fn into_raw(self) -> *mut Json {
+        let item = self.0.as_ptr();
+        std::mem::forget(self);
+        item
+    }
+
+    // take ownership of a freshly created item, panicking if cJSON failed to allocate it
+    fn from_created(item: *mut cJSON) -> OwnedJson {
+        OwnedJson::from_raw(item as *mut Json).expect("cJSON failed to allocate the Json item")
+    }
+}
+
+impl std::ops::Deref for OwnedJson {
+    type Target = Json;
+
+    fn deref(&self) -> &Json {
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl Drop for OwnedJson {
+    fn drop(&mut self) {
+        unsafe { cJSON_Delete(self.0.as_ptr() as *mut cJSON) };
+    }
+}
+
+/// Create a Json item of type `Number`.
+///
+/// Panics if cJSON fails to allocate the item.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = OwnedJson::from(3.14);
+///     assert_eq!(json.as_ptr().is_type_number(), true);
+///     assert_eq!(json.valuedouble, 3.14);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+impl From<f64> for OwnedJson {
+    fn from(number: f64) -> OwnedJson {
+        OwnedJson::from_created(unsafe { cJSON_CreateNumber(number) })
+    }
+}
+
+/// Create a Json item of type `Number`.
+///
+/// cJSON stores numbers as `f64`, so integers beyond 2^53 in magnitude lose precision.
+/// Panics if cJSON fails to allocate the item.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = OwnedJson::from(-42_i64);
+///     assert_eq!(json.as_ptr().is_type_number(), true);
+///     assert_eq!(json.valueint, -42);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+impl From<i64> for OwnedJson {
+    fn from(number: i64) -> OwnedJson {
+        OwnedJson::from_created(unsafe { cJSON_CreateNumber(number as f64) })
+    }
+}
+
+/// Create a Json item of type `True` or `False`.
+///
+/// Panics if cJSON fails to allocate the item.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     assert_eq!(OwnedJson::from(true).as_ptr().is_type_true(), true);
+///     assert_eq!(OwnedJson::from(false).as_ptr().is_type_false(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+impl From<bool> for OwnedJson {
+    fn from(boolean: bool) -> OwnedJson {
+        OwnedJson::from_created(unsafe { cJSON_CreateBool(if boolean { 1 } else { 0 }) })
+    }
+}
+
+/// Create a Json item of type `String`.
+///
+/// Panics if the string contains a null byte (use `cjson_create_string` to get a `Result`
+/// instead) or if cJSON fails to allocate the item.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = OwnedJson::from("Nemuel");
+///     assert_eq!(json.as_ptr().is_type_string(), true);
+///     assert_eq!(cjson_get_string_value(json.as_ptr()).unwrap(), "Nemuel");
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+impl From<&str> for OwnedJson {
+    fn from(string: &str) -> OwnedJson {
+        let c_str = CString::new(string).expect("Json strings cannot contain null bytes");
+        OwnedJson::from_created(unsafe { cJSON_CreateString(c_str.as_ptr()) })
+    }
+}
+
+/// Fluent population of an owned Json item of type `Object`.
+///
+/// The `with_*` methods add a member and hand the OwnedJson back, so an object can be built in a
+/// single expression. Like `OwnedJson::from(&str)`, they panic instead of returning errors: use
+/// `ObjectBuilder` when keys or values may contain null bytes.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let built = OwnedJson::object()
+///         .with_number("x", 1.0)
+///         .with_string("y", "z")
+///         .with_bool("ok", true);
+///
+///     let parsed = OwnedJson::from_raw(cjson_parse_json(r#"{"x":1,"y":"z","ok":true}"#.to_string()).unwrap()).unwrap();
+///     assert!(*built == *parsed);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+impl OwnedJson {
+    /// Create an empty Json item of type `Object`.
+    ///
+    /// Panics if cJSON fails to allocate the item.
+    pub fn object() -> OwnedJson {
+        OwnedJson::from_created(unsafe { cJSON_CreateObject() })
+    }
+
+    /// Add a member of type `String` and return the object.
+    ///
+    /// Panics if the root is not of type `Object`, if `key` or `value` contains a null byte or if
+    /// cJSON fails to allocate the member.
+    pub fn with_string(self, key: &str, value: &str) -> OwnedJson {
+        self.with_item(key, OwnedJson::from(value))
+    }
+
+    /// Add a member of type `Number` and return the object.
+    ///
+    /// Panics if the root is not of type `Object`, if `key` contains a null byte or if cJSON fails
+    /// to allocate the member.
+    pub fn with_number(self, key: &str, value: f64) -> OwnedJson {
+        self.with_item(key, OwnedJson::from(value))
+    }
+
+    /// Add a member of type `True` or `False` and return the object.
+    ///
+    /// Panics if the root is not of type `Object`, if `key` contains a null byte or if cJSON fails
+    /// to allocate the member.
+    pub fn with_bool(self, key: &str, value: bool) -> OwnedJson {
+        self.with_item(key, OwnedJson::from(value))
+    }
+
+    // add an owned item as a member of the root object, panicking on failure
+    fn with_item(self, key: &str, item: OwnedJson) -> OwnedJson {
+        assert!(
+            self.as_ptr().is_type_object(),
+            "cannot add a member to a non-object Json item"
+        );
+        let c_key = CString::new(key).expect("Json keys cannot contain null bytes");
+        let added = unsafe {
+            cJSON_AddItemToObject(
+                self.as_ptr() as *mut cJSON,
+                c_key.as_ptr(),
+                item.as_ptr() as *mut cJSON,
+            )
+        };
+        assert!(added == 1, "cJSON failed to add the member");
+        item.into_raw();
+        self
+    }
+}
+
+impl OwnedJson {
+    /// Borrow the member with the specified key (case-sensitive) if the root is of type `Object`.
+    ///
+    /// The returned reference cannot outlive the OwnedJson. It is only valid as long as the member
+    /// stays in the tree, so it must not be used after detaching or deleting the member through a
+    /// raw pointer.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     let owner = OwnedJson::from_raw(cjson_parse_json(r#"{"user":{"name":"Nemuel"}}"#.to_string()).unwrap()).unwrap();
+    ///     let name = owner.get("user").and_then(|user| user.get("name")).unwrap();
+    ///     assert_eq!(cjson_get_string_value(name.as_ptr()).unwrap(), "Nemuel");
+    ///     assert_eq!(owner.get("missing").is_none(), true);
+    ///     println!("Test passed"); // output: Test passed
+    /// }
+    /// ```
+    ///
+    /// A child reference cannot outlive its owner:
+    /// ```rust,compile_fail
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     let child;
+    ///     {
+    ///         let owner = OwnedJson::from_raw(cjson_parse_json(r#"{"a":1}"#.to_string()).unwrap()).unwrap();
+    ///         child = owner.get("a").unwrap();
+    ///     }
+    ///     println!("{}", child.valuedouble);
+    /// }
+    /// ```
+    pub fn get(&self, key: &str) -> Option<JsonRef<'_>> {
+        JsonRef::new(self.as_ptr()).get(key)
+    }
+
+    /// Borrow the item at the specified index if the root is of type `Array`.
+    ///
+    /// The same lifetime rules as for `get` apply.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     let owner = OwnedJson::from_raw(cjson_parse_json("[10,20]".to_string()).unwrap()).unwrap();
+    ///     assert_eq!(owner.get_index(1).unwrap().valuedouble, 20.0);
+    ///     assert_eq!(owner.get_index(2).is_none(), true);
+    ///     println!("Test passed"); // output: Test passed
+    /// }
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<JsonRef<'_>> {
+        JsonRef::new(self.as_ptr()).get_index(index)
+    }
+}
+
+/// Reference to an item inside a tree owned by an OwnedJson, borrowing the owner so that it
+/// cannot outlive the tree.
+///
+/// NOTE: The borrow checker only tracks the owner. Detaching or deleting the referenced item
+/// through a raw pointer still invalidates the reference.
+#[derive(Clone, Copy)]
+pub struct JsonRef<'a> {
+    item: NonNull<Json>,
+    owner: std::marker::PhantomData<&'a Json>,
+}
+
+impl<'a> JsonRef<'a> {
+    // wrap an item of a tree that is borrowed for 'a (the pointer must not be null)
+    fn new(item: *mut Json) -> JsonRef<'a> {
+        JsonRef {
+            item: NonNull::new(item).expect("JsonRef items are never null"),
+            owner: std::marker::PhantomData,
+        }
+    }
+
+    /// Get a mutable pointer to the referenced item (still owned by the tree).
+    pub fn as_ptr(&self) -> *mut Json {
+        self.item.as_ptr()
+    }
+
+    /// Borrow the member with the specified key (case-sensitive) if this item is of type `Object`.
+    pub fn get(&self, key: &str) -> Option<JsonRef<'a>> {
+        if !self.as_ptr().is_type_object() {
+            return None;
+        }
+        object_member(self.as_ptr(), key).map(JsonRef::new)
+    }
+
+    /// Borrow the item at the specified index if this item is of type `Array`.
+    pub fn get_index(&self, index: usize) -> Option<JsonRef<'a>> {
+        if !self.as_ptr().is_type_array() {
+            return None;
+        }
+        child_items(self.as_ptr())
+            .get(index)
+            .copied()
+            .map(JsonRef::new)
+    }
+}
+
+impl std::ops::Deref for JsonRef<'_> {
+    type Target = Json;
+
+    fn deref(&self) -> &Json {
+        unsafe { self.item.as_ref() }
+    }
+}
+
+/// Owner of a Json item of type `Array`, deleting it when dropped.
+///
+/// Iterating over an OwnedArray by value detaches its items one by one and hands each of them
+/// over as an OwnedJson, so they survive the array. The source array is emptied in the process;
+/// whatever has not been yielded when the iterator is dropped is deleted along with it.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let array = OwnedArray::from_raw(cjson_parse_json(r#"[1,"two",{"three":3}]"#.to_string()).unwrap()).unwrap();
+///     let items: Vec<OwnedJson> = array.into_iter().collect();
+///
+///     assert_eq!(items.len(), 3);
+///     assert_eq!(items[0].valuedouble, 1.0);
+///     assert_eq!(cjson_get_string_value(items[1].as_ptr()).unwrap(), "two");
+///     assert_eq!(items[2].as_ptr().print_unformatted().unwrap(), r#"{"three":3}"#);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub struct OwnedArray(OwnedJson);
+
+impl OwnedArray {
+    /// Take ownership of a Json item of type `Array` (which must not belong to another item).
+    ///
+    /// Args:
+    /// - `array: *mut Json` - Mutable pointer to the Json item of type `Array`.
+    ///
+    /// Returns:
+    /// - `Ok(OwnedArray)` - the owner of the array.
+    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
+    /// - `Err(JsonError::InvalidTypeError(String))` - if the item is not of type `Array` (it is
+    /// left to the caller).
+    pub fn from_raw(array: *mut Json) -> Result<OwnedArray, JsonError> {
+        if array.is_null() {
+            return Err(JsonError::NullPointer);
+        }
+        if !array.is_type_array() {
+            return Err(JsonError::InvalidTypeError(
+                "cannot own a non-array Json item as an array".to_string(),
+            ));
+        }
+        OwnedJson::from_raw(array).map(OwnedArray)
+    }
+
+    /// Get a mutable pointer to the array (still owned by the OwnedArray).
+    pub fn as_ptr(&self) -> *mut Json {
+        self.0.as_ptr()
+    }
+
+    /// Release ownership of the array without deleting it (to be deleted by the caller).
+    pub fn into_raw(self) -> *mut Json {
+        self.0.into_raw()
+    }
+}
+
+impl std::ops::Deref for OwnedArray {
+    type Target = Json;
+
+    fn deref(&self) -> &Json {
+        &self.0
+    }
+}
+
+impl IntoIterator for OwnedArray {
+    type Item = OwnedJson;
+    type IntoIter = OwnedArrayIter;
+
+    fn into_iter(self) -> OwnedArrayIter {
+        OwnedArrayIter(self.0)
+    }
+}
+
+/// Iterator detaching the items of an OwnedArray, created by `OwnedArray::into_iter`.
+pub struct OwnedArrayIter(OwnedJson);
+
+impl Iterator for OwnedArrayIter {
+    type Item = OwnedJson;
+
+    fn next(&mut self) -> Option<OwnedJson> {
+        let array = self.0.as_ptr();
+        let first = unsafe { (*array).child };
+        if first.is_null() {
+            return None;
+        }
+        let item = unsafe { cJSON_DetachItemViaPointer(array as *mut cJSON, first as *mut cJSON) };
+        OwnedJson::from_raw(item as *mut Json).ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = child_items(self.0.as_ptr()).len();
+        (remaining, Some(remaining))
+    }
+}
+
+/// Owner of a Json item of type `Object`, deleting it when dropped.
+///
+/// Iterating over an OwnedObject by value detaches its members one by one and hands each of them
+/// over as a `(key, OwnedJson)` pair, so they survive the object. The source object is emptied in
+/// the process; whatever has not been yielded when the iterator is dropped is deleted along with
+/// it. Keys that are not valid UTF-8 have the invalid sequences replaced by `U+FFFD`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let object = OwnedObject::from_raw(cjson_parse_json(r#"{"name":"Nemuel","age":20,"tags":["a"]}"#.to_string()).unwrap()).unwrap();
+///     let members: Vec<(String, OwnedJson)> = object.into_iter().collect();
+///
+///     let keys: Vec<&str> = members.iter().map(|(key, _)| key.as_str()).collect();
+///     assert_eq!(keys, ["name", "age", "tags"]);
+///     assert_eq!(cjson_get_string_value(members[0].1.as_ptr()).unwrap(), "Nemuel");
+///     assert_eq!(members[1].1.valuedouble, 20.0);
+///     assert_eq!(members[2].1.as_ptr().print_unformatted().unwrap(), r#"["a"]"#);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub struct OwnedObject(OwnedJson);
+
+impl OwnedObject {
+    /// Take ownership of a Json item of type `Object` (which must not belong to another item).
+    ///
+    /// Args:
+    /// - `object: *mut Json` - Mutable pointer to the Json item of type `Object`.
+    ///
+    /// Returns:
+    /// - `Ok(OwnedObject)` - the owner of the object.
+    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
+    /// - `Err(JsonError::InvalidTypeError(String))` - if the item is not of type `Object` (it is
+    /// left to the caller).
+    pub fn from_raw(object: *mut Json) -> Result<OwnedObject, JsonError> {
+        if object.is_null() {
+            return Err(JsonError::NullPointer);
+        }
+        if !object.is_type_object() {
+            return Err(JsonError::InvalidTypeError(
+                "cannot own a non-object Json item as an object".to_string(),
+            ));
+        }
+        OwnedJson::from_raw(object).map(OwnedObject)
+    }
+
+    /// Get a mutable pointer to the object (still owned by the OwnedObject).
+    pub fn as_ptr(&self) -> *mut Json {
+        self.0.as_ptr()
+    }
+
+    /// Release ownership of the object without deleting it (to be deleted by the caller).
+    pub fn into_raw(self) -> *mut Json {
+        self.0.into_raw()
+    }
+}
+
+impl std::ops::Deref for OwnedObject {
+    type Target = Json;
+
+    fn deref(&self) -> &Json {
+        &self.0
+    }
+}
+
+impl IntoIterator for OwnedObject {
+    type Item = (String, OwnedJson);
+    type IntoIter = OwnedObjectIter;
+
+    fn into_iter(self) -> OwnedObjectIter {
+        OwnedObjectIter(self.0)
+    }
+}
+
+/// Iterator detaching the members of an OwnedObject, created by `OwnedObject::into_iter`.
+pub struct OwnedObjectIter(OwnedJson);
+
+impl Iterator for OwnedObjectIter {
+    type Item = (String, OwnedJson);
+
+    fn next(&mut self) -> Option<(String, OwnedJson)> {
+        let object = self.0.as_ptr();
+        let first = unsafe { (*object).child };
+        if first.is_null() {
+            return None;
+        }
+        let key = match unsafe { (*first).string.as_ref() } {
+            Some(key) => unsafe { CStr::from_ptr(key) }
+                .to_string_lossy()
+                .into_owned(),
+            None => String::new(),
+        };
+        let member =
+            unsafe { cJSON_DetachItemViaPointer(object as *mut cJSON, first as *mut cJSON) };
+        OwnedJson::from_raw(member as *mut Json)
+            .ok()
+            .map(|member| (key, member))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = child_items(self.0.as_ptr()).len();
+        (remaining, Some(remaining))
+    }
+}
+
+/// Reusable buffer for `print_reusing`, so that printing many documents does not allocate a new
+/// buffer for each of them.
+///
+/// The buffer starts small and grows to the size of any document that does not fit, then keeps
+/// its size.
+#[derive(Debug, Default)]
+pub struct PrintScratch {
+    buffer: Vec<u8>,
+}
+
+impl PrintScratch {
+    /// Create an empty scratch buffer (memory is allocated on first use).
+    pub fn new() -> PrintScratch {
+        PrintScratch::default()
+    }
+
+    /// Create a scratch buffer able to hold `capacity` bytes before growing.
+    pub fn with_capacity(capacity: usize) -> PrintScratch {
+        PrintScratch {
+            buffer: vec![0; capacity],
+        }
+    }
+
+    /// Get the current size of the buffer in bytes.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+}
+
+/// Buffer allocated with `cJSON_malloc` that is freed with `cJSON_free` when dropped.
+///
+/// A safe alternative to pairing `cjson_malloc` and `cjson_free` by hand, eg. as the preallocated
+/// buffer for `print_preallocated`. The memory is zeroed on allocation.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_parse_json(r#"{"name":"Nemuel","age":20}"#.to_string()).unwrap();
+///
+///     let mut buffer = CjsonBuffer::new(64).unwrap();
+///     assert_eq!(buffer.len(), 64);
+///     json.print_preallocated(buffer.as_mut_ptr(), buffer.len() as i32, false).unwrap();
+///     assert_eq!(buffer.to_str().unwrap(), r#"{"name":"Nemuel","age":20}"#);
+///     println!("Test passed"); // output: Test passed
+/// } // `buffer` is freed here
+/// ```
+#[derive(Debug)]
+pub struct CjsonBuffer {
+    ptr: NonNull<c_char>,
+    len: usize,
+}
+
+impl CjsonBuffer {
+    /// Allocate a zeroed buffer of `len` bytes (`Err(JsonError::NullPointer)` if the allocation
+    /// fails).
+    pub fn new(len: usize) -> Result<CjsonBuffer, JsonError> {
+        // cJSON_malloc(0) may return null, so at least one byte is always allocated
+        let ptr = unsafe { cJSON_malloc(len.max(1)) } as *mut c_char;
+        match NonNull::new(ptr) {
+            Some(ptr) => {
+                unsafe { std::ptr::write_bytes(ptr.as_ptr(), 0, len.max(1)) };
+                Ok(CjsonBuffer { ptr, len })
+            }
+            None => Err(JsonError::NullPointer),
+        }
+    }
+
+    /// Get a mutable pointer to the start of the buffer, valid for `len()` bytes.
+    pub fn as_mut_ptr(&mut self) -> *mut c_char {
+        self.ptr.as_ptr()
+    }
+
+    /// Get the size of the buffer in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the buffer has a size of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the content of the buffer up to the first null byte (or all of it if there is none).
+    pub fn to_str(&self) -> Result<&str, JsonError> {
+        let bytes = unsafe { std::slice::from_raw_parts(self.ptr.as_ptr() as *const u8, self.len) };
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        std::str::from_utf8(&bytes[..end]).map_err(JsonError::Utf8Error)
+    }
+}
+
+impl Drop for CjsonBuffer {
+    fn drop(&mut self) {
+        unsafe { cJSON_free(self.ptr.as_ptr() as *mut c_void) };
+    }
+}
+
+/// Cache of parsed documents keyed by their input string (requires the `parse-cache` feature).
+///
+/// Each distinct input is parsed once and its tree kept for the lifetime of the cache. Every call
+/// to `get_or_parse` then returns a deep copy, so callers can modify or delete what they get
+/// without affecting the cache. Memory use grows with the number of distinct inputs: each one is
+/// held twice (the input string and its tree) until `clear` is called or the cache is dropped, so
+/// only cache payloads that actually repeat.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let mut cache = ParseCache::new();
+///
+///     let mut first = cache.get_or_parse(r#"{"name":"Nemuel"}"#).unwrap();
+///     assert_eq!((cache.hits(), cache.misses()), (0, 1));
+///
+///     let mut second = cache.get_or_parse(r#"{"name":"Nemuel"}"#).unwrap();
+///     assert_eq!((cache.hits(), cache.misses()), (1, 1));
+///     assert_eq!(cjson_compare(first, second, true), true);
+///     assert_ne!(first, second); // every call hands out its own copy
+///
+///     cjson_delete(&mut first);
+///     cjson_delete(&mut second);
+///     assert_eq!(cache.len(), 1);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+#[cfg(feature = "parse-cache")]
+#[derive(Default)]
+pub struct ParseCache {
+    entries: std::collections::HashMap<String, OwnedJson>,
+    hits: usize,
+    misses: usize,
+}
+
+#[cfg(feature = "parse-cache")]
+impl ParseCache {
+    /// Create an empty cache.
+    pub fn new() -> ParseCache {
+        ParseCache::default()
+    }
+
+    /// Get a copy of the document parsed from `input`, parsing it only if it is not cached yet.
+    ///
+    /// Args:
+    /// - `input: &str` - The JSON string to be parsed.
+    ///
+    /// Returns:
+    /// - `Ok(*mut Json)` - a deep copy of the parsed document (to be deleted by the caller).
+    /// - `Err(JsonError)` - any error `cjson_parse_json` reports (nothing is cached then), or
+    /// `JsonError::NullPointer` if the copy could not be allocated.
+    pub fn get_or_parse(&mut self, input: &str) -> Result<*mut Json, JsonError> {
+        let root = match self.entries.get(input) {
+            Some(root) => {
+                self.hits += 1;
+                root.as_ptr()
+            }
+            None => {
+                let root = OwnedJson::from_raw(cjson_parse_json(input.to_string())?)?;
+                self.misses += 1;
+                let item = root.as_ptr();
+                self.entries.insert(input.to_string(), root);
+                item
+            }
+        };
+        cjson_try_duplicate(root, true)
+    }
+
+    /// Get the number of calls answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Get the number of calls that had to parse their input.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Get the number of cached documents.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether no document is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Delete every cached document (the hit and miss counters are kept).
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+// value recorded by a builder, turned into a Json item when the builder is built
+enum BuilderValue {
+    String(String),
+    Number(f64),
+    Bool(bool),
+    Null,
+    Object(ObjectBuilder),
+    Item(*mut Json),
+}
+
+impl BuilderValue {
+    // create the Json item for this value
+    fn build(self) -> Result<*mut Json, JsonError> {
+        let item = match self {
+            BuilderValue::String(string) => match CString::new(string) {
+                Ok(c_str) => unsafe { cJSON_CreateString(c_str.as_ptr()) as *mut Json },
+                Err(err) => return Err(JsonError::CStringError(err)),
+            },
+            BuilderValue::Number(number) => unsafe { cJSON_CreateNumber(number) as *mut Json },
+            BuilderValue::Bool(boolean) => unsafe {
+                cJSON_CreateBool(if boolean { 1 } else { 0 }) as *mut Json
+            },
+            BuilderValue::Null => unsafe { cJSON_CreateNull() as *mut Json },
+            BuilderValue::Object(builder) => return builder.build(),
+            BuilderValue::Item(item) => item,
+        };
+        if item.is_null() {
+            Err(JsonError::NullPointer)
+        } else {
+            Ok(item)
+        }
+    }
+}
+
+/// Builder for Json items of type `Object` with chainable methods.
+///
+/// Members are only recorded by the chained calls; the Json items are created by `build()`, which
+/// is also where conversion errors (eg. null bytes in keys or values) are reported.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let mut built = ObjectBuilder::new()
+///         .string("name", "Nemuel")
+///         .number("age", 20.0)
+///         .bool("verified", true)
+///         .null("nickname")
+///         .child("address", ObjectBuilder::new().string("city", "Nairobi"))
+///         .build()
+///         .unwrap();
+///
+///     let mut parsed = cjson_parse_json(
+///         r#"{"name":"Nemuel","age":20,"verified":true,"nickname":null,"address":{"city":"Nairobi"}}"#
+///             .to_string(),
+///     )
+///     .unwrap();
+///     assert_eq!(cjson_compare(built, parsed, true), true);
+///
+///     match ObjectBuilder::new().string("bad\0key", "value").build() {
+///         Err(JsonError::CStringError(_)) => {}
+///         _ => panic!("the null byte should have been rejected"),
+///     }
+///
+///     cjson_delete(&mut built);
+///     cjson_delete(&mut parsed);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+#[derive(Default)]
+pub struct ObjectBuilder {
+    members: Vec<(String, BuilderValue)>,
+}
+
+impl ObjectBuilder {
+    /// Create new, empty instance of the ObjectBuilder struct.
+    pub fn new() -> ObjectBuilder {
+        ObjectBuilder::default()
+    }
+
+    /// Add a member of type `String`.
+    pub fn string(mut self, key: &str, value: &str) -> ObjectBuilder {
+        self.members
+            .push((key.to_string(), BuilderValue::String(value.to_string())));
+        self
+    }
+
+    /// Add a member of type `Number`.
+    pub fn number(mut self, key: &str, value: f64) -> ObjectBuilder {
+        self.members
+            .push((key.to_string(), BuilderValue::Number(value)));
+        self
+    }
+
+    /// Add a member of type `Bool`.
+    pub fn bool(mut self, key: &str, value: bool) -> ObjectBuilder {
+        self.members
+            .push((key.to_string(), BuilderValue::Bool(value)));
+        self
+    }
+
+    /// Add a member of type `Null`.
+    pub fn null(mut self, key: &str) -> ObjectBuilder {
+        self.members.push((key.to_string(), BuilderValue::Null));
+        self
+    }
+
+    /// Add a nested object described by another builder.
+    pub fn child(mut self, key: &str, child: ObjectBuilder) -> ObjectBuilder {
+        self.members
+            .push((key.to_string(), BuilderValue::Object(child)));
+        self
+    }
+
+    /// Create the Json item of type `Object` with all the recorded members (in order).
+    ///
+    /// Returns:
+    /// - `Ok(*mut Json)` - a mutable pointer to the new object (to be deleted by the caller).
+    /// - `Err(JsonError::CStringError(NulError))` - if a key or string value contains a null byte.
+    /// - `Err(JsonError::NullPointer)` - if cJSON fails to allocate an item.
+    pub fn build(self) -> Result<*mut Json, JsonError> {
+        let object = unsafe { cJSON_CreateObject() as *mut Json };
+        if object.is_null() {
+            return Err(JsonError::NullPointer);
+        }
+
+        for (key, value) in self.members {
+            let added = match CString::new(key) {
+                Ok(c_key) => value.build().and_then(|item| {
+                    let added = unsafe {
+                        cJSON_AddItemToObject(
+                            object as *mut cJSON,
+                            c_key.as_ptr(),
+                            item as *mut cJSON,
+                        )
+                    };
+                    if added == 1 {
+                        Ok(())
+                    } else {
+                        unsafe { cJSON_Delete(item as *mut cJSON) };
+                        Err(JsonError::NullPointer)
+                    }
+                }),
+                Err(err) => Err(JsonError::CStringError(err)),
+            };
+            if let Err(err) = added {
+                unsafe { cJSON_Delete(object as *mut cJSON) };
+                return Err(err);
+            }
+        }
+        Ok(object)
+    }
+}
+
+/// Builder for Json items of type `Array` with typed push methods.
+///
+/// Items are only recorded by the chained calls; the Json items are created by `build()`, which is
+/// also where conversion errors (eg. null bytes in strings) are reported. Items passed to `push`
+/// are owned by the builder: they end up in the array, or get deleted if building fails or the
+/// builder is dropped without being built.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let mut array = ArrayBuilder::new()
+///         .push_number(1.0)
+///         .push_string("two")
+///         .push_bool(true)
+///         .build()
+///         .unwrap();
+///
+///     assert_eq!(cjson_get_array_size(array).unwrap(), 3);
+///     let first = cjson_get_array_item(array, 0).unwrap();
+///     assert_eq!(first.is_type_number(), true);
+///     assert_eq!(cjson_get_number_value(first).unwrap(), 1.0);
+///     let second = cjson_get_array_item(array, 1).unwrap();
+///     assert_eq!(cjson_get_string_value(second).unwrap(), "two");
+///     let third = cjson_get_array_item(array, 2).unwrap();
+///     assert_eq!(third.is_type_true(), true);
+///     assert_eq!(array.print_unformatted().unwrap(), r#"[1,"two",true]"#);
+///
+///     let nested = ArrayBuilder::new().push(cjson_create_null()).build().unwrap();
+///     assert_eq!(nested.print_unformatted().unwrap(), "[null]");
+///
+///     // the pushed item is deleted along with the builder
+///     drop(ArrayBuilder::new().push(cjson_create_string("unused".to_string()).unwrap()));
+///
+///     cjson_delete(&mut array);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+#[derive(Default)]
+pub struct ArrayBuilder {
+    items: Vec<BuilderValue>,
+}
+
+impl ArrayBuilder {
+    /// Create new, empty instance of the ArrayBuilder struct.
+    pub fn new() -> ArrayBuilder {
+        ArrayBuilder::default()
+    }
+
+    /// Append an item of type `Number`.
+    pub fn push_number(mut self, value: f64) -> ArrayBuilder {
+        self.items.push(BuilderValue::Number(value));
+        self
+    }
+
+    /// Append an item of type `String`.
+    pub fn push_string(mut self, value: &str) -> ArrayBuilder {
+        self.items.push(BuilderValue::String(value.to_string()));
+        self
+    }
+
+    /// Append an item of type `Bool`.
+    pub fn push_bool(mut self, value: bool) -> ArrayBuilder {
+        self.items.push(BuilderValue::Bool(value));
+        self
+    }
+
+    /// Append an existing Json item (which must not belong to another array or object).
+    pub fn push(mut self, item: *mut Json) -> ArrayBuilder {
+        self.items.push(BuilderValue::Item(item));
+        self
+    }
+
+    /// Create the Json item of type `Array` with all the recorded items (in order).
+    ///
+    /// Returns:
+    /// - `Ok(*mut Json)` - a mutable pointer to the new array (to be deleted by the caller).
+    /// - `Err(JsonError::CStringError(NulError))` - if a string value contains a null byte.
+    /// - `Err(JsonError::NullPointer)` - if a pushed item is null or cJSON fails to allocate an item.
+    pub fn build(mut self) -> Result<*mut Json, JsonError> {
+        let array = unsafe { cJSON_CreateArray() as *mut Json };
+        let mut items = std::mem::take(&mut self.items).into_iter();
+        let mut result = if array.is_null() {
+            Err(JsonError::NullPointer)
+        } else {
+            Ok(array)
+        };
+
+        while result.is_ok() {
+            let value = match items.next() {
+                Some(value) => value,
+                None => break,
+            };
+            result = value.build().and_then(|item| {
+                if unsafe { cJSON_AddItemToArray(array as *mut cJSON, item as *mut cJSON) } == 1 {
+                    Ok(array)
+                } else {
+                    unsafe { cJSON_Delete(item as *mut cJSON) };
+                    Err(JsonError::NullPointer)
+                }
+            });
+        }
+
+        if result.is_err() {
+            // the items that were not added yet are still owned by the builder
+            for value in items {
+                if let BuilderValue::Item(item) = value {
+                    unsafe { cJSON_Delete(item as *mut cJSON) };
+                }
+            }
+            unsafe { cJSON_Delete(array as *mut cJSON) };
+        }
+        result
+    }
+}
+
+impl Drop for ArrayBuilder {
+    // delete the pushed items of a builder that was never built
+    fn drop(&mut self) {
+        for value in self.items.drain(..) {
+            if let BuilderValue::Item(item) = value {
+                unsafe { cJSON_Delete(item as *mut cJSON) };
+            }
+        }
+    }
+}
+
+pub trait JsonPtrExt {
+    fn is_type_invalid(&self) -> bool;
+    fn is_type_false(&self) -> bool;
+    fn is_type_true(&self) -> bool;
+    fn is_type_bool(&self) -> bool;
+    fn is_type_null(&self) -> bool;
+    fn is_type_number(&self) -> bool;
+    fn is_type_string(&self) -> bool;
+    fn is_type_array(&self) -> bool;
+    fn is_type_object(&self) -> bool;
+    fn is_type_raw(&self) -> bool;
+    fn print(&self) -> Result<String, JsonError>;
+    fn print_buffered(&self, prebuffer: i32, fmt: bool) -> Result<String, JsonError>;
+    fn print_preallocated(
+        &self,
+        buffer: *mut i8,
+        length: i32,
+        format: bool,
+    ) -> Result<(), JsonError>;
+    fn print_unformatted(&self) -> Result<String, JsonError>;
+    fn delete(&self);
+    fn to_pretty_string(&self) -> Result<String, JsonError>;
+    fn to_compact_string(&self) -> Result<String, JsonError>;
+    fn print_reusing<'s>(
+        &self,
+        scratch: &'s mut PrintScratch,
+        pretty: bool,
+    ) -> Result<&'s str, JsonError>;
+    fn as_bool(&self) -> Option<bool>;
+    fn as_f64(&self) -> Option<f64>;
+    fn as_str(&self) -> Option<&str>;
+}
+
+impl JsonPtrExt for *mut Json {
+    /// Check whether the Json object is of type `Invalid`.
+    ///
+    /// Returns:
+    /// - `bool` - indicating whether or not the Json object is of type `Invalid`.
+    fn is_type_invalid(&self) -> bool {
+        match unsafe { self.as_mut() } {
+            Some(json) => json.is_type_invalid(),
+            None => false,
+        }
+    }
+
+    /// Check whether the Json object is of type `False`.
+    ///
+    /// Returns:
+    /// - `bool` - indicating whether or not the Json object is of type `False`.
+    fn is_type_false(&self) -> bool {
+        match unsafe { self.as_mut() } {
+            Some(json) => json.is_type_false(),
+            None => false,
+        }
+    }
+
+    /// Check whether the Json object is of type `True`.
+    ///
+    /// Returns:
+    /// - `bool` - indicating whether or not the Json object is of type `True`.
+    fn is_type_true(&self) -> bool {
+        match unsafe { self.as_mut() } {
+            Some(json) => json.is_type_true(),
+            None => false,
+        }
+    }
+
+    /// Check whether the Json object is of type `Bool`.
+    ///
+    /// Returns:
+    /// - `bool` - indicating whether or not the Json object is of type `Bool`.
+    fn is_type_bool(&self) -> bool {
+        match unsafe { self.as_mut() } {
+            Some(json) => json.is_type_bool(),
+            None => false,
+        }
+    }
+
+    /// Check whether the Json object is of type `Null`.
+    ///
+    /// Returns:
+    /// - `bool` - indicating whether or not the Json object is of type `Null`.
+    fn is_type_null(&self) -> bool {
+        match unsafe { self.as_mut() } {
+            Some(json) => json.is_type_null(),
+            None => false,
+        }
+    }
+
+    /// Check whether the Json object is of type `Number`.
+    ///
+    /// Returns:
+    /// - `bool` - indicating whether or not the Json object is of type `Number`.
+    fn is_type_number(&self) -> bool {
+        match unsafe { self.as_mut() } {
+            Some(json) => json.is_type_number(),
+            None => false,
+        }
+    }
+
+    /// Check whether the Json object is of type `String`.
+    ///
+    /// Returns:
+    /// - `bool` - indicating whether or not the Json object is of type `String`.
+    fn is_type_string(&self) -> bool {
+        match unsafe { self.as_mut() } {
+            Some(json) => json.is_type_string(),
+            None => false,
+        }
+    }
+
+    /// Check whether the Json object is of type `Array`.
+    ///
+    /// Returns:
+    /// - `bool` - indicating whether or not the Json object is of type `Array`.
+    fn is_type_array(&self) -> bool {
+        match unsafe { self.as_mut() } {
+            Some(json) => json.is_type_array(),
+            None => false,
+        }
+    }
+
+    /// Check whether the Json object is of type `Object`.
+    ///
+    /// Returns:
+    /// - `bool` - indicating whether or not the Json object is of type `Object`.
+    fn is_type_object(&self) -> bool {
+        match unsafe { self.as_mut() } {
+            Some(json) => json.is_type_object(),
+            None => false,
+        }
+    }
+
+    /// Check whether the Json object is of type `Raw`.
+    ///
+    /// Returns:
+    /// - `bool` - indicating whether or not the Json object is of type `Raw`.
+    fn is_type_raw(&self) -> bool {
+        match unsafe { self.as_mut() } {
+            Some(json) => json.is_type_raw(),
+            None => false,
+        }
+    }
+
+    /// Generate a string representation of the JSON object eg.
+    /// ```json
+    /// {
+    ///     "name": "Nemuel",
+    ///     "age": 20
+    /// }
+    /// ```
+    ///
+    /// Returns:
+    /// - `Ok(String)` - if the JSON object's string representation is successfully generated.
+    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
+    /// - `Err(JsonError::PrintError)` - if the string generation fails.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     let json: *mut Json = cjson_create_object();
+    ///     match json.print() {
+    ///         Ok(result) => assert_eq!(result, "{\n}"),
+    ///         Err(err) => eprintln!("{}", err),
+    ///     }
+    /// }
+    /// ```
+    fn print(&self) -> Result<String, JsonError> {
+        match unsafe { self.as_mut() } {
+            Some(json) => json.print(),
+            None => Err(JsonError::NullPointer),
+        }
+    }
+
+    /// Generate a string representation of the JSON object with dynamic buffer resizing.
+    ///
+    /// Args:
+    /// - `prebuffer: i32`: Size of buffer to start with.
+    /// - `fmt: bool`: Whether or not to have the output formatted/pretty-printed.
+    ///
+    /// Returns:
+    /// - `Ok(String)` - if the buffer allocation and string generation go well.
+    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
+    /// - `Err(JsonError::PrintBufferedError)` - if an error occurs during allocation and/or string
+    /// generation.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     let json: *mut Json = cjson_create_object();
+    ///     match json.print_buffered(8, false) {
+    ///         Ok(result) => assert_eq!(result, "{}"),
+    ///         Err(err) => eprintln!("{}", err),
+    ///     }
+    /// }
+    /// ```
+    fn print_buffered(&self, prebuffer: i32, fmt: bool) -> Result<String, JsonError> {
+        match unsafe { self.as_mut() } {
+            Some(json) => json.print_buffered(prebuffer, fmt),
+            None => Err(JsonError::NullPointer),
+        }
+    }
+
+    /// Generate a string representation of the JSON object into a preallocated buffer.
+    ///
+    /// Args:
+    /// - `buffer: *mut i8`: Preallocated buffer where the generated string will be stored.
+    /// - `length: i32`: Number of bytes to write (preferably equal to the size of the allocated buffer).
+    /// - `format: bool`: Whether or not to have the output formatted/pretty-printed.
+    ///
+    /// Returns:
+    /// - `Ok(())` - if all goes well.
+    /// - `Err(NullPointer)` - if the pointer is null.
+    /// - `Err(PrintPreallocatedError)` - if an error occurs during the string generation or copying
+    /// into the buffer.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    /// use libc::malloc;
+    /// use std::ffi::CStr;
+    ///
+    /// fn main() {
+    ///     let json: *mut Json = cjson_create_object();
+    ///     let buffer: *mut i8 = unsafe { malloc(8) as *mut i8 };
+    ///     match json.print_preallocated(buffer, 8, false) {
+    ///         Ok(_) => unsafe {
+    ///             let c_str = CStr::from_ptr(buffer);
+    ///            let result = c_str.to_str().unwrap_or_default().to_string();
+    ///             assert_eq!(result, "{}");
+    ///             println!("Test passed!");
+    ///         },
+    ///         Err(err) => eprintln!("{}", err),
+    ///     }
+    /// }
+    /// ```
+    fn print_preallocated(
+        &self,
+        buffer: *mut i8,
+        length: i32,
+        format: bool,
+    ) -> Result<(), JsonError> {
+        match unsafe { self.as_mut() } {
+            Some(json) => {
+                if json.print_preallocated(buffer, length, format) {
+                    Ok(())
+                } else {
+                    Err(JsonError::PrintPreallocatedError)
+                }
+            }
+            None => Err(JsonError::NullPointer),
+        }
+    }
+
+    /// Generate an **unformatted** string representation of the JSON object eg.
+    /// ```json
+    /// {
+    ///     "name": "Nemuel",
+    ///     "age": 20
+    /// }
+    /// ```
+    ///
+    /// Returns:
+    /// - `Ok(String)` - if the JSON object's string representation is successfully generated.
+    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
+    /// - `Err(JsonError::PrintError)` - if the string generation fails.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
     /// fn main() {
     ///     let json: *mut Json = cjson_create_object();
     ///     match json.print() {
@@ -463,2425 +2974,7763 @@ impl JsonPtrExt for *mut Json {
     ///     }
     /// }
     /// ```
-    fn print(&self) -> Result<String, JsonError> {
+    fn print_unformatted(&self) -> Result<String, JsonError> {
         match unsafe { self.as_mut() } {
-            Some(json) => json.print(),
+            Some(json) => json.print_unformatted(),
             None => Err(JsonError::NullPointer),
         }
     }
 
-    /// Generate a string representation of the JSON object with dynamic buffer resizing.
-    ///
-    /// Args:
-    /// - `prebuffer: i32`: Size of buffer to start with.
-    /// - `fmt: bool`: Whether or not to have the output formatted/pretty-printed.
-    ///
-    /// Returns:
-    /// - `Ok(String)` - if the buffer allocation and string generation go well.
-    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
-    /// - `Err(JsonError::PrintBufferedError)` - if an error occurs during allocation and/or string
-    /// generation.
-    ///
-    /// Example:
-    /// ```rust
-    /// use cjson_rs::*;
-    ///
-    /// fn main() {
-    ///     let json: *mut Json = cjson_create_object();
-    ///     match json.print_buffered(8, false) {
-    ///         Ok(result) => assert_eq!(result, "{}"),
-    ///         Err(err) => eprintln!("{}", err),
-    ///     }
-    /// }
-    /// ```
-    fn print_buffered(&self, prebuffer: i32, fmt: bool) -> Result<String, JsonError> {
-        match unsafe { self.as_mut() } {
-            Some(json) => json.print_buffered(prebuffer, fmt),
-            None => Err(JsonError::NullPointer),
+    /// Delete a JSON entity and all its subentities.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     let json: *mut Json = cjson_create_object();
+    ///     json.delete();
+    /// }
+    /// ```
+    fn delete(&self) {
+        unsafe { self.as_mut().map(|json| json.delete()) };
+    }
+
+    /// Generate a pretty-printed (formatted) string representation of the JSON object.
+    ///
+    /// This is the same as `print`, named after the output it produces.
+    ///
+    /// Returns:
+    /// - `Ok(String)` - if the JSON object's string representation is successfully generated.
+    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
+    /// - `Err(JsonError::PrintError)` - if the string generation fails.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     let json = cjson_parse_json(r#"{"name":"Nemuel","age":20}"#.to_string()).unwrap();
+    ///     assert_eq!(json.to_pretty_string().unwrap(), "{\n\t\"name\":\t\"Nemuel\",\n\t\"age\":\t20\n}");
+    ///     assert_eq!(json.to_compact_string().unwrap(), r#"{"name":"Nemuel","age":20}"#);
+    ///     println!("Test passed"); // output: Test passed
+    /// }
+    /// ```
+    fn to_pretty_string(&self) -> Result<String, JsonError> {
+        self.print()
+    }
+
+    /// Generate a compact (unformatted) string representation of the JSON object.
+    ///
+    /// This is the same as `print_unformatted`, named after the output it produces.
+    ///
+    /// Returns:
+    /// - `Ok(String)` - if the JSON object's string representation is successfully generated.
+    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
+    /// - `Err(JsonError::PrintError)` - if the string generation fails.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     let json = cjson_parse_json(r#"{"name":"Nemuel","age":20}"#.to_string()).unwrap();
+    ///     assert_eq!(json.to_compact_string().unwrap(), r#"{"name":"Nemuel","age":20}"#);
+    ///     assert_ne!(json.to_compact_string().unwrap(), json.to_pretty_string().unwrap());
+    ///     println!("Test passed"); // output: Test passed
+    /// }
+    /// ```
+    fn to_compact_string(&self) -> Result<String, JsonError> {
+        self.print_unformatted()
+    }
+
+    /// Generate a string representation of the JSON object into a reusable scratch buffer.
+    ///
+    /// The output is written with `cJSON_PrintPreallocated`. When it does not fit, the output is
+    /// measured with `cjson_serialized_len`, the buffer grows to that size and printing is retried
+    /// once, so once the buffer is large enough no allocation happens at all.
+    ///
+    /// Args:
+    /// - `scratch: &mut PrintScratch` - The buffer to print into (its previous content is
+    /// overwritten).
+    /// - `pretty: bool` - Whether or not to have the output formatted/pretty-printed.
+    ///
+    /// Returns:
+    /// - `Ok(&str)` - the string representation, borrowed from `scratch`.
+    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
+    /// - `Err(JsonError::PrintPreallocatedError)` - if the item or one of its children cannot be
+    /// printed (eg. an `Invalid` item) or its string representation does not fit in `i32::MAX` bytes.
+    /// - `Err(JsonError::Utf8Error(Utf8Error))` - if the output is not valid UTF-8.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     let mut scratch = PrintScratch::new();
+    ///     for i in 0..1000 {
+    ///         let mut json = cjson_create_object();
+    ///         cjson_add_number_to_object(json, "id", i as f64).unwrap();
+    ///         cjson_add_string_to_object(json, "name", "Nemuel").unwrap();
+    ///
+    ///         let printed = json.print_reusing(&mut scratch, false).unwrap();
+    ///         assert_eq!(printed, format!(r#"{{"id":{},"name":"Nemuel"}}"#, i));
+    ///         cjson_delete(&mut json);
+    ///     }
+    ///
+    ///     let json = cjson_parse_json(r#"{"name":"Nemuel"}"#.to_string()).unwrap();
+    ///     assert_eq!(json.print_reusing(&mut scratch, true).unwrap(), json.print().unwrap());
+    ///     assert!(scratch.capacity() < 1024);
+    ///
+    ///     let array = cjson_create_array();
+    ///     let invalid = cjson_create_null();
+    ///     unsafe { (*invalid).type_ = 0 };
+    ///     cjson_add_item_to_array(array, invalid).unwrap();
+    ///     assert!(array.print_reusing(&mut scratch, false).is_err());
+    ///     assert!(scratch.capacity() < 1024);
+    ///     println!("Test passed"); // output: Test passed
+    /// }
+    /// ```
+    fn print_reusing<'s>(
+        &self,
+        scratch: &'s mut PrintScratch,
+        pretty: bool,
+    ) -> Result<&'s str, JsonError> {
+        if self.is_null() {
+            return Err(JsonError::NullPointer);
+        }
+        if self.is_type_invalid() {
+            return Err(JsonError::PrintPreallocatedError);
+        }
+
+        if scratch.buffer.is_empty() {
+            scratch.buffer.resize(256, 0);
+        }
+        // first attempt with the buffer as is, then at most one retry with a measured size
+        for attempt in 0..2 {
+            let length = match i32::try_from(scratch.buffer.len()) {
+                Ok(length) => length,
+                Err(_) => return Err(JsonError::PrintPreallocatedError),
+            };
+            let buffer = scratch.buffer.as_mut_ptr() as *mut c_char;
+            if self.print_preallocated(buffer, length, pretty).is_ok() {
+                break;
+            }
+            if attempt == 1 {
+                return Err(JsonError::PrintPreallocatedError);
+            }
+            // an item that cannot be printed at all fails here instead of growing the buffer;
+            // cJSON_PrintPreallocated wants 5 bytes of slack beyond the output
+            let needed = match cjson_serialized_len(*self, pretty) {
+                Ok(len) => len + 5,
+                Err(_) => return Err(JsonError::PrintPreallocatedError),
+            };
+            if needed <= scratch.buffer.len() {
+                return Err(JsonError::PrintPreallocatedError);
+            }
+            scratch.buffer.resize(needed, 0);
+        }
+
+        let end = scratch
+            .buffer
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(scratch.buffer.len());
+        match std::str::from_utf8(&scratch.buffer[..end]) {
+            Ok(printed) => Ok(printed),
+            Err(err) => Err(JsonError::Utf8Error(err)),
+        }
+    }
+
+    /// Get the value of a Json item of type `Bool`.
+    ///
+    /// Returns:
+    /// - `Some(bool)` - the value if the item is of type `True` or `False`.
+    /// - `None` - if the pointer is null or the item is of any other type.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     assert_eq!(cjson_create_true().as_bool(), Some(true));
+    ///     assert_eq!(cjson_create_null().as_bool(), None);
+    ///     assert_eq!(std::ptr::null_mut::<Json>().as_bool(), None);
+    ///     println!("Test passed"); // output: Test passed
+    /// }
+    /// ```
+    fn as_bool(&self) -> Option<bool> {
+        unsafe { self.as_ref() }.and_then(|json| json.as_bool())
+    }
+
+    /// Get the value of a Json item of type `Number`.
+    ///
+    /// Returns:
+    /// - `Some(f64)` - the value if the item is of type `Number`.
+    /// - `None` - if the pointer is null or the item is of any other type.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     assert_eq!(cjson_create_number(2.5).as_f64(), Some(2.5));
+    ///     assert_eq!(cjson_create_string("2.5".to_string()).unwrap().as_f64(), None);
+    ///     println!("Test passed"); // output: Test passed
+    /// }
+    /// ```
+    fn as_f64(&self) -> Option<f64> {
+        unsafe { self.as_ref() }.and_then(|json| json.as_f64())
+    }
+
+    /// Get the value of a Json item of type `String`.
+    ///
+    /// Returns:
+    /// - `Some(&str)` - the value if the item is of type `String` and holds valid UTF-8.
+    /// - `None` - if the pointer is null, the item is of any other type or the value is not valid
+    /// UTF-8.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     let name = cjson_create_string("Nemuel".to_string()).unwrap();
+    ///     assert_eq!(name.as_str(), Some("Nemuel"));
+    ///     assert_eq!(cjson_create_false().as_str(), None);
+    ///     println!("Test passed"); // output: Test passed
+    /// }
+    /// ```
+    fn as_str(&self) -> Option<&str> {
+        unsafe { self.as_ref() }.and_then(|json| json.as_str())
+    }
+}
+
+/// Generate a canonical string representation of a Json item: no insignificant whitespace and
+/// object members sorted lexicographically (byte-wise) by key at every level.
+///
+/// The output only depends on the content of the item, not on the order in which object members
+/// were added, which makes it suitable for signing or hashing. Numbers and strings are printed
+/// exactly like `print_unformatted` prints them (NaN and infinities become `null`).
+///
+/// Args:
+/// - `item: *mut Json` - Mutable pointer to the Json item to be printed.
+///
+/// Returns:
+/// - `Ok(String)` - the canonical string representation of the item.
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
+/// - `Err(JsonError::PrintError)` - if the item (or one of its nested items) cannot be printed.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let mut first = cjson_parse_json("{\"b\": [1, 2.5], \"a\": {\"y\": null, \"x\": true}}".to_string()).unwrap();
+///     let mut second = cjson_parse_json("{\"a\": {\"x\": true, \"y\": null}, \"b\": [1, 2.5]}".to_string()).unwrap();
+///
+///     let canonical = cjson_print_canonical(first).unwrap();
+///     assert_eq!(canonical, "{\"a\":{\"x\":true,\"y\":null},\"b\":[1,2.5]}");
+///     assert_eq!(canonical, cjson_print_canonical(second).unwrap());
+///
+///     cjson_delete(&mut first);
+///     cjson_delete(&mut second);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_print_canonical(item: *mut Json) -> Result<String, JsonError> {
+    let mut canonical = String::new();
+    write_canonical(item, &mut canonical)?;
+    Ok(canonical)
+}
+
+/// Generate a formatted string representation of a Json item, cutting off deeply nested items.
+///
+/// The output has the same layout as `print`, except that non-empty arrays and objects found
+/// `max_depth` levels below the root are printed as the placeholder string `"..."` instead of
+/// their contents (a `max_depth` of 0 truncates the root itself).
+///
+/// Args:
+/// - `root: *mut Json` - Mutable pointer to the Json item to be printed.
+/// - `max_depth: usize` - Number of nesting levels printed in full.
+///
+/// Returns:
+/// - `Ok(String)` - the (possibly truncated) string representation of the item.
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
+/// - `Err(JsonError::PrintError)` - if the item (or one of its nested items) cannot be printed.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_parse_json(r#"{"name":"Nemuel","tags":["a","b"],"address":{"city":{"name":"Nairobi"}}}"#.to_string()).unwrap();
+///
+///     let truncated = cjson_print_truncated(json, 1).unwrap();
+///     assert_eq!(truncated, "{\n\t\"name\":\t\"Nemuel\",\n\t\"tags\":\t\"...\",\n\t\"address\":\t\"...\"\n}");
+///
+///     assert_eq!(cjson_print_truncated(json, 2).unwrap().matches("\"...\"").count(), 1);
+///     assert_eq!(cjson_print_truncated(json, 3).unwrap(), json.print().unwrap());
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_print_truncated(root: *mut Json, max_depth: usize) -> Result<String, JsonError> {
+    let mut printed = String::new();
+    write_truncated(root, 0, max_depth, &mut printed)?;
+    Ok(printed)
+}
+
+/// Remove all unnecessary whitespace and formatting from a JSON string.
+///
+/// Args:
+/// - `json: String` - The JSON string to be minified.
+///
+/// Returns:
+/// - `Ok(())` - if the operation gets performed.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let mut json_str: String = "{\n\t\"name\": \"Nemuel\",\n\t\"age\": 20\n}".to_string();
+///     cjson_minify(&mut json_str).unwrap();
+///     assert_eq!(json_str, r#"{"name":"Nemuel","age":20}"#);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_minify(json: &mut String) -> Result<(), JsonError> {
+    match CString::new((*json).as_bytes()) {
+        Ok(c_str) => {
+            let c_str_mut_ptr = c_str.as_ptr() as *mut i8;
+            unsafe { cJSON_Minify(c_str_mut_ptr) };
+            let minified = unsafe { CStr::from_ptr(c_str_mut_ptr) };
+            *json = minified.to_string_lossy().into_owned();
+            Ok(())
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Remove all unnecessary whitespace and formatting from raw JSON bytes.
+///
+/// Unlike `cjson_minify`, the input is copied into a writable buffer that cJSON minifies in place,
+/// and the output is returned byte for byte, so content that is not valid UTF-8 is preserved.
+///
+/// Args:
+/// - `input: &[u8]` - The JSON bytes to be minified.
+///
+/// Returns:
+/// - `Ok(Vec<u8>)` - the minified bytes (without a null terminator).
+/// - `Err(JsonError::CStringError(NulError))` - if the input contains a null byte, since cJSON would
+/// stop minifying there.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let input = b"{\n\t\"say\": \"\\\"hi\\\"\\n\", // greeting\n\t\"bytes\": \"\xff\xfe\"\n}";
+///     let minified = cjson_minify_bytes(input).unwrap();
+///     assert_eq!(minified, b"{\"say\":\"\\\"hi\\\"\\n\",\"bytes\":\"\xff\xfe\"}".to_vec());
+///
+///     assert!(cjson_minify_bytes(b"[1,\0 2]").is_err());
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_minify_bytes(input: &[u8]) -> Result<Vec<u8>, JsonError> {
+    match CString::new(input) {
+        Ok(c_str) => {
+            let mut buffer = c_str.into_bytes_with_nul();
+            unsafe { cJSON_Minify(buffer.as_mut_ptr() as *mut c_char) };
+            let length = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+            buffer.truncate(length);
+            Ok(buffer)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+// generate the (formatted or unformatted) string representation of a Json item as raw bytes
+fn print_bytes(item: *mut Json, pretty: bool) -> Result<Vec<u8>, JsonError> {
+    let c_str = unsafe {
+        if pretty {
+            cJSON_Print(item as *const cJSON)
+        } else {
+            cJSON_PrintUnformatted(item as *const cJSON)
+        }
+    };
+    if c_str.is_null() {
+        return Err(JsonError::PrintError);
+    }
+    Ok(Json::take_printed_bytes(c_str))
+}
+
+/// Append the string representation of a Json item to a byte buffer, keeping what is already in
+/// it.
+///
+/// Handy for building newline-delimited JSON streams. The output is copied byte for byte, so string
+/// values that are not valid UTF-8 are preserved.
+///
+/// Args:
+/// - `item: *mut Json` - Mutable pointer to the Json item to be printed.
+/// - `out: &mut Vec<u8>` - The buffer to append to.
+/// - `pretty: bool` - Whether or not to have the output formatted/pretty-printed.
+///
+/// Returns:
+/// - `Ok(())` - if the string representation was appended.
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
+/// - `Err(JsonError::PrintError)` - if the string generation fails (`out` is left untouched).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let first = cjson_parse_json(r#"{"id":1}"#.to_string()).unwrap();
+///     let second = cjson_parse_json(r#"{"id":2}"#.to_string()).unwrap();
+///
+///     let mut stream = Vec::new();
+///     cjson_append_bytes(first, &mut stream, false).unwrap();
+///     stream.push(b'\n');
+///     cjson_append_bytes(second, &mut stream, false).unwrap();
+///     stream.push(b'\n');
+///     assert_eq!(stream, b"{\"id\":1}\n{\"id\":2}\n".to_vec());
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_append_bytes(
+    item: *mut Json,
+    out: &mut Vec<u8>,
+    pretty: bool,
+) -> Result<(), JsonError> {
+    if item.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    out.extend_from_slice(&print_bytes(item, pretty)?);
+    Ok(())
+}
+
+/// Write the string representation of a Json item to a `std::fmt::Write` sink, eg. a String the
+/// caller already owns.
+///
+/// Args:
+/// - `item: *mut Json` - Mutable pointer to the Json item to be printed.
+/// - `w: &mut W` - The sink to write to.
+/// - `pretty: bool` - Whether or not to have the output formatted/pretty-printed.
+///
+/// Returns:
+/// - `Ok(())` - if the string representation was written.
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
+/// - `Err(JsonError::PrintError)` - if the string generation fails.
+/// - `Err(JsonError::Utf8Error(Utf8Error))` - if the output is not valid UTF-8.
+/// - `Err(JsonError::FmtError(fmt::Error))` - if the sink reports an error.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_parse_json(r#"{"name":"Nemuel","age":20}"#.to_string()).unwrap();
+///
+///     let mut out = String::with_capacity(64);
+///     out.push_str("payload: ");
+///     cjson_print_to_fmt(json, &mut out, false).unwrap();
+///     assert_eq!(out, r#"payload: {"name":"Nemuel","age":20}"#);
+///     assert_eq!(out.capacity(), 64);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_print_to_fmt<W: std::fmt::Write>(
+    item: *mut Json,
+    w: &mut W,
+    pretty: bool,
+) -> Result<(), JsonError> {
+    if item.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    let printed = print_bytes(item, pretty)?;
+    match std::str::from_utf8(&printed) {
+        Ok(printed) => w.write_str(printed).map_err(JsonError::FmtError),
+        Err(err) => Err(JsonError::Utf8Error(err)),
+    }
+}
+
+/// Print the formatted string representation of a Json item to stderr and hand the item back, so
+/// it can be used inline like `dbg!`.
+///
+/// A null pointer is printed as `<null>`, and an item that cannot be printed as the error message in
+/// angle brackets.
+///
+/// Args:
+/// - `item: *mut Json` - Mutable pointer to the Json item to be printed.
+///
+/// Returns:
+/// - `*mut Json` - `item` itself.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     if std::env::var_os("CJSON_DBG_CHILD").is_some() {
+///         let json = cjson_dbg(cjson_parse_json(r#"{"name":"Nemuel"}"#.to_string()).unwrap());
+///         assert!(json.is_type_object());
+///         assert!(cjson_dbg(std::ptr::null_mut()).is_null());
+///         return;
+///     }
+///
+///     // run this example again in a child process to capture its stderr
+///     let output = std::process::Command::new(std::env::current_exe().unwrap())
+///         .env("CJSON_DBG_CHILD", "1")
+///         .output()
+///         .unwrap();
+///     assert!(output.status.success());
+///     assert_eq!(String::from_utf8(output.stderr).unwrap(), "{\n\t\"name\":\t\"Nemuel\"\n}\n<null>\n");
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_dbg(item: *mut Json) -> *mut Json {
+    match item.print() {
+        Ok(printed) => eprintln!("{}", printed),
+        Err(JsonError::NullPointer) => eprintln!("<null>"),
+        Err(err) => eprintln!("<{}>", err),
+    }
+    item
+}
+
+/// Get the length in bytes of the string representation of a Json item, eg. to pre-size a response
+/// buffer or enforce a payload limit.
+///
+/// Args:
+/// - `item: *mut Json` - Mutable pointer to the Json item to be measured.
+/// - `pretty: bool` - Whether or not to measure the formatted/pretty-printed output.
+///
+/// Returns:
+/// - `Ok(usize)` - the number of bytes (excluding the trailing NUL) the item serializes to.
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
+/// - `Err(JsonError::PrintError)` - if the string generation fails.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_parse_json(r#"{"name":"Nemuel","tags":["é",1,true]}"#.to_string()).unwrap();
+///
+///     assert_eq!(cjson_serialized_len(json, true).unwrap(), json.print().unwrap().len());
+///     assert_eq!(
+///         cjson_serialized_len(json, false).unwrap(),
+///         json.print_unformatted().unwrap().len()
+///     );
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_serialized_len(item: *mut Json, pretty: bool) -> Result<usize, JsonError> {
+    if item.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    Ok(print_bytes(item, pretty)?.len())
+}
+
+/// Generate the unformatted string representation of a Json item as raw bytes, eg. to write it
+/// straight to a socket.
+///
+/// The bytes are copied out of the buffer cJSON prints into in one go, without checking that they
+/// are valid UTF-8, and that buffer is then freed.
+///
+/// Args:
+/// - `item: *mut Json` - Mutable pointer to the Json item to be printed.
+///
+/// Returns:
+/// - `Ok(Vec<u8>)` - the bytes of the string representation (without a trailing null byte).
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
+/// - `Err(JsonError::PrintError)` - if the string generation fails.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_parse_json(r#"{"name":"Nemuel","tags":["a",1,true]}"#.to_string()).unwrap();
+///
+///     let bytes = cjson_print_unformatted_bytes(json).unwrap();
+///     assert_eq!(bytes, json.print_unformatted().unwrap().into_bytes());
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_print_unformatted_bytes(item: *mut Json) -> Result<Vec<u8>, JsonError> {
+    if item.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    print_bytes(item, false)
+}
+
+/// Parse a JSON string into a Json object.
+///
+/// Args:
+/// - `value: String`: The JSON string to be parsed. Providing an empty string will result in
+/// JsonError::EmptyStringError.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - if the parsing happens successfully.
+/// - `Err(JsonError::EmptyStringError)` - if the provided `value` string is empty (can't parse an
+/// empty string).
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let value  = "{\"name\":\"Nemuel\", \"age\":20}".to_string();
+///     match cjson_parse_json(value) {
+///         Ok(json) => println!("{}", json.print().unwrap()),
+///         Err(err) => eprintln!("{}", err),
+///     }
+/// }
+/// ```
+///
+/// Output:
+/// ```json
+/// {
+///     "name": "Nemuel",
+///     "age":  20
+/// }
+/// ```
+pub fn cjson_parse_json(value: String) -> Result<*mut Json, JsonError> {
+    if value.is_empty() {
+        return Err(JsonError::EmptyStringError);
+    }
+
+    match CString::new(value) {
+        Ok(c_str) => {
+            let json = unsafe { cJSON_Parse(c_str.as_ptr()) };
+            if json.is_null() {
+                Err(JsonError::ParseError)
+            } else {
+                Ok(json as *mut Json)
+            }
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Parse a JSON string into a Json object, treating input made only of whitespace as empty.
+///
+/// Args:
+/// - `value: &str`: The JSON string to be parsed (leading and trailing whitespace is ignored).
+///
+/// Returns:
+/// - `Ok(*mut Json)` - if the parsing happens successfully.
+/// - `Err(JsonError::EmptyStringError)` - if `value` is empty or only contains whitespace.
+/// - `Err(JsonError::ParseError)` - if the string is not valid JSON.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     assert!(matches!(cjson_parse_trimmed(""), Err(JsonError::EmptyStringError)));
+///     assert!(matches!(cjson_parse_trimmed("   "), Err(JsonError::EmptyStringError)));
+///
+///     let json = cjson_parse_trimmed("  {}  ").unwrap();
+///     assert_eq!(json.is_type_object(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_parse_trimmed(value: &str) -> Result<*mut Json, JsonError> {
+    cjson_parse_json(value.trim().to_string())
+}
+
+/// Parse a specific length of a JSON string into a Json object.
+///
+/// Args:
+/// - `value: String`: The JSON string to be parsed. Providing an empty string will result in
+/// JsonError::EmptyStringError.
+/// - `buffer_length: usize`: Length of the JSON string to be parsed.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - if the parsing happens successfully.
+/// - `Err(JsonError::EmptyStringError)` - if the provided `value` string is empty (can't parse an
+/// empty string).
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let value = "{\"rps\":500} more text".to_string();
+///     match cjson_parse_json_with_length(value, 11) {
+///         Ok(json) => println!("{}", json.print().unwrap()),
+///         Err(err) => eprintln!("{}", err),
+///     }
+/// }
+/// ```
+///
+/// Output:
+/// ```json
+/// {
+///     "rps": 500
+/// }
+/// ```
+pub fn cjson_parse_json_with_length(
+    value: String,
+    buffer_length: usize,
+) -> Result<*mut Json, JsonError> {
+    if value.is_empty() {
+        return Err(JsonError::EmptyStringError);
+    }
+
+    match CString::new(value) {
+        Ok(c_str) => {
+            let json = unsafe { cJSON_ParseWithLength(c_str.as_ptr(), buffer_length) };
+            if json.is_null() {
+                Err(JsonError::ParseError)
+            } else {
+                Ok(json as *mut Json)
+            }
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Parse a JSON string into a Json object (with additional options).
+///
+/// Args:
+/// - `value: String`: The JSON string to be parsed. Providing an empty string will result in
+/// JsonError::EmptyStringError.
+/// - `return_parse_end: *mut *const char` - Mutable pointer to constant character pointer that will
+/// indicate where parsing ended.
+/// - `require_null_terminated: bool` - Boolean value specifying whether or not the JSON string should be
+/// null terminated.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - if the parsing happens successfully.
+/// - `Err(JsonError::EmptyStringError)` - if the provided `value` string is empty (can't parse an
+/// empty string).
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+/// use core::ffi::c_char;
+///
+/// fn main() {
+///     let value = "{\"rps\":500}";
+///     let mut return_parse_end: *const c_char = std::ptr::null_mut();
+///     match cjson_parse_json_with_opts(value, &mut return_parse_end, false) {
+///         Ok(json) => {
+///             println!("{}", json.print().unwrap());
+///         }
+///         Err(err) => eprintln!("{}", err),
+///     }
+/// }
+/// ```
+///
+/// Output:
+/// ```json
+/// {
+///     "rps": 500
+/// }
+/// ```
+pub fn cjson_parse_json_with_opts(
+    value: &str,
+    return_parse_end: &mut *const c_char,
+    require_null_terminated: bool,
+) -> Result<*mut Json, JsonError> {
+    if value.is_empty() {
+        return Err(JsonError::EmptyStringError);
+    }
+
+    match CString::new(value) {
+        Ok(c_str) => {
+            let json = unsafe {
+                cJSON_ParseWithOpts(
+                    c_str.as_ptr(),
+                    return_parse_end as *mut *const i8,
+                    if require_null_terminated { 1 } else { 0 },
+                )
+            };
+            if json.is_null() {
+                Err(JsonError::ParseError)
+            } else {
+                Ok(json as *mut Json)
+            }
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Parse a specific length of a JSON string into a Json object (with additional options).
+///
+/// Args:
+/// - `value: String`: The JSON string to be parsed. Providing an empty string will result in
+/// JsonError::EmptyStringError.
+/// - `buffer_length: usize`: Length of the JSON string to be parsed.
+/// - `return_parse_end: *mut *const char` - Mutable pointer to constant character pointer that will
+/// indicate where parsing ended.
+/// - `require_null_terminated: bool` - Boolean value specifying whether or not the JSON string should be
+/// null terminated.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - if the parsing happens successfully.
+/// - `Err(JsonError::EmptyStringError)` - if the provided `value` string is empty (can't parse an
+/// empty string).
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+/// use core::ffi::c_char;
+///
+/// fn main() {
+///     let value = "{\"rps\":500}";
+///     let mut return_parse_end: *const c_char = std::ptr::null_mut();
+///     match cjson_parse_json_with_length_opts(value, 11, &mut return_parse_end, false) {
+///         Ok(json) => {
+///             println!("{}", json.print().unwrap());
+///         }
+///         Err(err) => eprintln!("{}", err),
+///     }
+/// }
+/// ```
+///
+/// Output:
+/// ```json
+/// {
+///     "rps": 500
+/// }
+/// ```
+pub fn cjson_parse_json_with_length_opts(
+    value: &str,
+    buffer_length: usize,
+    return_parse_end: &mut *const c_char,
+    require_null_terminated: bool,
+) -> Result<*mut Json, JsonError> {
+    if value.is_empty() {
+        return Err(JsonError::EmptyStringError);
+    }
+
+    match CString::new(value) {
+        Ok(c_str) => {
+            let json = unsafe {
+                cJSON_ParseWithLengthOpts(
+                    c_str.as_ptr(),
+                    buffer_length,
+                    return_parse_end as *mut *const i8,
+                    if require_null_terminated { 1 } else { 0 },
+                )
+            };
+            if json.is_null() {
+                Err(JsonError::ParseError)
+            } else {
+                Ok(json as *mut Json)
+            }
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Event emitted by `cjson_parse_events` while walking a JSON document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseEvent {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    Key(String),
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Null,
+}
+
+// emit the events describing a Json item (and everything nested in it) in document order
+fn emit_parse_events<F: FnMut(ParseEvent)>(item: *mut Json, handler: &mut F) {
+    let lossy = |string: *mut i8| match unsafe { string.as_ref() } {
+        Some(string) => unsafe { CStr::from_ptr(string) }
+            .to_string_lossy()
+            .into_owned(),
+        None => String::new(),
+    };
+
+    if item.is_type_object() {
+        handler(ParseEvent::StartObject);
+        for member in child_items(item) {
+            handler(ParseEvent::Key(lossy(unsafe { (*member).string })));
+            emit_parse_events(member, handler);
+        }
+        handler(ParseEvent::EndObject);
+    } else if item.is_type_array() {
+        handler(ParseEvent::StartArray);
+        for child in child_items(item) {
+            emit_parse_events(child, handler);
+        }
+        handler(ParseEvent::EndArray);
+    } else if item.is_type_number() {
+        handler(ParseEvent::Number(unsafe { (*item).valuedouble }));
+    } else if item.is_type_string() {
+        handler(ParseEvent::Str(lossy(unsafe { (*item).valuestring })));
+    } else if item.is_type_bool() {
+        handler(ParseEvent::Bool(item.is_type_true()));
+    } else {
+        handler(ParseEvent::Null);
+    }
+}
+
+/// Parse JSON bytes and report their content as a sequence of events (SAX style) instead of
+/// handing back a tree.
+///
+/// cJSON has no streaming parser, so the document is still parsed into a temporary tree, which is
+/// walked to emit the events and deleted before returning. The handler is only called once the
+/// whole document has been parsed successfully.
+///
+/// Args:
+/// - `bytes: &[u8]` - The JSON document to be parsed (no null terminator needed).
+/// - `handler: F` - Closure called with every event, in document order.
+///
+/// Returns:
+/// - `Ok(())` - if the document was parsed and all events were emitted.
+/// - `Err(JsonError::EmptyStringError)` - if `bytes` is empty.
+/// - `Err(JsonError::ParseError)` - if the document is not valid JSON.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let mut events = Vec::new();
+///     cjson_parse_events(br#"{"name":"Nemuel","tags":[1,true,null]}"#, |event| events.push(event)).unwrap();
+///     assert_eq!(
+///         events,
+///         vec![
+///             ParseEvent::StartObject,
+///             ParseEvent::Key("name".to_string()),
+///             ParseEvent::Str("Nemuel".to_string()),
+///             ParseEvent::Key("tags".to_string()),
+///             ParseEvent::StartArray,
+///             ParseEvent::Number(1.0),
+///             ParseEvent::Bool(true),
+///             ParseEvent::Null,
+///             ParseEvent::EndArray,
+///             ParseEvent::EndObject,
+///         ]
+///     );
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_parse_events<F: FnMut(ParseEvent)>(
+    bytes: &[u8],
+    mut handler: F,
+) -> Result<(), JsonError> {
+    if bytes.is_empty() {
+        return Err(JsonError::EmptyStringError);
+    }
+
+    let json = unsafe { cJSON_ParseWithLength(bytes.as_ptr() as *const c_char, bytes.len()) };
+    if json.is_null() {
+        return Err(JsonError::ParseError);
+    }
+    emit_parse_events(json as *mut Json, &mut handler);
+    unsafe { cJSON_Delete(json) };
+    Ok(())
+}
+
+/// Parse a JSON string into a Json object, rejecting objects that repeat a key.
+///
+/// The JSON standard allows duplicate keys (cJSON keeps all of them), which many applications
+/// prefer to treat as an error. Keys are compared exactly (case-sensitive).
+///
+/// Args:
+/// - `value: &str` - The JSON string to be parsed.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - if the parsing happens successfully and no object repeats a key.
+/// - `Err(JsonError::EmptyStringError)` - if the provided `value` string is empty.
+/// - `Err(JsonError::ParseError)` - if the string is not valid JSON.
+/// - `Err(JsonError::DuplicateKey(String))` - holding the first repeated key found (the parsed tree
+/// is deleted).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_parse_strict(r#"{"a":1,"b":{"a":2}}"#).unwrap();
+///     assert_eq!(json.is_type_object(), true);
+///
+///     match cjson_parse_strict(r#"{"a":1,"b":{"c":2,"c":3}}"#) {
+///         Err(JsonError::DuplicateKey(key)) => assert_eq!(key, "c"),
+///         _ => panic!("the repeated key should have been rejected"),
+///     }
+///
+///     match cjson_parse_strict(r#"{"a":{"x":1,"x":2},"b":{"y":1,"y":2}}"#) {
+///         Err(JsonError::DuplicateKey(key)) => assert_eq!(key, "x"),
+///         _ => panic!("the first repeated key should have been reported"),
+///     }
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_parse_strict(value: &str) -> Result<*mut Json, JsonError> {
+    if value.is_empty() {
+        return Err(JsonError::EmptyStringError);
+    }
+
+    let json =
+        unsafe { cJSON_ParseWithLength(value.as_ptr() as *const c_char, value.len()) } as *mut Json;
+    if json.is_null() {
+        return Err(JsonError::ParseError);
+    }
+
+    let mut pending = vec![json];
+    while let Some(item) = pending.pop() {
+        let children = child_items(item);
+        if item.is_type_object() {
+            let mut keys = std::collections::HashSet::new();
+            for &member in &children {
+                let key = match unsafe { (*member).string.as_ref() } {
+                    Some(key) => unsafe { CStr::from_ptr(key) }.to_bytes(),
+                    None => continue,
+                };
+                if !keys.insert(key) {
+                    let key = String::from_utf8_lossy(key).into_owned();
+                    unsafe { cJSON_Delete(json as *mut cJSON) };
+                    return Err(JsonError::DuplicateKey(key));
+                }
+            }
+        }
+        // pushed in reverse so that subtrees are checked in document order
+        pending.extend(children.into_iter().rev());
+    }
+    Ok(json)
+}
+
+/// Parse a JSON string into a Json object, rejecting anything but whitespace after the value.
+///
+/// `cjson_parse_json` stops at the end of the first complete value, so eg. `{"a":1} garbage` is
+/// accepted there; this is the equivalent of cJSON's `require_null_terminated` option, with a
+/// dedicated error for the trailing data.
+///
+/// Args:
+/// - `value: &str` - The JSON string to be parsed.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - if the parsing happens successfully.
+/// - `Err(JsonError::EmptyStringError)` - if the provided `value` string is empty.
+/// - `Err(JsonError::ParseError)` - if the string does not start with a valid JSON value.
+/// - `Err(JsonError::TrailingData(usize))` - holding the byte offset into `value` of the first
+/// character after the value (and the whitespace following it).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_parse_exact("{\"a\":1}  \n").unwrap();
+///     assert_eq!(json.print_unformatted().unwrap(), r#"{"a":1}"#);
+///
+///     assert!(cjson_parse_json(r#"{"a":1} garbage"#.to_string()).is_ok());
+///     assert!(matches!(cjson_parse_exact(r#"{"a":1} garbage"#), Err(JsonError::TrailingData(8))));
+///     assert!(matches!(cjson_parse_exact(r#"{"a":}"#), Err(JsonError::ParseError)));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_parse_exact(value: &str) -> Result<*mut Json, JsonError> {
+    if value.is_empty() {
+        return Err(JsonError::EmptyStringError);
+    }
+
+    let (json, end) = parse_with_end(value);
+    if json.is_null() {
+        return Err(JsonError::ParseError);
+    }
+
+    match trailing_data(value, end) {
+        Some(offset) => {
+            unsafe { cJSON_Delete(json) };
+            Err(JsonError::TrailingData(offset))
+        }
+        None => Ok(json as *mut Json),
+    }
+}
+
+// parse the value at the start of `value`, returning the root (null on failure) along with the
+// byte offset at which cJSON stopped, ie. the end of the value or the position of the error
+fn parse_with_end(value: &str) -> (*mut cJSON, usize) {
+    let start = value.as_ptr() as *const c_char;
+    let mut parse_end: *const c_char = std::ptr::null();
+    let json = unsafe { cJSON_ParseWithLengthOpts(start, value.len(), &mut parse_end, 0) };
+    let end = if parse_end.is_null() {
+        0
+    } else {
+        (parse_end as usize - start as usize).min(value.len())
+    };
+    (json, end)
+}
+
+// byte offset of the first non-whitespace byte of `value` at or after `end`, if any (cJSON treats
+// every byte up to and including the space as whitespace)
+fn trailing_data(value: &str, end: usize) -> Option<usize> {
+    value.as_bytes()[end..]
+        .iter()
+        .position(|&b| b > b' ')
+        .map(|trailing| end + trailing)
+}
+
+/// Parse a JSON string into a Json object, reporting where parsing failed.
+///
+/// The offset is taken from the parse end reported by cJSON for this call (the position
+/// `cjson_get_error_ptr` points to), so it stays correct when other threads parse concurrently.
+///
+/// Args:
+/// - `value: &str` - The JSON string to be parsed.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - if the parsing happens successfully.
+/// - `Err((JsonError::EmptyStringError, 0))` - if the provided `value` string is empty.
+/// - `Err((JsonError::ParseError, usize))` - holding the byte offset into `value` at which parsing
+/// failed.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_parse_with_error(r#"{"name":"Nemuel"}"#).unwrap();
+///     assert_eq!(json.is_type_object(), true);
+///
+///     match cjson_parse_with_error(r#"{"name":"Nemuel",}"#) {
+///         Err((JsonError::ParseError, offset)) => assert_eq!(offset, 17),
+///         _ => panic!("the trailing comma should have been rejected"),
+///     }
+///     match cjson_parse_with_error("[1, 2, ?]") {
+///         Err((JsonError::ParseError, offset)) => assert_eq!(offset, 7),
+///         _ => panic!("the stray character should have been rejected"),
+///     }
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_parse_with_error(value: &str) -> Result<*mut Json, (JsonError, usize)> {
+    if value.is_empty() {
+        return Err((JsonError::EmptyStringError, 0));
+    }
+
+    let (json, end) = parse_with_end(value);
+    if json.is_null() {
+        return Err((JsonError::ParseError, end));
+    }
+    Ok(json as *mut Json)
+}
+
+/// Convert a byte offset into `input` to a 1-based line and column.
+///
+/// Lines are separated by `\n`, and columns count characters (not bytes) from the start of the
+/// line. An offset past the end of `input` is treated as pointing at its end.
+///
+/// Args:
+/// - `input: &str` - The text the offset refers to.
+/// - `offset: usize` - Byte offset into `input`, e.g. from `cjson_parse_with_error`.
+///
+/// Returns:
+/// - `(usize, usize)` - the line and column of the offset, both starting at 1.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let input = "{\n  \"a\": 1,\n  \"b\": ?\n}";
+///     let offset = input.find('?').unwrap();
+///     assert_eq!(cjson_error_location(input, offset), (3, 8));
+///     assert_eq!(cjson_error_location(input, 0), (1, 1));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_error_location(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input.as_bytes()[..offset.min(input.len())];
+    let line_start = before
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |newline| newline + 1);
+    let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+    // count the bytes starting a UTF-8 sequence, i.e. the characters
+    let column = before[line_start..]
+        .iter()
+        .filter(|&&b| b & 0xC0 != 0x80)
+        .count()
+        + 1;
+    (line, column)
+}
+
+/// Parse a JSON string into a Json object, reporting the line and column at which parsing failed.
+///
+/// Args:
+/// - `value: &str` - The JSON string to be parsed.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - if the parsing happens successfully.
+/// - `Err(JsonError::EmptyStringError)` - if the provided `value` string is empty.
+/// - `Err(JsonError::ParseErrorAt { line, column })` - holding the 1-based position at which
+/// parsing failed.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let input = "{\n  \"name\": \"Nemuel\",\n  \"age\": twenty\n}";
+///     match cjson_parse_with_location(input) {
+///         Err(err @ JsonError::ParseErrorAt { .. }) => {
+///             assert_eq!(err.to_string(), "failed to parse the JSON string at line 3, column 10");
+///         }
+///         _ => panic!("the bare word should have been rejected"),
+///     }
+///
+///     let json = cjson_parse_with_location("[1, 2]").unwrap();
+///     assert_eq!(json.is_type_array(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_parse_with_location(value: &str) -> Result<*mut Json, JsonError> {
+    cjson_parse_with_error(value).map_err(|(err, offset)| match err {
+        JsonError::ParseError => {
+            let (line, column) = cjson_error_location(value, offset);
+            JsonError::ParseErrorAt { line, column }
+        }
+        err => err,
+    })
+}
+
+/// Parse newline-delimited JSON (NDJSON), one document per line.
+///
+/// Blank lines are skipped and a trailing `\r` on a line is ignored. Each line must hold exactly
+/// one document: anything but whitespace after it is an error.
+///
+/// Args:
+/// - `input: &str` - The NDJSON payload.
+///
+/// Returns:
+/// - `Ok(Vec<*mut Json>)` - the root of each document, in order (each to be deleted by the caller).
+/// - `Err(JsonError::ParseErrorAt { line, column })` - for the first line that is not valid JSON or
+/// has data after its document, with `line` counted within `input` (the documents parsed so far are
+/// deleted).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let roots = cjson_parse_ndjson("{\"id\":1}\n\n{\"id\":2}\r\n[3]\n").unwrap();
+///     assert_eq!(roots.len(), 3);
+///     assert_eq!(roots[1].print_unformatted().unwrap(), r#"{"id":2}"#);
+///     assert_eq!(roots[2].is_type_array(), true);
+///
+///     match cjson_parse_ndjson("{\"id\":1}\n{\"id\":}\n{\"id\":3}") {
+///         Err(JsonError::ParseErrorAt { line, column }) => assert_eq!((line, column), (2, 7)),
+///         _ => panic!("the second line should have been rejected"),
+///     }
+///
+///     match cjson_parse_ndjson("[1]\n{\"a\":1}{\"b\":2}\n") {
+///         Err(JsonError::ParseErrorAt { line, column }) => assert_eq!((line, column), (2, 8)),
+///         _ => panic!("the second document on line 2 should have been rejected"),
+///     }
+///     match cjson_parse_ndjson("[1] trailing") {
+///         Err(JsonError::ParseErrorAt { line, column }) => assert_eq!((line, column), (1, 5)),
+///         _ => panic!("the trailing word should have been rejected"),
+///     }
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_parse_ndjson(input: &str) -> Result<Vec<*mut Json>, JsonError> {
+    let mut roots = Vec::new();
+    for (index, line) in input.split('\n').enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (root, end) = parse_with_end(line);
+        let error = if root.is_null() {
+            Some(end)
+        } else {
+            trailing_data(line, end)
+        };
+        match error {
+            None => roots.push(root as *mut Json),
+            Some(offset) => {
+                unsafe { cJSON_Delete(root) };
+                for root in roots {
+                    unsafe { cJSON_Delete(root as *mut cJSON) };
+                }
+                return Err(JsonError::ParseErrorAt {
+                    line: index + 1,
+                    column: cjson_error_location(line, offset).1,
+                });
+            }
+        }
+    }
+    Ok(roots)
+}
+
+/// Parse back-to-back JSON documents (with or without whitespace between them).
+///
+/// Args:
+/// - `input: &str` - The concatenated JSON documents.
+///
+/// Returns:
+/// - `Ok(Vec<*mut Json>)` - the root of each document, in order (each to be deleted by the caller).
+/// An input made only of whitespace gives an empty Vec.
+/// - `Err(JsonError::ParseErrorAt { line, column })` - for the first document that is not valid
+/// JSON, with the position counted within `input` (the documents parsed so far are deleted).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let roots = cjson_parse_stream(r#"{"a":1}{"b":2}"#).unwrap();
+///     assert_eq!(roots.len(), 2);
+///     assert_eq!(roots[0].print_unformatted().unwrap(), r#"{"a":1}"#);
+///     assert_eq!(roots[1].print_unformatted().unwrap(), r#"{"b":2}"#);
+///
+///     assert_eq!(cjson_parse_stream(" [1] \n 2 \"three\"\n").unwrap().len(), 3);
+///     assert!(matches!(cjson_parse_stream("{} {"), Err(JsonError::ParseErrorAt { line: 1, .. })));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_parse_stream(input: &str) -> Result<Vec<*mut Json>, JsonError> {
+    let mut roots = Vec::new();
+    let mut offset = 0;
+    loop {
+        let rest = &input[offset..];
+        // skip what cJSON treats as whitespace (any control character or space)
+        let skipped = rest.len() - rest.trim_start_matches(|c: char| c <= ' ').len();
+        offset += skipped;
+        if offset == input.len() {
+            break;
+        }
+
+        let start = input[offset..].as_ptr() as *const c_char;
+        let mut parse_end: *const c_char = std::ptr::null();
+        let root =
+            unsafe { cJSON_ParseWithLengthOpts(start, input.len() - offset, &mut parse_end, 0) };
+        let end = if parse_end.is_null() {
+            offset
+        } else {
+            offset + (parse_end as usize - start as usize)
+        };
+        if root.is_null() {
+            for root in roots {
+                unsafe { cJSON_Delete(root as *mut cJSON) };
+            }
+            let (line, column) = cjson_error_location(input, end);
+            return Err(JsonError::ParseErrorAt { line, column });
+        }
+
+        roots.push(root as *mut Json);
+        offset = end.max(offset + 1).min(input.len());
+    }
+    Ok(roots)
+}
+
+/// Get error message associated with the last parsing operation that failed.
+///
+/// Returns:
+/// - `Some(String)` - if an error message exists.
+/// - `None` - if there is no error message.
+pub fn cjson_get_error_ptr() -> Option<String> {
+    let c_str = unsafe { cJSON_GetErrorPtr() };
+    if !c_str.is_null() {
+        let c_str_ref = unsafe { CStr::from_ptr(c_str) };
+        Some(c_str_ref.to_str().unwrap_or_default().to_string())
+    } else {
+        None
+    }
+}
+
+/// Create Json item of type `Raw`.
+///
+/// Args:
+/// - `raw: String` - Raw string (JSON or otherwise).
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the created Json item of type `Raw`.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_raw("\"count\": 5".to_string()).unwrap();
+///     println!("{}", json.print().unwrap()); // output: "count": 5
+/// }
+/// ```
+pub fn cjson_create_raw(raw: String) -> Result<*mut Json, JsonError> {
+    match CString::new(raw) {
+        Ok(c_str) => {
+            let json = unsafe { cJSON_CreateRaw(c_str.as_ptr()) as *mut Json };
+            Ok(json)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Escape a string for use inside a JSON string literal, the same way cJSON prints string values.
+///
+/// Quotes, backslashes and control characters are escaped (`\uXXXX` for the control characters
+/// without a short form); everything else, including non-ASCII characters, is kept as is. The
+/// surrounding quotes are not added.
+///
+/// Args:
+/// - `s: &str` - The string to be escaped.
+///
+/// Returns:
+/// - `String` - the escaped string.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let escaped = cjson_escape_string("say \"hi\"\nbye\u{1}");
+///     assert_eq!(escaped, r#"say \"hi\"\nbye\u0001"#);
+///
+///     let json = cjson_create_raw(format!("{{\"message\":\"{}\"}}", escaped)).unwrap();
+///     let parsed = cjson_parse_json(json.print().unwrap()).unwrap();
+///     let message = cjson_get_object_item(parsed, "message").unwrap();
+///     assert_eq!(cjson_get_string_value(message).unwrap(), "say \"hi\"\nbye\u{1}");
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_escape_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    escape_into(s, &mut escaped);
+    escaped
+}
+
+/// Reverse the escaping of the content of a JSON string literal.
+///
+/// Handles `\"`, `\\`, `\/`, `\b`, `\f`, `\n`, `\r`, `\t` and `\uXXXX` (including surrogate pairs
+/// for characters outside the Basic Multilingual Plane). The surrounding quotes must not be included.
+///
+/// Args:
+/// - `s: &str` - The escaped string.
+///
+/// Returns:
+/// - `Ok(String)` - the unescaped string.
+/// - `Err(JsonError::ParseError)` - if `s` contains an unknown or truncated escape sequence, or an
+/// unpaired surrogate.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     assert_eq!(cjson_unescape_string(r#"\u0041BC"#).unwrap(), "ABC");
+///     assert_eq!(cjson_unescape_string(r#"a\"b\\c\nd\/"#).unwrap(), "a\"b\\c\nd/");
+///     assert_eq!(cjson_unescape_string(r#"\ud83d\ude00"#).unwrap(), "\u{1F600}");
+///     assert!(matches!(cjson_unescape_string(r#"\x41"#), Err(JsonError::ParseError)));
+///     assert!(matches!(cjson_unescape_string(r#"\ud83d"#), Err(JsonError::ParseError)));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_unescape_string(s: &str) -> Result<String, JsonError> {
+    let hex4 = |chars: &mut std::str::Chars| -> Result<u32, JsonError> {
+        let digits: String = chars.take(4).collect();
+        if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(JsonError::ParseError);
+        }
+        u32::from_str_radix(&digits, 16).map_err(|_| JsonError::ParseError)
+    };
+
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        let c = match chars.next() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                let mut code = hex4(&mut chars)?;
+                if (0xD800..0xDC00).contains(&code) {
+                    // a high surrogate has to be followed by an escaped low surrogate
+                    if chars.next() != Some('\\') || chars.next() != Some('u') {
+                        return Err(JsonError::ParseError);
+                    }
+                    let low = hex4(&mut chars)?;
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return Err(JsonError::ParseError);
+                    }
+                    code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                }
+                char::from_u32(code).ok_or(JsonError::ParseError)?
+            }
+            _ => return Err(JsonError::ParseError),
+        };
+        unescaped.push(c);
+    }
+    Ok(unescaped)
+}
+
+/// Create Json item of type `Null`.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Null`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_null();
+///     assert_eq!(json.is_type_null(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_null() -> *mut Json {
+    unsafe { cJSON_CreateNull() as *mut Json }
+}
+
+/// Create a new JSON object (instance of the Json struct).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::{cjson_create_object, Json};
+///
+/// fn main() {
+///     let json: *mut Json = cjson_create_object();
+/// }
+/// ```
+pub fn cjson_create_object() -> *mut Json {
+    unsafe { cJSON_CreateObject() as *mut Json }
+}
+
+/// Create Json item of type `True`.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `True`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_true();
+///     assert_eq!(json.is_type_true(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_true() -> *mut Json {
+    unsafe { cJSON_CreateTrue() as *mut Json }
+}
+
+/// Create Json item of type `False`.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `False`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_false();
+///     assert_eq!(json.is_type_false(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_false() -> *mut Json {
+    unsafe { cJSON_CreateFalse() as *mut Json }
+}
+
+/// Create Json item of type `Bool`.
+///
+/// Args:
+/// - `boolean: bool`: Boolean value for the Json item to create (true or false).
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Bool`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_bool(true);
+///     assert_eq!(json.is_type_bool(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_bool(boolean: bool) -> *mut Json {
+    unsafe { cJSON_CreateBool(if boolean { 1 } else { 0 }) as *mut Json }
+}
+
+/// Create Json item of type `Number`.
+///
+/// NaN and infinite values are accepted as they are, but cJSON prints them as `null`, so they are
+/// silently lost on serialization. Use `cjson_create_number_checked` to reject them instead.
+///
+/// Args:
+/// - `num: f64`: Numeric value for the Json item to create.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Number`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_number(254.0);
+///     assert_eq!(json.is_type_number(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_number(num: f64) -> *mut Json {
+    unsafe { cJSON_CreateNumber(num) as *mut Json }
+}
+
+/// Create Json item of type `Number`, rejecting values that JSON cannot represent.
+///
+/// Args:
+/// - `num: f64`: Numeric value for the Json item to create.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the created Json item of type `Number`.
+/// - `Err(JsonError::NonFiniteNumber)` - if `num` is NaN, positive or negative infinity.
+/// - `Err(JsonError::NullPointer)` - if the item could not be allocated.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_number_checked(254.5).unwrap();
+///     assert_eq!(cjson_get_number_value(json).unwrap(), 254.5);
+///
+///     assert!(matches!(cjson_create_number_checked(f64::NAN), Err(JsonError::NonFiniteNumber)));
+///     assert!(matches!(cjson_create_number_checked(f64::INFINITY), Err(JsonError::NonFiniteNumber)));
+///     assert!(matches!(cjson_create_number_checked(f64::NEG_INFINITY), Err(JsonError::NonFiniteNumber)));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_number_checked(num: f64) -> Result<*mut Json, JsonError> {
+    if !num.is_finite() {
+        return Err(JsonError::NonFiniteNumber);
+    }
+
+    let item = cjson_create_number(num);
+    if item.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+    Ok(item)
+}
+
+/// Create a Json item that always prints as a floating-point number, eg. `5.0` instead of `5`.
+///
+/// cJSON prints any integral number without a fractional part and keeps no state that could
+/// change that, so unlike `cjson_create_number` this creates an item of type `Raw` holding the
+/// number's text with `.0` appended when needed. It prints as a JSON number and parses back into
+/// a `Number`, but `is_type_number` is false for it and its value has to be read from the text.
+/// NaN and infinite values cannot be written as JSON numbers: for those an item of type `Number`
+/// is created, which prints as `null`.
+///
+/// Args:
+/// - `num: f64`: Numeric value for the Json item to create.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item (null if the allocation fails).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let float = cjson_create_number_as_float(5.0);
+///     let number = cjson_create_number(5.0);
+///     assert_eq!(float.print().unwrap(), "5.0");
+///     assert_eq!(number.print().unwrap(), "5");
+///
+///     assert_eq!(cjson_create_number_as_float(2.5).print().unwrap(), "2.5");
+///     assert_eq!(cjson_create_number_as_float(1e300).print().unwrap(), "1e+300");
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_number_as_float(num: f64) -> *mut Json {
+    if !num.is_finite() {
+        return cjson_create_number(num);
+    }
+
+    let item = cjson_create_number(num);
+    let mut text = match unsafe { item.as_ref() } {
+        Some(json) => format_number(json),
+        None => return std::ptr::null_mut(),
+    };
+    unsafe { cJSON_Delete(item as *mut cJSON) };
+    if !text.contains(['.', 'e', 'E']) {
+        text.push_str(".0");
+    }
+
+    // a formatted number never contains a null byte
+    let c_text = CString::new(text).unwrap_or_default();
+    unsafe { cJSON_CreateRaw(c_text.as_ptr()) as *mut Json }
+}
+
+/// Set the number value for a Json item of type `Number` to the specified value.
+///
+/// Args:
+/// - `item: *mut Json` - Mutable pointer to Json item of type `Number` whose number value is to be set.
+/// - `number: f64` - The number value to set for the Json item of type `Number`.
+///
+/// Returns:
+/// - `Ok(f64)` - if the operation happens successfully.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the provided Json item is not of type `Number`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let number_item = cjson_create_number(1.0);
+///     assert_eq!(cjson_get_number_value(number_item).unwrap(), 1.0);
+///
+///     let new_number_value = cjson_set_number_helper(number_item, 2.0).unwrap();
+///     assert_eq!(new_number_value, 2.0);
+///
+///     assert_eq!(cjson_get_number_value(number_item).unwrap(), 2.0);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_set_number_helper(object: *mut Json, number: f64) -> Result<f64, JsonError> {
+    if !object.is_type_number() {
+        Err(JsonError::InvalidTypeError(
+            "cannot set number value for a non-number Json item".to_string(),
+        ))
+    } else {
+        Ok(unsafe { cJSON_SetNumberHelper(object as *mut cJSON, number) })
+    }
+}
+
+/// Set the number value for a Json item of type `Number`, reporting whether its integer value
+/// (`valueint`) lost precision.
+///
+/// cJSON stores the number as-is in `valuedouble`, but `valueint` is truncated towards zero and
+/// clamped to the `i32` range.
+///
+/// Args:
+/// - `item: *mut Json` - Mutable pointer to Json item of type `Number` whose number value is to be set.
+/// - `number: f64` - The number value to set for the Json item of type `Number`.
+///
+/// Returns:
+/// - `Ok((f64, bool))` - the value stored by cJSON, and whether the stored `valueint` differs from
+/// `number`.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the provided Json item is not of type `Number`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let number_item = cjson_create_number(1.0);
+///
+///     assert_eq!(cjson_set_number_checked(number_item, 42.0).unwrap(), (42.0, false));
+///     assert_eq!(cjson_set_number_checked(number_item, 3e9 + 0.5).unwrap(), (3e9 + 0.5, true));
+///     assert_eq!(number_item.as_f64(), Some(3e9 + 0.5));
+///     assert_eq!(unsafe { (*number_item).valueint }, i32::MAX);
+///     assert_eq!(cjson_set_number_checked(number_item, -7.25).unwrap(), (-7.25, true));
+///     assert_eq!(unsafe { (*number_item).valueint }, -7);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_set_number_checked(item: *mut Json, number: f64) -> Result<(f64, bool), JsonError> {
+    let stored = cjson_set_number_helper(item, number)?;
+    // cJSON truncates and clamps valueint exactly like a saturating `as` cast
+    let lossy = number as i32 as f64 != number;
+    Ok((stored, lossy))
+}
+
+/// Create Json item of type `String` (copies the string).
+///
+/// Args:
+/// - `string: String`: String value for the Json item to create.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `String`.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_string("Nemuel".to_string()).unwrap();
+///     assert_eq!(json.is_type_string(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_string(string: String) -> Result<*mut Json, JsonError> {
+    match CString::new(string) {
+        Ok(c_str) => {
+            let json = unsafe { cJSON_CreateString(c_str.as_ptr()) as *mut Json };
+            Ok(json)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Set the string value of a Json item of type `String` to the specified value.
+///
+/// Args:
+/// - `item: *mut Json` - Mutable pointer to Json item of type `String` whose string value is to be set.
+/// - `valuestring: &str` - String slice specifying the value to set for the Json item of type `String`.
+///
+/// Returns:
+/// - `Ok(String)` - if the operation happens successfully.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the provided Json item is not of type `String`.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+/// - `Err(JsonError::SetStringFailed)` - if cJSON refuses to set the value (eg. on a string
+/// reference item, whose value it does not own) or fails to allocate it.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let string_item = cjson_create_string("Nemuel".to_string()).unwrap();
+///     assert_eq!(cjson_get_string_value(string_item).unwrap(), "Nemuel");
+///
+///     let new_string_value = cjson_set_value_string(string_item, "Wainaina").unwrap();
+///     assert_eq!(new_string_value, "Wainaina");
+///
+///     assert_eq!(cjson_get_string_value(string_item).unwrap(), "Wainaina");
+///
+///     // flag the item as a string reference (cJSON_IsReference), whose value cJSON does not own
+///     let reference = cjson_create_string("Nemuel".to_string()).unwrap();
+///     unsafe { (*reference).type_ |= 256 };
+///     let result = cjson_set_value_string(reference, "a much longer value");
+///     assert!(matches!(result, Err(JsonError::SetStringFailed)));
+///     assert_eq!(cjson_get_string_value(reference).unwrap(), "Nemuel");
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_set_value_string(object: *mut Json, valuestring: &str) -> Result<String, JsonError> {
+    if !object.is_type_string() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot set string value for a non-string Json item".to_string(),
+        ));
+    }
+
+    match CString::new(valuestring) {
+        Ok(c_str) => {
+            let c_str_ptr = unsafe { cJSON_SetValuestring(object as *mut cJSON, c_str.as_ptr()) };
+            if c_str_ptr.is_null() {
+                return Err(JsonError::SetStringFailed);
+            }
+            let str = unsafe { CStr::from_ptr(c_str_ptr).to_string_lossy().into_owned() };
+            Ok(str)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Create Json item of type `Array`.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_array();
+///     assert_eq!(json.is_type_array(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_array() -> *mut Json {
+    unsafe { cJSON_CreateArray() as *mut Json }
+}
+
+/// Create Json item of type `String`.
+///
+/// It points directly to the string. This means the `valuestring` field of the Json struct will
+/// not be deleted by `cjson_delete`, and you are therefore responsible for its lifetime (useful
+/// for constants).
+///
+/// Args:
+/// - `string: String`: String value for the Json item to create.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `String`.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_string_reference("Nemuel".to_string()).unwrap();
+///     assert_eq!(json.is_type_string(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_string_reference(string: String) -> Result<*mut Json, JsonError> {
+    match CString::new(string) {
+        Ok(c_str) => {
+            let json = unsafe { cJSON_CreateStringReference(c_str.as_ptr()) as *mut Json };
+            Ok(json)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Create Json item of type `Array` that doesn't "own" its content.
+///
+/// Args:
+/// - `child: *mut Json`: Json item of type `Array` to create a reference to.
+///
+/// Returns:
+/// - `*mut Json` - a reference to the provided Json item of type `Array`.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the provided Json item is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let child = cjson_create_array();
+///     let reference = cjson_create_array_reference(child).unwrap();
+///     assert_eq!(reference.is_type_array(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_array_reference(child: *mut Json) -> Result<*mut Json, JsonError> {
+    if !child.is_type_array() {
+        Err(JsonError::InvalidTypeError(
+            "cannot create array reference to a non-array Json item".to_string(),
+        ))
+    } else {
+        let reference = unsafe { cJSON_CreateArrayReference(child as *mut cJSON) as *mut Json };
+        Ok(reference)
+    }
+}
+
+/// Create Json item of type `Array` that doesn't "own" its content.
+///
+/// Args:
+/// - `child: *mut Json`: Json item of type `Array` to create a reference to.
+///
+/// Returns:
+/// - `*mut Json` - a reference to the provided Json item of type `Array`.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the provided Json item is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let child = cjson_create_object();
+///     let reference = cjson_create_object_reference(child).unwrap();
+///     assert_eq!(reference.is_type_object(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_object_reference(child: *mut Json) -> Result<*mut Json, JsonError> {
+    if !child.is_type_object() {
+        Err(JsonError::InvalidTypeError(
+            "cannot create object reference to a non-object Json item".to_string(),
+        ))
+    } else {
+        let reference = unsafe { cJSON_CreateObjectReference(child as *mut cJSON) as *mut Json };
+        Ok(reference)
+    }
+}
+
+/// Create Json item of type `Array` containing integers.
+///
+/// Args:
+/// - `numbers: *const i32` - Pointer to a signed 32-bit integer (start of the numbers array).
+/// - `count: i32` - Number of array elements to include in the `Array` being created (typically just the
+/// size of the `numbers` array).
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` containing integers.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let numbers: [i32; 5] = [1, 2, 3, 4, 5];
+///     let json = cjson_create_int_array(&numbers[0], numbers.len() as i32);
+///     assert_eq!(json.is_type_array(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_int_array(numbers: *const i32, count: i32) -> *mut Json {
+    unsafe { cJSON_CreateIntArray(numbers, count) as *mut Json }
+}
+
+/// Create Json item of type `Array` containing single-precision floating-point values.
+///
+/// Args:
+/// - `numbers: *const i32` - Pointer to a signed single-precision floating-point value (start of the
+/// numbers array).
+/// - `count: i32` - Number of array elements to include in the `Array` being created (typically just the
+/// size of the `numbers` array).
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` containing single-precision
+/// floating-point values.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let numbers: [f32; 5] = [1.0, 2.0, 3.0, 4.0, 5.0];
+///     let json = cjson_create_float_array(&numbers[0], numbers.len() as i32);
+///     assert_eq!(json.is_type_array(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_float_array(numbers: *const f32, count: i32) -> *mut Json {
+    unsafe { cJSON_CreateFloatArray(numbers, count) as *mut Json }
+}
+
+/// Create Json item of type `Array` containing double-precision floating-point values.
+///
+/// Args:
+/// - `numbers: *const i64` - Pointer to a signed double-precision floating-point value (start of the
+/// numbers array).
+/// - `count: i32` - Number of array elements to include in the `Array` being created (typically just the
+/// size of the `numbers` array).
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` containing double-precision
+/// floating-point values.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let numbers: [f64; 5] = [1.0, 2.0, 3.0, 4.0, 5.0];
+///     let json = cjson_create_double_array(&numbers[0], numbers.len() as i32);
+///     assert_eq!(json.is_type_array(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_double_array(numbers: *const f64, count: i32) -> *mut Json {
+    unsafe { cJSON_CreateDoubleArray(numbers, count) as *mut Json }
+}
+
+/// Create Json item of type `Array` containing integers from a slice.
+///
+/// Unlike `cjson_create_int_array`, the element count is taken from the slice itself.
+///
+/// Args:
+/// - `numbers: &[i32]` - The values to include in the `Array`.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` containing integers
+/// (null if the allocation fails or the slice holds more than `i32::MAX` elements).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_int_array_from_slice(&[1, -2, 3]);
+///     assert_eq!(cjson_get_array_size(json).unwrap(), 3);
+///     assert_eq!(cjson_get_number_value(cjson_get_array_item(json, 2).unwrap()).unwrap(), 3.0);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_int_array_from_slice(numbers: &[i32]) -> *mut Json {
+    match i32::try_from(numbers.len()) {
+        Ok(count) => unsafe { cJSON_CreateIntArray(numbers.as_ptr(), count) as *mut Json },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Create Json item of type `Array` containing single-precision floating-point values from a slice.
+///
+/// Unlike `cjson_create_float_array`, the element count is taken from the slice itself.
+///
+/// Args:
+/// - `numbers: &[f32]` - The values to include in the `Array`.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` containing single-precision floating-point values
+/// (null if the allocation fails or the slice holds more than `i32::MAX` elements).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_float_array_from_slice(&[1.5, 2.5, 3.25]);
+///     assert_eq!(cjson_get_array_size(json).unwrap(), 3);
+///     assert_eq!(cjson_get_number_value(cjson_get_array_item(json, 2).unwrap()).unwrap(), 3.25);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_float_array_from_slice(numbers: &[f32]) -> *mut Json {
+    match i32::try_from(numbers.len()) {
+        Ok(count) => unsafe { cJSON_CreateFloatArray(numbers.as_ptr(), count) as *mut Json },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Create Json item of type `Array` containing double-precision floating-point values from a slice.
+///
+/// Unlike `cjson_create_double_array`, the element count is taken from the slice itself.
+///
+/// Args:
+/// - `numbers: &[f64]` - The values to include in the `Array`.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` containing double-precision floating-point values
+/// (null if the allocation fails or the slice holds more than `i32::MAX` elements).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_double_array_from_slice(&[0.1, 0.2, 0.3]);
+///     assert_eq!(cjson_get_array_size(json).unwrap(), 3);
+///     assert_eq!(cjson_get_number_value(cjson_get_array_item(json, 2).unwrap()).unwrap(), 0.3);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_double_array_from_slice(numbers: &[f64]) -> *mut Json {
+    match i32::try_from(numbers.len()) {
+        Ok(count) => unsafe { cJSON_CreateDoubleArray(numbers.as_ptr(), count) as *mut Json },
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Create Json item of type `Array` containing string values.
+///
+/// **Deprecated:** the separate `count` is redundant with the length of `strings`. Use
+/// `cjson_create_string_array_safe`, which takes the count from the slice. A `count` larger than
+/// the slice is clamped to its length.
+///
+/// Args:
+/// - `strings: &[&str]` - Reference to an array of string slices.
+/// - `count: i32` - Number of array elements to include in the `Array` being created (typically just the
+/// size of the `strings` array).
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` containing string values.
+///
+/// Example:
+/// ```rust
+/// #![allow(deprecated)]
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let strings = ["Alice", "Bob", "Chloe"];
+///     let arr = cjson_create_string_array(&strings, strings.len() as i32).unwrap();
+///     assert_eq!(arr.is_type_array(), true);
+///
+///     let clamped = cjson_create_string_array(&strings, 10).unwrap();
+///     assert_eq!(cjson_get_array_size(clamped).unwrap(), 3);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+#[deprecated(note = "use `cjson_create_string_array_safe`, which takes the count from the slice")]
+pub fn cjson_create_string_array(strings: &[&str], count: i32) -> Result<*mut Json, JsonError> {
+    let count = count.min(i32::try_from(strings.len()).unwrap_or(i32::MAX));
+    create_string_array(strings, count)
+}
+
+/// Create Json item of type `Array` containing string values, one per element of `strings`.
+///
+/// Args:
+/// - `strings: &[&str]` - The string values to include in the `Array`.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the created Json item of type `Array` containing string
+/// values.
+/// - `Err(JsonError::CStringError(NulError))` - if one of the strings contains a null byte.
+/// - `Err(JsonError::NumberOutOfRange)` - if `strings` holds more than `i32::MAX` elements (the
+/// most cJSON can create an array from).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let arr = cjson_create_string_array_safe(&["Alice", "Bob", "Chloe"]).unwrap();
+///     assert_eq!(cjson_get_array_size(arr).unwrap(), 3);
+///     assert_eq!(cjson_get_string_value(cjson_get_array_item(arr, 2).unwrap()).unwrap(), "Chloe");
+///
+///     assert!(matches!(cjson_create_string_array_safe(&["a\0b"]), Err(JsonError::CStringError(_))));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_create_string_array_safe(strings: &[&str]) -> Result<*mut Json, JsonError> {
+    match i32::try_from(strings.len()) {
+        Ok(count) => create_string_array(strings, count),
+        Err(_) => Err(JsonError::NumberOutOfRange),
+    }
+}
+
+// create an array from the first `count` strings (`count` must not exceed the number of strings)
+fn create_string_array(strings: &[&str], count: i32) -> Result<*mut Json, JsonError> {
+    let mut c_strings: Vec<CString> = Vec::with_capacity(strings.len());
+
+    for &s in strings {
+        match CString::new(s) {
+            Ok(c_str) => c_strings.push(c_str),
+            Err(err) => return Err(JsonError::CStringError(err)),
+        }
+    }
+
+    let pointers: Vec<*const c_void> = c_strings
+        .iter()
+        .map(|s| s.as_ptr() as *const c_void)
+        .collect();
+
+    let array = unsafe {
+        cJSON_CreateStringArray(pointers.as_ptr() as *const *const c_char, count) as *mut Json
+    };
+    Ok(array)
+}
+
+// append the created items to a new array, returning null (and deleting everything created so
+// far) if any allocation fails
+fn array_from_created<I: Iterator<Item = *mut cJSON>>(items: I) -> *mut Json {
+    let array = unsafe { cJSON_CreateArray() };
+    if array.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    for item in items {
+        if item.is_null() || unsafe { cJSON_AddItemToArray(array, item) } != 1 {
+            unsafe {
+                cJSON_Delete(item);
+                cJSON_Delete(array);
+            }
+            return std::ptr::null_mut();
+        }
+    }
+    array as *mut Json
+}
+
+/// Create Json item of type `Array` containing the numbers produced by an iterator.
+///
+/// Unlike the slice-based constructors, the values can be produced lazily.
+///
+/// Args:
+/// - `nums: I` - The numbers to include in the `Array`, in order.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` (null if an
+/// allocation fails).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let evens = cjson_array_from_iter((1..=10).filter(|n| n % 2 == 0).map(f64::from));
+///     assert_eq!(evens.print_unformatted().unwrap(), "[2,4,6,8,10]");
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_array_from_iter<I: IntoIterator<Item = f64>>(nums: I) -> *mut Json {
+    array_from_created(
+        nums.into_iter()
+            .map(|num| unsafe { cJSON_CreateNumber(num) }),
+    )
+}
+
+/// Create Json item of type `Array` containing the booleans produced by an iterator.
+///
+/// Args:
+/// - `bools: I` - The booleans to include in the `Array`, in order.
+///
+/// Returns:
+/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` (null if an
+/// allocation fails).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let flags = cjson_bool_array_from_iter((0..4).map(|n| n % 3 == 0));
+///     assert_eq!(flags.print_unformatted().unwrap(), "[true,false,false,true]");
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_bool_array_from_iter<I: IntoIterator<Item = bool>>(bools: I) -> *mut Json {
+    array_from_created(
+        bools
+            .into_iter()
+            .map(|boolean| unsafe { cJSON_CreateBool(if boolean { 1 } else { 0 }) }),
+    )
+}
+
+/// Create Json item of type `Array` containing the strings produced by an iterator.
+///
+/// Args:
+/// - `strings: I` - The strings to include in the `Array`, in order.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the created Json item of type `Array` (null if an
+/// allocation fails).
+/// - `Err(JsonError::CStringError(NulError))` - if one of the strings contains a null byte.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let names = ["alice", "bob", "chloe"];
+///     let array = cjson_string_array_from_iter(names.iter().filter(|name| name.len() > 3)).unwrap();
+///     assert_eq!(array.print_unformatted().unwrap(), r#"["alice","chloe"]"#);
+///
+///     let owned = cjson_string_array_from_iter((1..=2).map(|n| format!("item-{}", n))).unwrap();
+///     assert_eq!(owned.print_unformatted().unwrap(), r#"["item-1","item-2"]"#);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_string_array_from_iter<I>(strings: I) -> Result<*mut Json, JsonError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut c_strings = Vec::new();
+    for string in strings {
+        match CString::new(string.as_ref()) {
+            Ok(c_str) => c_strings.push(c_str),
+            Err(err) => return Err(JsonError::CStringError(err)),
+        }
+    }
+    Ok(array_from_created(c_strings.iter().map(|c_str| unsafe {
+        cJSON_CreateString(c_str.as_ptr())
+    })))
+}
+
+/// Create Json item of type `Object` from `(key, item)` pairs, taking ownership of the items.
+///
+/// On error the object built so far is deleted, and so are the item that could not be added and
+/// the items of the pairs not yet consumed, so nothing leaks.
+///
+/// Args:
+/// - `pairs: I` - The keys and the Json items to add under them, in order.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the created Json item of type `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if one of the keys contains a null byte.
+/// - `Err(JsonError::NullPointer)` - if one of the items is null or an allocation fails.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let pairs = vec![
+///         ("name".to_string(), cjson_create_string("Nemuel".to_string()).unwrap()),
+///         ("age".to_string(), cjson_create_number(20.0)),
+///         ("tags".to_string(), cjson_create_string_array_safe(&["a", "b"]).unwrap()),
+///     ];
+///     let object = cjson_object_from_pairs(pairs).unwrap();
+///     assert_eq!(
+///         object.print_unformatted().unwrap(),
+///         r#"{"name":"Nemuel","age":20,"tags":["a","b"]}"#
+///     );
+///
+///     let invalid = vec![
+///         ("ok".to_string(), cjson_create_true()),
+///         ("n\0pe".to_string(), cjson_create_false()),
+///     ];
+///     assert!(matches!(cjson_object_from_pairs(invalid), Err(JsonError::CStringError(_))));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_object_from_pairs<I: IntoIterator<Item = (String, *mut Json)>>(
+    pairs: I,
+) -> Result<*mut Json, JsonError> {
+    let mut pairs = pairs.into_iter();
+    let object = unsafe { cJSON_CreateObject() };
+    if object.is_null() {
+        for (_, item) in pairs {
+            unsafe { cJSON_Delete(item as *mut cJSON) };
+        }
+        return Err(JsonError::NullPointer);
+    }
+
+    for (key, item) in pairs.by_ref() {
+        let err = match CString::new(key) {
+            Ok(c_key) => {
+                let added = !item.is_null()
+                    && unsafe { cJSON_AddItemToObject(object, c_key.as_ptr(), item as *mut cJSON) }
+                        == 1;
+                if added {
+                    continue;
+                }
+                JsonError::NullPointer
+            }
+            Err(err) => JsonError::CStringError(err),
+        };
+        unsafe {
+            cJSON_Delete(item as *mut cJSON);
+            cJSON_Delete(object);
+        }
+        for (_, rest) in pairs {
+            unsafe { cJSON_Delete(rest as *mut cJSON) };
+        }
+        return Err(err);
+    }
+    Ok(object as *mut Json)
+}
+
+/// Get the size of Json item of type `Array`.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` whose size we want.
+///
+/// Returns:
+/// - `Ok(i32)` - if the size of the Json item of type `Array` is successfully determined.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let strings = ["Alice", "Bob", "Chloe", "Dan", "Eyal"];
+///     let arr = cjson_create_string_array_safe(&strings).unwrap();
+///     assert_eq!(cjson_get_array_size(arr).unwrap(), 5);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_get_array_size(array: *mut Json) -> Result<i32, JsonError> {
+    if !array.is_type_array() {
+        Err(JsonError::InvalidTypeError(
+            "cannot get array size for a non-array Json item".to_string(),
+        ))
+    } else {
+        Ok(unsafe { cJSON_GetArraySize(array as *const cJSON) })
+    }
+}
+
+/// Get the size of Json item of type `Array` as a `usize`, ready for indexing.
+///
+/// `cJSON_GetArraySize` counts the items in a `size_t` but returns an `int`, so a size that does not
+/// fit (or a corrupted list of items) could come back negative; this is reported as an error
+/// instead of wrapping around.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` whose size we want.
+///
+/// Returns:
+/// - `Ok(usize)` - the number of items in the array.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Err(JsonError::NumberOutOfRange)` - if cJSON reports a negative size.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let arr = cjson_create_int_array_from_slice(&[3, 1, 4, 1, 5]);
+///     let len = cjson_array_len(arr).unwrap();
+///     assert_eq!(len, 5);
+///     assert_eq!(cjson_get_array_item(arr, (len - 1) as i32).unwrap().as_f64(), Some(5.0));
+///     assert!(cjson_array_len(cjson_create_object()).is_err());
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_array_len(array: *mut Json) -> Result<usize, JsonError> {
+    let size = cjson_get_array_size(array)?;
+    usize::try_from(size).map_err(|_| JsonError::NumberOutOfRange)
+}
+
+/// Append the values of a Json item of type `Array` holding only numbers to a vector, which can be
+/// reused across calls to avoid allocating for every array.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` to read from.
+/// - `out: &mut Vec<f64>` - The vector the numbers are appended to, in order.
+///
+/// Returns:
+/// - `Ok(())` - if every item was appended.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`
+/// or one of its items is not of type `Number` (`out` is then left as it was).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let values: Vec<f64> = (0..1000).map(|n| n as f64 * 0.5).collect();
+///     let array = cjson_create_double_array_from_slice(&values);
+///
+///     let mut out = Vec::with_capacity(1000);
+///     cjson_array_as_f64_slice(array, &mut out).unwrap();
+///     assert_eq!(out.len(), 1000);
+///     assert_eq!(out, values);
+///
+///     let mixed = cjson_parse_json("[1, \"two\", 3]".to_string()).unwrap();
+///     assert!(cjson_array_as_f64_slice(mixed, &mut out).is_err());
+///     assert_eq!(out.len(), 1000);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_array_as_f64_slice(array: *mut Json, out: &mut Vec<f64>) -> Result<(), JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot read numbers from a non-array Json item".to_string(),
+        ));
+    }
+
+    let original_len = out.len();
+    // walk the sibling chain from the first item rather than collecting the items first
+    let mut current = unsafe { cJSON_GetArrayItem(array as *const cJSON, 0) } as *mut Json;
+    while !current.is_null() {
+        if !current.is_type_number() {
+            let index = out.len() - original_len;
+            out.truncate(original_len);
+            return Err(JsonError::InvalidTypeError(format!(
+                "cannot read the non-number item at index {} as a number",
+                index
+            )));
+        }
+        out.push(unsafe { (*current).valuedouble });
+        current = unsafe { (*current).next };
+    }
+    Ok(())
+}
+
+/// Get the item at the provided index of a Json item of type `Array`.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` from which we want to get an item.
+/// - `index: i32` - Index of the item we want to get from the Json item of type `Array`.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - mutable pointer to the item at the specified index.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let strings = ["Alice", "Bob", "Chloe", "Dan", "Eyal"];
+///     let arr = cjson_create_string_array_safe(&strings).unwrap();
+///     match cjson_get_array_item(arr, 2) {
+///         Ok(item) => {
+///             println!("{}", item.print().unwrap()); // output: "Chloe"
+///         }
+///         Err(err) => eprintln!("{}", err),
+///     }
+/// }
+/// ```
+pub fn cjson_get_array_item(array: *mut Json, index: i32) -> Result<*mut Json, JsonError> {
+    if !array.is_type_array() {
+        Err(JsonError::InvalidTypeError(
+            "cannot get array item from a non-array Json item".to_string(),
+        ))
+    } else {
+        Ok(unsafe { cJSON_GetArrayItem(array as *const cJSON, index) as *mut Json })
+    }
+}
+
+// get the item at `index` of a Json item of type Array, reporting a missing index as an error
+fn array_item_at(array: *mut Json, index: i32) -> Result<*mut Json, JsonError> {
+    let item = cjson_get_array_item(array, index)?;
+    if item.is_null() {
+        Err(JsonError::IndexOutOfRange(index))
+    } else {
+        Ok(item)
+    }
+}
+
+/// Get the value of the item of type `Number` at the provided index of a Json item of type `Array`.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` from which we want to get the value.
+/// - `index: i32` - Index of the item whose value we want.
+///
+/// Returns:
+/// - `Ok(f64)` - the number value of the item at the specified index.
+/// - `Err(JsonError::InvalidTypeError(String))` - if `array` is not of type `Array` or the item is
+/// not of type `Number`.
+/// - `Err(JsonError::IndexOutOfRange(i32))` - if there is no item at `index`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let array = cjson_parse_json(r#"[1.5,"two",true]"#.to_string()).unwrap();
+///     assert_eq!(cjson_get_array_number(array, 0).unwrap(), 1.5);
+///     assert!(matches!(cjson_get_array_number(array, 1), Err(JsonError::InvalidTypeError(_))));
+///     assert!(matches!(cjson_get_array_number(array, 3), Err(JsonError::IndexOutOfRange(3))));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_get_array_number(array: *mut Json, index: i32) -> Result<f64, JsonError> {
+    match array_item_at(array, index)?.as_f64() {
+        Some(number) => Ok(number),
+        None => Err(JsonError::InvalidTypeError(format!(
+            "the item at index {} is not a number",
+            index
+        ))),
+    }
+}
+
+/// Get the value of the item of type `String` at the provided index of a Json item of type `Array`.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` from which we want to get the value.
+/// - `index: i32` - Index of the item whose value we want.
+///
+/// Returns:
+/// - `Ok(String)` - the string value of the item at the specified index.
+/// - `Err(JsonError::InvalidTypeError(String))` - if `array` is not of type `Array` or the item is
+/// not of type `String`.
+/// - `Err(JsonError::IndexOutOfRange(i32))` - if there is no item at `index`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let array = cjson_parse_json(r#"[1.5,"two",true]"#.to_string()).unwrap();
+///     assert_eq!(cjson_get_array_string(array, 1).unwrap(), "two");
+///     assert!(matches!(cjson_get_array_string(array, 2), Err(JsonError::InvalidTypeError(_))));
+///     assert!(matches!(cjson_get_array_string(array, -1), Err(JsonError::IndexOutOfRange(-1))));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_get_array_string(array: *mut Json, index: i32) -> Result<String, JsonError> {
+    let item = array_item_at(array, index)?;
+    if !item.is_type_string() {
+        return Err(JsonError::InvalidTypeError(format!(
+            "the item at index {} is not a string",
+            index
+        )));
+    }
+    cjson_get_string_value(item)
+}
+
+/// Get the value of the item of type `True` or `False` at the provided index of a Json item of type
+/// `Array`.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` from which we want to get the value.
+/// - `index: i32` - Index of the item whose value we want.
+///
+/// Returns:
+/// - `Ok(bool)` - the boolean value of the item at the specified index.
+/// - `Err(JsonError::InvalidTypeError(String))` - if `array` is not of type `Array` or the item is
+/// not of type `True` or `False`.
+/// - `Err(JsonError::IndexOutOfRange(i32))` - if there is no item at `index`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let array = cjson_parse_json(r#"[1.5,"two",true]"#.to_string()).unwrap();
+///     assert_eq!(cjson_get_array_bool(array, 2).unwrap(), true);
+///     assert!(matches!(cjson_get_array_bool(array, 0), Err(JsonError::InvalidTypeError(_))));
+///     assert!(matches!(cjson_get_array_bool(array, 10), Err(JsonError::IndexOutOfRange(10))));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_get_array_bool(array: *mut Json, index: i32) -> Result<bool, JsonError> {
+    match array_item_at(array, index)?.as_bool() {
+        Some(boolean) => Ok(boolean),
+        None => Err(JsonError::InvalidTypeError(format!(
+            "the item at index {} is not a boolean",
+            index
+        ))),
+    }
+}
+
+/// Add an item to Json item of type `Array`.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` where the item will be added.
+/// - `item: *mut Json` - The item to add to the Json item of type `Array`.
+///
+/// Returns:
+/// - `Ok(bool)` - indicating success or failure in adding the item to the Json item of type `Array`.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let numbers = [1, 2, 3, 4];
+///     let arr = cjson_create_int_array(&numbers[0], 4);
+///     let item = cjson_create_number(5.0);
+///     let success = cjson_add_item_to_array(arr, item).unwrap();
+///     assert_eq!(success, true);
+///     assert_eq!(cjson_get_array_size(arr).unwrap(), 5);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_add_item_to_array(array: *mut Json, item: *mut Json) -> Result<bool, JsonError> {
+    if !array.is_type_array() {
+        Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-array Json item".to_string(),
+        ))
+    } else {
+        let result = unsafe { cJSON_AddItemToArray(array as *mut cJSON, item as *mut cJSON) };
+        if result == 1 {
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+/// Add an item to Json item of type `Array`, refusing to create a cycle.
+///
+/// Adding an array to itself (or to one of its own descendants) would make printing and deleting
+/// it loop forever or free items twice, so the subtree of `item` is searched for `array` first.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` where the item will be added.
+/// - `item: *mut Json` - The item to add to the Json item of type `Array`.
+///
+/// Returns:
+/// - `Ok(bool)` - indicating success or failure in adding the item to the Json item of type `Array`.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Err(JsonError::CycleDetected)` - if `array` is `item` itself or nested inside it.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let outer = cjson_create_array();
+///     let inner = cjson_create_array();
+///     assert!(cjson_add_item_to_array_checked(outer, inner).unwrap());
+///
+///     assert!(matches!(cjson_add_item_to_array_checked(outer, outer), Err(JsonError::CycleDetected)));
+///     assert!(matches!(cjson_add_item_to_array_checked(inner, outer), Err(JsonError::CycleDetected)));
+///     assert_eq!(outer.print_unformatted().unwrap(), "[[]]");
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_add_item_to_array_checked(
+    array: *mut Json,
+    item: *mut Json,
+) -> Result<bool, JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-array Json item".to_string(),
+        ));
+    }
+    if subtree_contains(item, array) {
+        return Err(JsonError::CycleDetected);
+    }
+    cjson_add_item_to_array(array, item)
+}
+
+/// Add an item to Json item of type `Array` while maintaining a reference to the original item rather
+/// than copying it.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` where the item will be added.
+/// - `item: *mut Json` - The item to add to the Json item of type `Array`.
+///
+/// Returns:
+/// - `Ok(bool)` - boolean value indicating success or failure of the operation.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let numbers = [1, 2, 3, 4];
+///     let arr = cjson_create_int_array(&numbers[0], 4);
+///     let item = cjson_create_number(5.0);
+///     let success = cjson_add_item_reference_to_array(arr, item).unwrap();
+///     assert_eq!(success, true);
+///     assert_eq!(cjson_get_array_size(arr).unwrap(), 5);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_add_item_reference_to_array(
+    array: *mut Json,
+    item: *mut Json,
+) -> Result<bool, JsonError> {
+    if !array.is_type_array() {
+        Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-array Json item".to_string(),
+        ))
+    } else {
+        let result =
+            unsafe { cJSON_AddItemReferenceToArray(array as *mut cJSON, item as *mut cJSON) };
+        if result == 1 {
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+/// Insert an item at a specific index in a Json item of type `Array`.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` where the item will be added.
+/// - `which: i32` - Index specifying where to insert the new item in the array.
+/// - `newitem: *mut Json` - The item to add to the Json item of type `Array`.
+///
+/// Returns:
+/// - `Ok(bool)` - indicating success or failure of the operation.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let numbers = [1, 3, 4, 5];
+///     let arr = cjson_create_int_array(&numbers[0], 4);
+///     let item = cjson_create_number(2.0);
+///     let success = cjson_insert_item_in_array(arr, 1, item).unwrap();
+///     assert_eq!(success, true);
+///     let new_item = cjson_get_array_item(arr, 1).unwrap();
+///     assert_eq!(cjson_get_number_value(new_item).unwrap(), 2.0);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_insert_item_in_array(
+    array: *mut Json,
+    which: i32,
+    newitem: *mut Json,
+) -> Result<bool, JsonError> {
+    if !array.is_type_array() {
+        Err(JsonError::InvalidTypeError(
+            "cannot insert item in a non-array Json item".to_string(),
+        ))
+    } else {
+        let result =
+            unsafe { cJSON_InsertItemInArray(array as *mut cJSON, which, newitem as *mut cJSON) };
+        if result == 1 {
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+/// Replace item at a specific index in Json item of type `Array`.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` in which the replacement will happen.
+/// - `which: i32` - Index specifying the item to be replaced.
+/// - `newitem: *mut Json` - The item replacing the one at the specified index.
+///
+/// Returns:
+/// - `Ok(bool)` - indicating success or faanilure of the operation.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let names = ["Alice", "Bob", "Chloe", "Dan"];
+///     let array = cjson_create_string_array_safe(&names).unwrap();
+///
+///     let original_item = cjson_get_array_item(array, 3).unwrap();
+///     assert_eq!(
+///         cjson_get_string_value(original_item).unwrap(),
+///         "Dan"
+///     );
+///
+///     let newitem = cjson_create_string("Diana".to_string()).unwrap();
+///     let success = cjson_replace_item_in_array(array, 3, newitem).unwrap();
+///     assert_eq!(success, true);
+///
+///     let new_item = cjson_get_array_item(array, 3).unwrap();
+///     assert_eq!(
+///         cjson_get_string_value(new_item).unwrap(),
+///         "Diana"
+///     );
+///
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_replace_item_in_array(
+    array: *mut Json,
+    which: i32,
+    newitem: *mut Json,
+) -> Result<bool, JsonError> {
+    if !array.is_type_array() {
+        Err(JsonError::InvalidTypeError(
+            "cannot replace item in a non-array Json item".to_string(),
+        ))
+    } else {
+        let result =
+            unsafe { cJSON_ReplaceItemInArray(array as *mut cJSON, which, newitem as *mut cJSON) };
+        if result == 1 {
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+/// Replace item at a specific index in Json item of type `Array`, handing back the old item
+/// instead of deleting it.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` in which an item is to be replaced.
+/// - `index: i32` - The index of the item to be replaced.
+/// - `newitem: *mut Json` - The new item to be put at that index (which must not belong to another
+/// array or object).
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the old, now detached, item (to be deleted or re-attached
+/// by the caller).
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Err(JsonError::NullPointer)` - if `newitem` is null.
+/// - `Err(JsonError::IndexOutOfRange(i32))` - if there is no item at `index` (`newitem` is left
+/// untouched).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let names = ["Alice", "Bob", "Chloe"];
+///     let array = cjson_create_string_array_safe(&names).unwrap();
+///
+///     let newitem = cjson_create_string("Brian".to_string()).unwrap();
+///     let mut old = cjson_swap_item_in_array(array, 1, newitem).unwrap();
+///     assert_eq!(cjson_get_string_value(old).unwrap(), "Bob");
+///     assert_eq!(array.print_unformatted().unwrap(), r#"["Alice","Brian","Chloe"]"#);
+///
+///     cjson_delete(&mut old);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_swap_item_in_array(
+    array: *mut Json,
+    index: i32,
+    newitem: *mut Json,
+) -> Result<*mut Json, JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot swap item in a non-array Json item".to_string(),
+        ));
+    }
+    if newitem.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    let items = child_items(array);
+    let old = match usize::try_from(index).ok().and_then(|i| items.get(i)) {
+        Some(&old) => old,
+        None => return Err(JsonError::IndexOutOfRange(index)),
+    };
+
+    let mut swapped = items;
+    swapped[index as usize] = newitem;
+    unsafe {
+        (*old).next = std::ptr::null_mut();
+        (*old).prev = std::ptr::null_mut();
+    }
+    relink_child_items(array, &swapped);
+    Ok(old)
+}
+
+/// Set the item at a specific index in Json item of type `Array`, growing the array with `Null`
+/// items if the index is past its end.
+///
+/// An existing item at `index` is replaced (and deleted). Otherwise `Null` items are appended up
+/// to `index` and `item` is appended after them.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` in which the item is to be set.
+/// - `index: usize` - The index at which `item` should end up.
+/// - `item: *mut Json` - The item to set (which must not belong to another array or object).
+///
+/// Returns:
+/// - `Ok(())` - if `item` now sits at `index`.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Err(JsonError::NullPointer)` - if `item` is null, an item could not be allocated or cJSON
+/// failed to add it (the array and `item` are left untouched).
+/// - `Err(JsonError::IndexOutOfRange(i32))` - if `index` is larger than `i32::MAX`, which a cJSON
+/// array cannot reach.
+/// - `Err(JsonError::CycleDetected)` - if `array` is `item` itself or nested inside it.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let array = cjson_create_string_array_safe(&["Alice", "Bob"]).unwrap();
+///
+///     cjson_array_set(array, 1, cjson_create_string("Brian".to_string()).unwrap()).unwrap();
+///     assert_eq!(array.print_unformatted().unwrap(), r#"["Alice","Brian"]"#);
+///
+///     cjson_array_set(array, 4, cjson_create_number(5.0)).unwrap();
+///     assert_eq!(array.print_unformatted().unwrap(), r#"["Alice","Brian",null,null,5]"#);
+///
+///     let mut item = cjson_create_true();
+///     let result = cjson_array_set(array, usize::MAX, item);
+///     assert!(matches!(result, Err(JsonError::IndexOutOfRange(_))));
+///     assert!(matches!(cjson_array_set(array, 0, array), Err(JsonError::CycleDetected)));
+///     assert_eq!(cjson_get_array_size(array).unwrap(), 5);
+///     cjson_delete(&mut item);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_array_set(array: *mut Json, index: usize, item: *mut Json) -> Result<(), JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot set item in a non-array Json item".to_string(),
+        ));
+    }
+    if item.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    // cJSON counts array items with an `int`, so larger indices can never be reached
+    if index > i32::MAX as usize {
+        return Err(JsonError::IndexOutOfRange(i32::MAX));
+    }
+    if subtree_contains(item, array) {
+        return Err(JsonError::CycleDetected);
+    }
+
+    let items = child_items(array);
+    if let Some(&old) = items.get(index) {
+        let replaced = unsafe {
+            cJSON_ReplaceItemViaPointer(array as *mut cJSON, old as *mut cJSON, item as *mut cJSON)
+        };
+        return if replaced == 1 {
+            Ok(())
+        } else {
+            Err(JsonError::NullPointer)
+        };
+    }
+
+    let mut padding = Vec::with_capacity(index - items.len());
+    for _ in items.len()..index {
+        let null = unsafe { cJSON_CreateNull() };
+        if null.is_null() {
+            for null in padding {
+                unsafe { cJSON_Delete(null) };
+            }
+            return Err(JsonError::NullPointer);
+        }
+        padding.push(null);
+    }
+    padding.push(item as *mut cJSON);
+
+    let original_len = items.len();
+    for (added, new) in padding.iter().enumerate() {
+        if unsafe { cJSON_AddItemToArray(array as *mut cJSON, *new) } != 1 {
+            // drop the padding again, leaving the array and `item` as they were
+            for extra in child_items(array).into_iter().skip(original_len) {
+                unsafe {
+                    cJSON_Delete(cJSON_DetachItemViaPointer(
+                        array as *mut cJSON,
+                        extra as *mut cJSON,
+                    ))
+                };
+            }
+            for null in &padding[added..padding.len() - 1] {
+                unsafe { cJSON_Delete(*null) };
+            }
+            return Err(JsonError::NullPointer);
+        }
+    }
+    Ok(())
+}
+
+/// Delete the items at several indices of a Json item of type `Array` at once.
+///
+/// All indices refer to the array as it was before the call: they are processed from the highest
+/// to the lowest, so deleting one item does not shift the others. Out-of-range and repeated
+/// indices are ignored.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` from which items are to be deleted.
+/// - `indices: &[usize]` - The indices of the items to delete, in any order.
+///
+/// Returns:
+/// - `Ok(usize)` - the number of items deleted.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let array = cjson_create_string_array_safe(&["Alice", "Bob", "Chloe", "Dan"]).unwrap();
+///
+///     assert_eq!(cjson_delete_array_indices(array, &[0, 2]).unwrap(), 2);
+///     assert_eq!(array.print_unformatted().unwrap(), r#"["Bob","Dan"]"#);
+///
+///     assert_eq!(cjson_delete_array_indices(array, &[5, 1, 1]).unwrap(), 1);
+///     assert_eq!(array.print_unformatted().unwrap(), r#"["Bob"]"#);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_delete_array_indices(array: *mut Json, indices: &[usize]) -> Result<usize, JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot delete items from a non-array Json item".to_string(),
+        ));
+    }
+
+    let items = child_items(array);
+    let mut indices: Vec<usize> = indices
+        .iter()
+        .copied()
+        .filter(|&index| index < items.len())
+        .collect();
+    indices.sort_unstable_by(|a, b| b.cmp(a));
+    indices.dedup();
+
+    for &index in &indices {
+        unsafe {
+            cJSON_DetachItemViaPointer(array as *mut cJSON, items[index] as *mut cJSON);
+            cJSON_Delete(items[index] as *mut cJSON);
+        }
+    }
+    Ok(indices.len())
+}
+
+/// Copy the items of a Json item of type `Array` in the range `[start, end)` into a new array.
+///
+/// The items are deep copies, so the source array is left untouched. `end` is clamped to the size of
+/// the array.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` to slice.
+/// - `start: usize` - Index of the first item to copy.
+/// - `end: usize` - Index one past the last item to copy.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a new Json item of type `Array` (to be deleted by the caller).
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Err(JsonError::IndexOutOfRange(i32))` - if `start` is greater than (the clamped) `end`.
+/// - `Err(JsonError::NullPointer)` - if an item could not be allocated.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let array = cjson_parse_json(r#"[1,2,{"three":3},4,5]"#.to_string()).unwrap();
+///
+///     let middle = cjson_array_slice(array, 1, 4).unwrap();
+///     assert_eq!(middle.print_unformatted().unwrap(), r#"[2,{"three":3},4]"#);
+///
+///     let tail = cjson_array_slice(array, 3, 100).unwrap();
+///     assert_eq!(tail.print_unformatted().unwrap(), "[4,5]");
+///
+///     assert!(matches!(cjson_array_slice(array, 4, 2), Err(JsonError::IndexOutOfRange(4))));
+///     assert_eq!(array.print_unformatted().unwrap(), r#"[1,2,{"three":3},4,5]"#);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_array_slice(
+    array: *mut Json,
+    start: usize,
+    end: usize,
+) -> Result<*mut Json, JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot slice a non-array Json item".to_string(),
+        ));
+    }
+
+    let items = child_items(array);
+    let end = end.min(items.len());
+    if start > end {
+        return Err(JsonError::IndexOutOfRange(
+            i32::try_from(start).unwrap_or(i32::MAX),
+        ));
+    }
+
+    let slice = array_from_created(
+        items[start..end]
+            .iter()
+            .map(|&item| unsafe { cJSON_Duplicate(item as *const cJSON, 1) }),
+    );
+    if slice.is_null() {
+        Err(JsonError::NullPointer)
+    } else {
+        Ok(slice)
+    }
+}
+
+/// Delete all the items of a Json item of type `Array` or `Object`, leaving it empty.
+///
+/// NOTE: Pointers to the deleted items (and anything nested in them) are left dangling.
+///
+/// Args:
+/// - `container: *mut Json` - The Json item of type `Array` or `Object` to be cleared.
+///
+/// Returns:
+/// - `Ok(usize)` - the number of items that were deleted.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `container` value provided is neither of
+/// type `Array` nor `Object`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let array = cjson_parse_json("[1,[2,3],{\"a\":4}]".to_string()).unwrap();
+///     assert_eq!(cjson_clear(array).unwrap(), 3);
+///     assert_eq!(cjson_get_array_size(array).unwrap(), 0);
+///     assert_eq!(array.print_unformatted().unwrap(), "[]");
+///
+///     let object = cjson_parse_json(r#"{"name":"Nemuel","age":20}"#.to_string()).unwrap();
+///     assert_eq!(cjson_clear(object).unwrap(), 2);
+///     assert_eq!(cjson_is_empty(object).unwrap(), true);
+///     cjson_add_bool_to_object(object, "ok", true).unwrap();
+///     assert_eq!(object.print_unformatted().unwrap(), r#"{"ok":true}"#);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_clear(container: *mut Json) -> Result<usize, JsonError> {
+    if !container.is_type_array() && !container.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot clear a non-array, non-object Json item".to_string(),
+        ));
+    }
+
+    let items = child_items(container);
+    for &item in &items {
+        unsafe {
+            cJSON_Delete(cJSON_DetachItemViaPointer(
+                container as *mut cJSON,
+                item as *mut cJSON,
+            ))
+        };
+    }
+    Ok(items.len())
+}
+
+/// Detach item at a specific index from Json item of type `Array`.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` from which an item is to be detached.
+/// - `which: i32` - The index of the item to be detached from the array.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the detached item if the operation is successful.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let names = ["Alice", "Bob", "Chloe"];
+///     let array = cjson_create_string_array_safe(&names).unwrap();
+///     assert_eq!(cjson_get_array_size(array).unwrap(), 3);
+///
+///     let detached_item = cjson_detach_item_from_array(array, 2).unwrap();
+///
+///     assert_eq!(cjson_get_string_value(detached_item).unwrap(), "Chloe");
+///     assert_eq!(cjson_get_array_size(array).unwrap(), 2);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_detach_item_from_array(array: *mut Json, which: i32) -> Result<*mut Json, JsonError> {
+    if !array.is_type_array() {
+        Err(JsonError::InvalidTypeError(
+            "cannot detach item from a non-array Json item".to_string(),
+        ))
+    } else {
+        Ok(unsafe { cJSON_DetachItemFromArray(array as *mut cJSON, which) as *mut Json })
+    }
+}
+
+/// Guard owning a Json item that was detached from its parent.
+///
+/// The item is deleted when the guard is dropped, unless ownership is handed back with
+/// `into_raw()` or the item is put back into a tree with `reattach()`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static FREES: AtomicUsize = AtomicUsize::new(0);
+///
+/// struct CountingAllocator;
+///
+/// impl Allocator for CountingAllocator {
+///     fn alloc(&self, size: usize) -> *mut libc::c_void {
+///         unsafe { libc::malloc(size) }
+///     }
+///
+///     fn free(&self, ptr: *mut libc::c_void) {
+///         FREES.fetch_add(1, Ordering::SeqCst);
+///         unsafe { libc::free(ptr) }
+///     }
+/// }
+///
+/// fn main() {
+///     Hooks::from_allocator(CountingAllocator).init();
+///     let mut array = cjson_parse_json(r#"["a","b"]"#.to_string()).unwrap();
+///
+///     // dropping the guard deletes the item (the item itself and its string)
+///     let before = FREES.load(Ordering::SeqCst);
+///     drop(cjson_detach_item_from_array_owned(array, 0).unwrap().unwrap());
+///     assert_eq!(FREES.load(Ordering::SeqCst) - before, 2);
+///
+///     // into_raw hands the item back without deleting it
+///     let before = FREES.load(Ordering::SeqCst);
+///     let mut raw = cjson_detach_item_from_array_owned(array, 0).unwrap().unwrap().into_raw();
+///     assert_eq!(FREES.load(Ordering::SeqCst), before);
+///     assert_eq!(cjson_get_string_value(raw).unwrap(), "b");
+///
+///     cjson_delete(&mut raw);
+///     cjson_delete(&mut array);
+///     Hooks::new(None, None).init();
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub struct DetachedJson(NonNull<Json>);
+
+impl DetachedJson {
+    /// Get a mutable pointer to the detached item (still owned by the guard).
+    pub fn as_ptr(&self) -> *mut Json {
+        self.0.as_ptr()
+    }
+
+    /// Release ownership of the detached item without deleting it (to be deleted or re-attached by
+    /// the caller).
+    pub fn into_raw(self) -> *mut Json {
+        let item = self.0.as_ptr();
+        std::mem::forget(self);
+        item
+    }
+
+    /// Attach the detached item to a Json item of type `Array` (appended at the end) or `Object`
+    /// (under the key it was detached with).
+    ///
+    /// Args:
+    /// - `parent: *mut Json` - Mutable pointer to the Json item of type `Array` or `Object` that is to
+    /// take ownership of the item.
+    ///
+    /// Returns:
+    /// - `Ok(())` - if the item was attached.
+    /// - `Err(JsonError::InvalidTypeError(String))` - if `parent` is neither an array nor an object,
+    /// or if it is an object and the item has no key. The item is deleted in this case.
+    pub fn reattach(self, parent: *mut Json) -> Result<(), JsonError> {
+        let item = self.as_ptr();
+        let key = unsafe { (*item).string };
+        let added = if parent.is_type_array() {
+            unsafe { cJSON_AddItemToArray(parent as *mut cJSON, item as *mut cJSON) == 1 }
+        } else if parent.is_type_object() && !key.is_null() {
+            unsafe { cJSON_AddItemToObject(parent as *mut cJSON, key, item as *mut cJSON) == 1 }
+        } else {
+            false
+        };
+
+        if added {
+            std::mem::forget(self);
+            Ok(())
+        } else {
+            Err(JsonError::InvalidTypeError(
+                "cannot reattach the item to this Json item".to_string(),
+            ))
+        }
+    }
+}
+
+impl Drop for DetachedJson {
+    fn drop(&mut self) {
+        unsafe { cJSON_Delete(self.0.as_ptr() as *mut cJSON) };
+    }
+}
+
+/// Detach item at a specific index from Json item of type `Array`, wrapped in a guard that deletes
+/// it unless it is re-attached or released.
+///
+/// Args:
+/// - `array: *mut Json` - Mutable pointer to the Json item of type `Array`.
+/// - `which: i32` - The index of the item to be detached from the array.
+///
+/// Returns:
+/// - `Ok(Some(DetachedJson))` - the guard owning the detached item.
+/// - `Ok(None)` - if there is no item at that index.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let from = cjson_parse_json(r#"[1,2]"#.to_string()).unwrap();
+///     let to = cjson_create_array();
+///
+///     let detached = cjson_detach_item_from_array_owned(from, 1).unwrap().unwrap();
+///     detached.reattach(to).unwrap();
+///     assert_eq!(from.print_unformatted().unwrap(), "[1]");
+///     assert_eq!(to.print_unformatted().unwrap(), "[2]");
+///     assert_eq!(cjson_detach_item_from_array_owned(from, 5).unwrap().is_none(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_detach_item_from_array_owned(
+    array: *mut Json,
+    which: i32,
+) -> Result<Option<DetachedJson>, JsonError> {
+    match cjson_detach_item_from_array(array, which) {
+        Ok(item) => Ok(NonNull::new(item).map(DetachedJson)),
+        Err(err) => Err(err),
+    }
+}
+
+/// Delete item at a specific index from Json item of type `Array`.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` from which an item is to be deleted.
+/// - `which: i32` - The index of the item to be deleted from the array.
+///
+/// Returns:
+/// - `Ok()` - if the operation is successful.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let names = ["Alice", "Bob", "Chloe"];
+///     let array = cjson_create_string_array_safe(&names).unwrap();
+///     assert_eq!(cjson_get_array_size(array).unwrap(), 3);
+///
+///     cjson_delete_item_from_array(array, 2).unwrap();
+///
+///     assert_eq!(cjson_get_array_size(array).unwrap(), 2);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_delete_item_from_array(array: *mut Json, which: i32) -> Result<(), JsonError> {
+    if !array.is_type_array() {
+        Err(JsonError::InvalidTypeError(
+            "cannot delete item from a non-array Json item".to_string(),
+        ))
+    } else {
+        unsafe { cJSON_DeleteItemFromArray(array as *mut cJSON, which) };
+        Ok(())
+    }
+}
+
+/// Add several items to Json item of type `Array`, one after the other.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` where the items will be added.
+/// - `items: impl IntoIterator<Item = *mut Json>` - The items to add to the Json item of type `Array`.
+///
+/// Returns:
+/// - `Ok(usize)` - the number of items added to the Json item of type `Array`.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Err(JsonError::ExtendArrayError(usize))` - if adding an item fails, carrying the index of that
+/// item. The items before it remain added and belong to the array, while the failing item and
+/// the items after it are left untouched and still belong to the caller (pass eg.
+/// `items.iter().copied()` to keep hold of them).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let array = cjson_create_array();
+///     let items = [
+///         cjson_create_number(1.0),
+///         cjson_create_string("two".to_string()).unwrap(),
+///         cjson_create_true(),
+///     ];
+///
+///     let added = cjson_extend_array(array, items).unwrap();
+///     assert_eq!(added, 3);
+///     assert_eq!(cjson_get_array_size(array).unwrap(), 3);
+///
+///     // the null item cannot be added, so it and the items after it stay with the caller
+///     let mut rest = [cjson_create_null(), std::ptr::null_mut(), cjson_create_false()];
+///     match cjson_extend_array(array, rest.iter().copied()) {
+///         Err(JsonError::ExtendArrayError(index)) => assert_eq!(index, 1),
+///         _ => panic!("the null item should have been rejected"),
+///     }
+///     assert_eq!(cjson_get_array_size(array).unwrap(), 4);
+///     cjson_delete(&mut rest[2]);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_extend_array(
+    array: *mut Json,
+    items: impl IntoIterator<Item = *mut Json>,
+) -> Result<usize, JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot extend a non-array Json item".to_string(),
+        ));
+    }
+
+    let mut added = 0;
+    for item in items {
+        let result = unsafe { cJSON_AddItemToArray(array as *mut cJSON, item as *mut cJSON) };
+        if result != 1 {
+            return Err(JsonError::ExtendArrayError(added));
+        }
+        added += 1;
+    }
+    Ok(added)
+}
+
+/// Reverse the order of the items in Json item of type `Array` (in place).
+///
+/// Empty and single-item arrays are left as they are.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` whose items are to be reversed.
+///
+/// Returns:
+/// - `Ok(())` - if the operation is successful.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let names = ["Alice", "Bob", "Chloe", "Dan", "Eyal"];
+///     let array = cjson_create_string_array_safe(&names).unwrap();
+///
+///     cjson_reverse_array(array).unwrap();
+///
+///     assert_eq!(cjson_get_array_size(array).unwrap(), 5);
+///     assert_eq!(
+///         array.print_unformatted().unwrap(),
+///         r#"["Eyal","Dan","Chloe","Bob","Alice"]"#
+///     );
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_reverse_array(array: *mut Json) -> Result<(), JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot reverse a non-array Json item".to_string(),
+        ));
+    }
+
+    let mut items = child_items(array);
+    if items.len() > 1 {
+        items.reverse();
+        relink_child_items(array, &items);
+    }
+    Ok(())
+}
+
+/// Keep only the items of Json item of type `Array` that satisfy the predicate.
+///
+/// The items for which the predicate returns `false` are detached from the array and deleted.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` to be filtered.
+/// - `predicate: F` - Closure receiving each item and returning whether or not to keep it.
+///
+/// Returns:
+/// - `Ok(usize)` - the number of items removed from the Json item of type `Array`.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let numbers = [1, 2, 3, 4];
+///     let array = cjson_create_int_array(&numbers[0], 4);
+///
+///     let removed = cjson_retain_array(array, |item| item.valuedouble > 2.0).unwrap();
+///
+///     assert_eq!(removed, 2);
+///     assert_eq!(cjson_get_array_size(array).unwrap(), 2);
+///     assert_eq!(array.print_unformatted().unwrap(), "[3,4]");
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_retain_array<F: Fn(&Json) -> bool>(
+    array: *mut Json,
+    predicate: F,
+) -> Result<usize, JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot retain items of a non-array Json item".to_string(),
+        ));
+    }
+
+    // the children are collected up front so that detaching does not disturb the walk
+    let mut removed = 0;
+    for item in child_items(array) {
+        if !predicate(unsafe { &*item }) {
+            unsafe {
+                let detached = cJSON_DetachItemViaPointer(array as *mut cJSON, item as *mut cJSON);
+                cJSON_Delete(detached);
+            }
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Find the first item of Json item of type `Array` that satisfies the predicate.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` to search.
+/// - `predicate: F` - Closure receiving each item and returning whether or not it is a match.
+///
+/// Returns:
+/// - `Ok(Some(*mut Json))` - a mutable pointer to the first matching item.
+/// - `Ok(None)` - if no item matches.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let numbers = [1, 2, 3, 4];
+///     let array = cjson_create_int_array(&numbers[0], 4);
+///
+///     let found = cjson_array_find(array, |item| item.valuedouble > 2.0).unwrap();
+///     assert_eq!(cjson_get_number_value(found.unwrap()).unwrap(), 3.0);
+///
+///     let missing = cjson_array_find(array, |item| item.valuedouble > 10.0).unwrap();
+///     assert_eq!(missing.is_none(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_array_find<F: Fn(&Json) -> bool>(
+    array: *mut Json,
+    predicate: F,
+) -> Result<Option<*mut Json>, JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot search a non-array Json item".to_string(),
+        ));
+    }
+
+    Ok(child_items(array)
+        .into_iter()
+        .find(|&item| predicate(unsafe { &*item })))
+}
+
+/// Find the index of the first item of Json item of type `Array` that satisfies the predicate.
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` to search.
+/// - `predicate: F` - Closure receiving each item and returning whether or not it is a match.
+///
+/// Returns:
+/// - `Ok(Some(i32))` - the index of the first matching item.
+/// - `Ok(None)` - if no item matches.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let numbers = [1, 2, 3, 4];
+///     let array = cjson_create_int_array(&numbers[0], 4);
+///
+///     let index = cjson_array_position(array, |item| item.valuedouble == 3.0).unwrap();
+///     assert_eq!(index, Some(2));
+///
+///     let missing = cjson_array_position(array, |item| item.valuedouble == 5.0).unwrap();
+///     assert_eq!(missing, None);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_array_position<F: Fn(&Json) -> bool>(
+    array: *mut Json,
+    predicate: F,
+) -> Result<Option<i32>, JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot search a non-array Json item".to_string(),
+        ));
+    }
+
+    Ok(child_items(array)
+        .into_iter()
+        .position(|item| predicate(unsafe { &*item }))
+        .map(|index| index as i32))
+}
+
+/// Get an item from Json item of type `Array`, where negative indices count from the end (`-1` is
+/// the last item).
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array`.
+/// - `index: isize` - Index of the item; `0..len` from the start, `-len..-1` from the end.
+///
+/// Returns:
+/// - `Ok(Some(*mut Json))` - a mutable pointer to the item.
+/// - `Ok(None)` - if the index is out of range.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let names = ["Alice", "Bob", "Chloe"];
+///     let array = cjson_create_string_array_safe(&names).unwrap();
+///
+///     let last = cjson_array_get_rev(array, -1).unwrap().unwrap();
+///     assert_eq!(cjson_get_string_value(last).unwrap(), "Chloe");
+///     let first = cjson_array_get_rev(array, -3).unwrap().unwrap();
+///     assert_eq!(cjson_get_string_value(first).unwrap(), "Alice");
+///     let second = cjson_array_get_rev(array, 1).unwrap().unwrap();
+///     assert_eq!(cjson_get_string_value(second).unwrap(), "Bob");
+///
+///     assert_eq!(cjson_array_get_rev(array, -4).unwrap(), None);
+///     assert_eq!(cjson_array_get_rev(array, 3).unwrap(), None);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_array_get_rev(array: *mut Json, index: isize) -> Result<Option<*mut Json>, JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot get item from a non-array Json item".to_string(),
+        ));
+    }
+
+    let items = child_items(array);
+    let position = if index < 0 {
+        items.len().checked_sub(index.unsigned_abs())
+    } else {
+        Some(index as usize)
+    };
+    Ok(position.and_then(|position| items.get(position).copied()))
+}
+
+/// Check whether Json item of type `Array` contains an item equivalent to `needle` (compared with
+/// `cjson_compare`, so nested arrays and objects are compared by structure and value).
+///
+/// Args:
+/// - `array: *mut Json` - The Json item of type `Array` to search.
+/// - `needle: *mut Json` - Mutable pointer to the Json item to look for.
+/// - `case_sensitive: bool` - Boolean value specifying whether or not to do case-sensitive comparison
+/// for object keys.
+///
+/// Returns:
+/// - `Ok(bool)` - whether or not an equivalent item was found.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let array = cjson_parse_json(r#"[1,{"name":"Nemuel","tags":["a"]},"x"]"#.to_string()).unwrap();
+///
+///     let needle = cjson_parse_json(r#"{"tags":["a"],"name":"Nemuel"}"#.to_string()).unwrap();
+///     assert_eq!(cjson_array_contains(array, needle, true).unwrap(), true);
+///
+///     let other = cjson_parse_json(r#"{"name":"Nemuel"}"#.to_string()).unwrap();
+///     assert_eq!(cjson_array_contains(array, other, true).unwrap(), false);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_array_contains(
+    array: *mut Json,
+    needle: *mut Json,
+    case_sensitive: bool,
+) -> Result<bool, JsonError> {
+    if !array.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot search a non-array Json item".to_string(),
+        ));
+    }
+
+    Ok(child_items(array).into_iter().any(|item| unsafe {
+        cJSON_Compare(
+            item as *const cJSON,
+            needle as *const cJSON,
+            if case_sensitive { 1 } else { 0 },
+        ) == 1
+    }))
+}
+
+/// Get the string value of a Json item of type `String`.
+///
+/// Args:
+/// - `item: *mut Json` - Mutable pointer to the Json item of type `String` whose string value we
+/// want to get.
+///
+/// Returns:
+/// - `Ok(String)` - if the string value is successfully gotten.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `String`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_string("Nemuel".to_string()).unwrap();
+///     assert_eq!(cjson_get_string_value(json).unwrap(), "Nemuel".to_string());
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_get_string_value(item: *mut Json) -> Result<String, JsonError> {
+    if !item.is_type_string() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot get string value from a non-string Json item".to_string(),
+        ));
+    }
+
+    let c_str = unsafe { cJSON_GetStringValue(item as *mut cJSON) };
+    Ok(unsafe {
+        CStr::from_ptr(c_str)
+            .to_str()
+            .unwrap_or_default()
+            .to_string()
+    })
+}
+
+/// Get the string value of a Json item of type `String` without copying it when possible.
+///
+/// The value is borrowed from the item when it is valid UTF-8, so no allocation happens. Otherwise
+/// invalid sequences are replaced with `U+FFFD` in an owned copy.
+///
+/// Args:
+/// - `item: &Json` - Reference to the Json item of type `String` whose string value we want to get.
+///
+/// Returns:
+/// - `Ok(Cow<str>)` - the string value, borrowed for as long as `item` is.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `String`.
+/// - `Err(JsonError::NullPointer)` - if the item holds no string value.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+/// use std::borrow::Cow;
+///
+/// fn main() {
+///     let json = OwnedJson::from("Nemuel");
+///     assert!(matches!(cjson_get_string_cow(&json).unwrap(), Cow::Borrowed("Nemuel")));
+///
+///     // make the first byte invalid UTF-8
+///     unsafe { *json.valuestring = 0xFFu8 as _ };
+///     let value = cjson_get_string_cow(&json).unwrap();
+///     assert!(matches!(value, Cow::Owned(_)));
+///     assert_eq!(value, "\u{FFFD}emuel");
+///
+///     assert!(matches!(cjson_get_string_cow(&OwnedJson::from(1.0)), Err(JsonError::InvalidTypeError(_))));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_get_string_cow(item: &Json) -> Result<Cow<'_, str>, JsonError> {
+    if !item.is_type_string() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot get string value from a non-string Json item".to_string(),
+        ));
+    }
+    if item.valuestring.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    let bytes = unsafe { CStr::from_ptr(item.valuestring) }.to_bytes();
+    Ok(String::from_utf8_lossy(bytes))
+}
+
+/// Get the number value of a Json item of type `Number`.
+///
+/// Args:
+/// - `item: *mut Json` - Mutable pointer to the Json item of type `Number` whose number value we
+/// want to get.
+///
+/// Returns:
+/// - `Ok(i32)` - if the number value is successfully gotten.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Number`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_create_number(254.0);
+///     assert_eq!(cjson_get_number_value(json).unwrap(), 254.0);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_get_number_value(item: *mut Json) -> Result<f64, JsonError> {
+    if !item.is_type_number() {
+        Err(JsonError::InvalidTypeError(
+            "cannot get number value from a non-number Json item".to_string(),
+        ))
+    } else {
+        Ok(unsafe { cJSON_GetNumberValue(item as *const cJSON) })
+    }
+}
+
+/// Get the number value of a Json item of type `Number`, or of a Json item of type `Raw` holding a
+/// JSON number (eg. one added with `cjson_add_raw_to_object`).
+///
+/// Args:
+/// - `item: *mut Json` - Pointer to the Json item of type `Number` or `Raw`.
+///
+/// Returns:
+/// - `Ok(f64)` - the number value of the item.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the item is neither of type `Number` nor `Raw`.
+/// - `Err(JsonError::ParseError)` - if the content of the Json item of type `Raw` is not exactly one
+/// JSON number (surrounding whitespace aside).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let object = cjson_create_object();
+///     cjson_add_number_to_object(object, "native", 20.0).unwrap();
+///     cjson_add_raw_to_object(object, "raw", "42").unwrap();
+///     cjson_add_raw_to_object(object, "list", "[1,2]").unwrap();
+///
+///     let native = cjson_get_object_item(object, "native").unwrap();
+///     let raw = cjson_get_object_item(object, "raw").unwrap();
+///     let list = cjson_get_object_item(object, "list").unwrap();
+///     assert_eq!(cjson_parse_number(native).unwrap(), 20.0);
+///     assert!(cjson_get_number_value(raw).is_err());
+///     assert_eq!(cjson_parse_number(raw).unwrap(), 42.0);
+///     assert!(matches!(cjson_parse_number(list), Err(JsonError::ParseError)));
+///
+///     cjson_add_raw_to_object(object, "trailing", "42abc").unwrap();
+///     let trailing = cjson_get_object_item(object, "trailing").unwrap();
+///     assert!(matches!(cjson_parse_number(trailing), Err(JsonError::ParseError)));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_parse_number(item: *mut Json) -> Result<f64, JsonError> {
+    if item.is_type_number() {
+        return cjson_get_number_value(item);
+    }
+    if !item.is_type_raw() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot parse a number from a non-number, non-raw Json item".to_string(),
+        ));
+    }
+
+    // cJSON prints raw values verbatim (and fails on a null one)
+    let raw = match print_bytes(item, false).map(String::from_utf8) {
+        Ok(Ok(raw)) => raw,
+        _ => return Err(JsonError::ParseError),
+    };
+    let parsed = match cjson_parse_exact(&raw) {
+        Ok(parsed) => parsed,
+        Err(_) => return Err(JsonError::ParseError),
+    };
+    let value = if parsed.is_type_number() {
+        Ok(unsafe { cJSON_GetNumberValue(parsed as *const cJSON) })
+    } else {
+        Err(JsonError::ParseError)
+    };
+    unsafe { cJSON_Delete(parsed as *mut cJSON) };
+    value
+}
+
+/// Add Json item of type `Null` to Json item of type `Object`.
+///
+/// Args:
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `Null` to.
+/// - `name: &str` - Key to set for the item being added.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `Null` that has been added.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let object = cjson_create_object();
+///     cjson_add_null_to_object(object, "test").unwrap();
+///     let test_null_item = cjson_get_object_item(object, "test").unwrap();
+///     assert_eq!(test_null_item.is_type_null(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_add_null_to_object(object: *mut Json, name: &str) -> Result<*mut Json, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(name) {
+        Ok(c_str) => {
+            let result =
+                unsafe { cJSON_AddNullToObject(object as *mut cJSON, c_str.as_ptr()) as *mut Json };
+            Ok(result)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Add Json item of type `True` to Json item of type `Object`.
+///
+/// Args:
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `True` to.
+/// - `name: &str` - Key to set for the item being added.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `True` that has been added.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let object = cjson_create_object();
+///     cjson_add_true_to_object(object, "test").unwrap();
+///     let test_true_item = cjson_get_object_item(object, "test").unwrap();
+///     assert_eq!(test_true_item.is_type_true(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_add_true_to_object(object: *mut Json, name: &str) -> Result<*mut Json, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(name) {
+        Ok(c_str) => {
+            let result =
+                unsafe { cJSON_AddTrueToObject(object as *mut cJSON, c_str.as_ptr()) as *mut Json };
+            Ok(result)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Add Json item of type `False` to Json item of type `Object`.
+///
+/// Args:
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `False` to.
+/// - `name: &str` - Key to set for the item being added.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `False` that has been added.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let object = cjson_create_object();
+///     cjson_add_false_to_object(object, "test").unwrap();
+///     let test_false_item = cjson_get_object_item(object, "test").unwrap();
+///     assert_eq!(test_false_item.is_type_false(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_add_false_to_object(object: *mut Json, name: &str) -> Result<*mut Json, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(name) {
+        Ok(c_str) => {
+            let result = unsafe {
+                cJSON_AddFalseToObject(object as *mut cJSON, c_str.as_ptr()) as *mut Json
+            };
+            Ok(result)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Add Json item of type `Bool` to Json item of type `Object`.
+///
+/// Args:
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `Bool` to.
+/// - `name: &str` - Key to set for the item being added.
+/// - `boolean: bool` - Boolean value for the Json item being added (true or false).
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `Bool` that has been added.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let object = cjson_create_object();
+///     cjson_add_bool_to_object(object, "test", true).unwrap();
+///     let test_bool_item = cjson_get_object_item(object, "test").unwrap();
+///     assert_eq!(test_bool_item.is_type_bool(), true);
+///     assert_eq!(test_bool_item.is_type_true(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_add_bool_to_object(
+    object: *mut Json,
+    name: &str,
+    boolean: bool,
+) -> Result<*mut Json, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(name) {
+        Ok(c_str) => {
+            let result = unsafe {
+                cJSON_AddBoolToObject(
+                    object as *mut cJSON,
+                    c_str.as_ptr(),
+                    if boolean { 1 } else { 0 },
+                ) as *mut Json
+            };
+            Ok(result)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Add Json item of type `Number` to Json item of type `Object`.
+///
+/// Args:
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `Number` to.
+/// - `name: &str` - Key to set for the item being added.
+/// - `number: f64` - Number value for the Json item being added.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `Number` that has been added.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let object = cjson_create_object();
+///     cjson_add_number_to_object(object, "kenya", 254.0).unwrap();
+///     let test_number_item = cjson_get_object_item(object, "kenya").unwrap();
+///     assert_eq!(test_number_item.is_type_number(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_add_number_to_object(
+    object: *mut Json,
+    name: &str,
+    number: f64,
+) -> Result<*mut Json, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(name) {
+        Ok(c_str) => {
+            let result = unsafe {
+                cJSON_AddNumberToObject(object as *mut cJSON, c_str.as_ptr(), number) as *mut Json
+            };
+            Ok(result)
         }
+        Err(err) => Err(JsonError::CStringError(err)),
     }
+}
 
-    /// Generate a string representation of the JSON object into a preallocated buffer.
-    ///
-    /// Args:
-    /// - `buffer: *mut i8`: Preallocated buffer where the generated string will be stored.
-    /// - `length: i32`: Number of bytes to write (preferably equal to the size of the allocated buffer).
-    /// - `format: bool`: Whether or not to have the output formatted/pretty-printed.
-    ///
-    /// Returns:
-    /// - `Ok(())` - if all goes well.
-    /// - `Err(NullPointer)` - if the pointer is null.
-    /// - `Err(PrintPreallocatedError)` - if an error occurs during the string generation or copying
-    /// into the buffer.
-    ///
-    /// Example:
-    /// ```rust
-    /// use cjson_rs::*;
-    /// use libc::malloc;
-    /// use std::ffi::CStr;
-    ///
-    /// fn main() {
-    ///     let json: *mut Json = cjson_create_object();
-    ///     let buffer: *mut i8 = unsafe { malloc(8) as *mut i8 };
-    ///     match json.print_preallocated(buffer, 8, false) {
-    ///         Ok(_) => unsafe {
-    ///             let c_str = CStr::from_ptr(buffer);
-    ///            let result = c_str.to_str().unwrap_or_default().to_string();
-    ///             assert_eq!(result, "{}");
-    ///             println!("Test passed!");
-    ///         },
-    ///         Err(err) => eprintln!("{}", err),
-    ///     }
-    /// }
-    /// ```
-    fn print_preallocated(
-        &self,
-        buffer: *mut i8,
-        length: i32,
-        format: bool,
-    ) -> Result<(), JsonError> {
-        match unsafe { self.as_mut() } {
-            Some(json) => {
-                if json.print_preallocated(buffer, length, format) {
-                    Ok(())
-                } else {
-                    Err(JsonError::PrintPreallocatedError)
-                }
+/// Add Json item of type `String` to Json item of type `Object`.
+///
+/// Args:
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `String` to.
+/// - `name: &str` - Key to set for the item being added.
+/// - `string: &str` - String value for the Json item being added.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `String` that has been added.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let object = cjson_create_object();
+///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
+///     let test_string_item = cjson_get_object_item(object, "name").unwrap();
+///     assert_eq!(test_string_item.is_type_string(), true);
+///     assert_eq!(
+///         cjson_get_string_value(test_string_item).unwrap(), "Nemuel"
+///     );
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_add_string_to_object(
+    object: *mut Json,
+    name: &str,
+    string: &str,
+) -> Result<*mut Json, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(name) {
+        Ok(name_c_str) => match CString::new(string) {
+            Ok(string_c_str) => {
+                let result = unsafe {
+                    cJSON_AddStringToObject(
+                        object as *mut cJSON,
+                        name_c_str.as_ptr(),
+                        string_c_str.as_ptr(),
+                    ) as *mut Json
+                };
+                Ok(result)
             }
-            None => Err(JsonError::NullPointer),
-        }
+            Err(err) => Err(JsonError::CStringError(err)),
+        },
+        Err(err) => Err(JsonError::CStringError(err)),
     }
+}
 
-    /// Generate an **unformatted** string representation of the JSON object eg.
-    /// ```json
-    /// {
-    ///     "name": "Nemuel",
-    ///     "age": 20
-    /// }
-    /// ```
-    ///
-    /// Returns:
-    /// - `Ok(String)` - if the JSON object's string representation is successfully generated.
-    /// - `Err(JsonError::NullPointer)` - if the pointer is null.
-    /// - `Err(JsonError::PrintError)` - if the string generation fails.
-    ///
-    /// Example:
-    /// ```rust
-    /// use cjson_rs::*;
-    ///
-    /// fn main() {
-    ///     let json: *mut Json = cjson_create_object();
-    ///     match json.print() {
-    ///         Ok(result) => assert_eq!(result, "{\n}"),
-    ///         Err(err) => eprintln!("{}", err),
-    ///     }
-    /// }
-    /// ```
-    fn print_unformatted(&self) -> Result<String, JsonError> {
-        match unsafe { self.as_mut() } {
-            Some(json) => json.print_unformatted(),
-            None => Err(JsonError::NullPointer),
-        }
+/// Add Json item of type `Raw` to Json item of type `Object`.
+///
+/// Args:
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `Raw` to.
+/// - `name: &str` - Key to set for the item being added.
+/// - `raw: &str` - Raw string (JSON or otherwise).
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `Raw` that has been added.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let object: *mut Json = cjson_create_object();
+///     cjson_add_raw_to_object(object, "name", "Nemuel").unwrap();
+///     let test_raw_item = cjson_get_object_item(object, "name").unwrap();
+///     assert_eq!(test_raw_item.is_type_raw(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_add_raw_to_object(
+    object: *mut Json,
+    name: &str,
+    raw: &str,
+) -> Result<*mut Json, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
     }
 
-    /// Delete a JSON entity and all its subentities.
-    ///
-    /// Example:
-    /// ```rust
-    /// use cjson_rs::*;
-    ///
-    /// fn main() {
-    ///     let json: *mut Json = cjson_create_object();
-    ///     json.delete();
-    /// }
-    /// ```
-    fn delete(&self) {
-        unsafe { self.as_mut().map(|json| json.delete()) };
+    match CString::new(name) {
+        Ok(name_c_str) => match CString::new(raw) {
+            Ok(raw_c_str) => {
+                let result = unsafe {
+                    cJSON_AddRawToObject(
+                        object as *mut cJSON,
+                        name_c_str.as_ptr(),
+                        raw_c_str.as_ptr(),
+                    ) as *mut Json
+                };
+                Ok(result)
+            }
+            Err(err) => Err(JsonError::CStringError(err)),
+        },
+        Err(err) => Err(JsonError::CStringError(err)),
     }
 }
 
-/// Remove all unnecessary whitespace and formatting from a JSON string.
+/// Add Json item of type `Object` to Json item of type `Object`.
 ///
 /// Args:
-/// - `json: String` - The JSON string to be minified.
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `Object` to.
+/// - `name: &str` - Key to set for the item being added.
 ///
 /// Returns:
-/// - `Ok(())` - if the operation gets performed.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `Object` that has been added.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let mut json_str: String = "{\n\t\"name\": \"Nemuel\",\n\t\"age\": 20\n}".to_string();
-///     cjson_minify(&mut json_str).unwrap();
-///     assert_eq!(json_str, r#"{"name":"Nemuel","age":20}"#);
+///     let object: *mut Json = cjson_create_object();
+///     cjson_add_object_to_object(object, "test").unwrap();
+///     let test_object_item = cjson_get_object_item(object, "test").unwrap();
+///     assert_eq!(test_object_item.is_type_object(), true);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_add_object_to_object(object: *mut Json, name: &str) -> Result<*mut Json, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(name) {
+        Ok(c_str) => {
+            let result = unsafe {
+                cJSON_AddObjectToObject(object as *mut cJSON, c_str.as_ptr()) as *mut Json
+            };
+            Ok(result)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
+}
+
+/// Add Json item of type `Array` to Json item of type `Object`.
+///
+/// Args:
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `Array` to.
+/// - `name: &str` - Key to set for the item being added.
+///
+/// Returns:
+/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `Array` that has been added.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let object: *mut Json = cjson_create_object();
+///     cjson_add_array_to_object(object, "test").unwrap();
+///     let test_array_item = cjson_get_object_item(object, "test").unwrap();
+///     assert_eq!(test_array_item.is_type_array(), true);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_minify(json: &mut String) -> Result<(), JsonError> {
-    match CString::new((*json).as_bytes()) {
+pub fn cjson_add_array_to_object(object: *mut Json, name: &str) -> Result<*mut Json, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(name) {
         Ok(c_str) => {
-            let c_str_mut_ptr = c_str.as_ptr() as *mut i8;
-            unsafe { cJSON_Minify(c_str_mut_ptr) };
-            let minified = unsafe { CStr::from_ptr(c_str_mut_ptr) };
-            *json = minified.to_string_lossy().into_owned();
-            Ok(())
+            let result = unsafe {
+                cJSON_AddArrayToObject(object as *mut cJSON, c_str.as_ptr()) as *mut Json
+            };
+            Ok(result)
         }
         Err(err) => Err(JsonError::CStringError(err)),
     }
 }
 
-/// Parse a JSON string into a Json object.
+/// Add Json item of any type to Json item of type `Object`.
+///
+/// NOTE: Existing members with the same key are kept, so the object ends up with duplicate keys.
+/// Use `cjson_upsert_to_object` to replace them instead.
 ///
 /// Args:
-/// - `value: String`: The JSON string to be parsed. Providing an empty string will result in
-/// JsonError::EmptyStringError.
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item to.
+/// - `string: &str` - Key to set for the item being added.
+/// - `item: *mut Json` - Json item to be added.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - if the parsing happens successfully.
-/// - `Err(JsonError::EmptyStringError)` - if the provided `value` string is empty (can't parse an
-/// empty string).
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+/// - `Ok(bool)` - a boolean value indicating whether or not the operation was successful.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be added to is not of type `Object`.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let value  = "{\"name\":\"Nemuel\", \"age\":20}".to_string();
-///     match cjson_parse_json(value) {
-///         Ok(json) => println!("{}", json.print().unwrap()),
-///         Err(err) => eprintln!("{}", err),
-///     }
-/// }
-/// ```
-///
-/// Output:
-/// ```json
-/// {
-///     "name": "Nemuel",
-///     "age":  20
+///     let test_item = cjson_create_null();
+///     let object = cjson_create_object();
+///     assert_eq!(
+///         cjson_add_item_to_object(object, "test", test_item).unwrap(),
+///         true
+///     );
+///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_parse_json(value: String) -> Result<*mut Json, JsonError> {
-    if value.is_empty() {
-        return Err(JsonError::EmptyStringError);
+pub fn cjson_add_item_to_object(
+    object: *mut Json,
+    string: &str,
+    item: *mut Json,
+) -> Result<bool, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
     }
 
-    match CString::new(value) {
+    match CString::new(string) {
         Ok(c_str) => {
-            let json = unsafe { cJSON_Parse(c_str.as_ptr()) };
-            if json.is_null() {
-                Err(JsonError::ParseError)
+            let success = unsafe {
+                cJSON_AddItemToObject(object as *mut cJSON, c_str.as_ptr(), item as *mut cJSON)
+            };
+            if success == 1 {
+                Ok(true)
             } else {
-                Ok(json as *mut Json)
+                Ok(false)
             }
         }
         Err(err) => Err(JsonError::CStringError(err)),
     }
 }
 
-/// Parse a specific length of a JSON string into a Json object.
+/// Add Json item of any type to Json item of type `Object`, refusing to create a cycle.
+///
+/// Adding an object to itself (or to one of its own descendants) would make printing and deleting
+/// it loop forever or free items twice, so the subtree of `item` is searched for `object` first.
 ///
 /// Args:
-/// - `value: String`: The JSON string to be parsed. Providing an empty string will result in
-/// JsonError::EmptyStringError.
-/// - `buffer_length: usize`: Length of the JSON string to be parsed.
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item to.
+/// - `string: &str` - Key to set for the item being added.
+/// - `item: *mut Json` - Json item to be added.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - if the parsing happens successfully.
-/// - `Err(JsonError::EmptyStringError)` - if the provided `value` string is empty (can't parse an
-/// empty string).
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+/// - `Ok(bool)` - a boolean value indicating whether or not the operation was successful.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be added to is not of type `Object`.
+/// - `Err(JsonError::CycleDetected)` - if `object` is `item` itself or nested inside it.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided key contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let value = "{\"rps\":500} more text".to_string();
-///     match cjson_parse_json_with_length(value, 11) {
-///         Ok(json) => println!("{}", json.print().unwrap()),
-///         Err(err) => eprintln!("{}", err),
-///     }
-/// }
-/// ```
+///     let root = cjson_create_object();
+///     let child = cjson_create_object();
+///     assert!(cjson_add_item_to_object_checked(root, "child", child).unwrap());
 ///
-/// Output:
-/// ```json
-/// {
-///     "rps": 500
+///     let result = cjson_add_item_to_object_checked(child, "root", root);
+///     assert!(matches!(result, Err(JsonError::CycleDetected)));
+///     assert_eq!(root.print_unformatted().unwrap(), r#"{"child":{}}"#);
+///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_parse_json_with_length(
-    value: String,
-    buffer_length: usize,
-) -> Result<*mut Json, JsonError> {
-    if value.is_empty() {
-        return Err(JsonError::EmptyStringError);
+pub fn cjson_add_item_to_object_checked(
+    object: *mut Json,
+    string: &str,
+    item: *mut Json,
+) -> Result<bool, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
     }
-
-    match CString::new(value) {
-        Ok(c_str) => {
-            let json = unsafe { cJSON_ParseWithLength(c_str.as_ptr(), buffer_length) };
-            if json.is_null() {
-                Err(JsonError::ParseError)
-            } else {
-                Ok(json as *mut Json)
-            }
-        }
-        Err(err) => Err(JsonError::CStringError(err)),
+    if subtree_contains(item, object) {
+        return Err(JsonError::CycleDetected);
     }
+    cjson_add_item_to_object(object, string, item)
 }
 
-/// Parse a JSON string into a Json object (with additional options).
+/// Add Json item of any type to Json item of type `Object`, replacing every existing member with the
+/// same key (case-sensitive).
+///
+/// Like `cjson_object_set_number`, `item` takes the place of the first member with the key (so the
+/// member order is kept) and any further members with that key are deleted; a new key is added at
+/// the end.
+/// On error the object is left untouched and `item` still belongs to the caller.
 ///
 /// Args:
-/// - `value: String`: The JSON string to be parsed. Providing an empty string will result in
-/// JsonError::EmptyStringError.
-/// - `return_parse_end: *mut *const char` - Mutable pointer to constant character pointer that will
-/// indicate where parsing ended.
-/// - `require_null_terminated: bool` - Boolean value specifying whether or not the JSON string should be
-/// null terminated.
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item to.
+/// - `key: &str` - Key to set for the item being added.
+/// - `item: *mut Json` - Json item to be added (which must not belong to another array or object).
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - if the parsing happens successfully.
-/// - `Err(JsonError::EmptyStringError)` - if the provided `value` string is empty (can't parse an
-/// empty string).
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+/// - `Ok(usize)` - the number of members that were replaced (0 if the key was new).
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be added to is not of type `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if `key` contains a null byte.
+/// - `Err(JsonError::NullPointer)` - if `item` is null or could not be added.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
-/// use core::ffi::c_char;
 ///
 /// fn main() {
-///     let value = "{\"rps\":500}";
-///     let mut return_parse_end: *const c_char = std::ptr::null_mut();
-///     match cjson_parse_json_with_opts(value, &mut return_parse_end, false) {
-///         Ok(json) => {
-///             println!("{}", json.print().unwrap());
-///         }
-///         Err(err) => eprintln!("{}", err),
-///     }
-/// }
-/// ```
-///
-/// Output:
-/// ```json
-/// {
-///     "rps": 500
+///     let object = cjson_create_object();
+///     assert_eq!(cjson_upsert_to_object(object, "x", cjson_create_number(1.0)).unwrap(), 0);
+///     assert_eq!(cjson_upsert_to_object(object, "x", cjson_create_number(2.0)).unwrap(), 1);
+///     assert_eq!(object.print_unformatted().unwrap(), r#"{"x":2}"#);
+///
+///     // duplicates left behind by cjson_add_item_to_object are cleaned up as well
+///     cjson_add_item_to_object(object, "x", cjson_create_number(3.0)).unwrap();
+///     assert_eq!(cjson_upsert_to_object(object, "x", cjson_create_true()).unwrap(), 2);
+///     assert_eq!(object.print_unformatted().unwrap(), r#"{"x":true}"#);
+///
+///     // existing members keep their position
+///     cjson_add_number_to_object(object, "y", 4.0).unwrap();
+///     cjson_upsert_to_object(object, "x", cjson_create_false()).unwrap();
+///     assert_eq!(object.print_unformatted().unwrap(), r#"{"x":false,"y":4}"#);
+///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_parse_json_with_opts(
-    value: &str,
-    return_parse_end: &mut *const c_char,
-    require_null_terminated: bool,
-) -> Result<*mut Json, JsonError> {
-    if value.is_empty() {
-        return Err(JsonError::EmptyStringError);
+pub fn cjson_upsert_to_object(
+    object: *mut Json,
+    key: &str,
+    item: *mut Json,
+) -> Result<usize, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
+    }
+    if item.is_null() {
+        return Err(JsonError::NullPointer);
     }
 
-    match CString::new(value) {
-        Ok(c_str) => {
-            let json = unsafe {
-                cJSON_ParseWithOpts(
-                    c_str.as_ptr(),
-                    return_parse_end as *mut *const i8,
-                    if require_null_terminated { 1 } else { 0 },
-                )
-            };
-            if json.is_null() {
-                Err(JsonError::ParseError)
-            } else {
-                Ok(json as *mut Json)
-            }
+    let existing: Vec<*mut Json> = child_items(object)
+        .into_iter()
+        .filter(|&member| {
+            let string = unsafe { (*member).string };
+            !string.is_null() && unsafe { CStr::from_ptr(string) }.to_bytes() == key.as_bytes()
+        })
+        .collect();
+    replace_or_add_member(object, key, item as *mut cJSON)?;
+    // the first match was replaced in place, only the duplicates after it are left to delete
+    for &member in existing.iter().skip(1) {
+        unsafe {
+            cJSON_DetachItemViaPointer(object as *mut cJSON, member as *mut cJSON);
+            cJSON_Delete(member as *mut cJSON);
         }
-        Err(err) => Err(JsonError::CStringError(err)),
     }
+    Ok(existing.len())
 }
 
-/// Parse a specific length of a JSON string into a Json object (with additional options).
+/// Add an item to Json item of type `Object` while maintaining a reference to the original item rather
+/// than copying it.
 ///
 /// Args:
-/// - `value: String`: The JSON string to be parsed. Providing an empty string will result in
-/// JsonError::EmptyStringError.
-/// - `buffer_length: usize`: Length of the JSON string to be parsed.
-/// - `return_parse_end: *mut *const char` - Mutable pointer to constant character pointer that will
-/// indicate where parsing ended.
-/// - `require_null_terminated: bool` - Boolean value specifying whether or not the JSON string should be
-/// null terminated.
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item to.
+/// - `string: &str` - Key to set for the item being added.
+/// - `item: *mut Json` - Json item to be added.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - if the parsing happens successfully.
-/// - `Err(JsonError::EmptyStringError)` - if the provided `value` string is empty (can't parse an
-/// empty string).
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+/// - `Ok(bool)` - a boolean value indicating whether or not the operation was successful.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be added to is not of type `Object`.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
-/// use core::ffi::c_char;
 ///
 /// fn main() {
-///     let value = "{\"rps\":500}";
-///     let mut return_parse_end: *const c_char = std::ptr::null_mut();
-///     match cjson_parse_json_with_length_opts(value, 11, &mut return_parse_end, false) {
-///         Ok(json) => {
-///             println!("{}", json.print().unwrap());
-///         }
-///         Err(err) => eprintln!("{}", err),
-///     }
-/// }
-/// ```
-///
-/// Output:
-/// ```json
-/// {
-///     "rps": 500
+///     let test_item = cjson_create_null();
+///     let object = cjson_create_object();
+///     assert_eq!(
+///         cjson_add_item_reference_to_object(object, "test", test_item).unwrap(),
+///         true
+///     );
+///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_parse_json_with_length_opts(
-    value: &str,
-    buffer_length: usize,
-    return_parse_end: &mut *const c_char,
-    require_null_terminated: bool,
-) -> Result<*mut Json, JsonError> {
-    if value.is_empty() {
-        return Err(JsonError::EmptyStringError);
+pub fn cjson_add_item_reference_to_object(
+    object: *mut Json,
+    string: &str,
+    item: *mut Json,
+) -> Result<bool, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
     }
 
-    match CString::new(value) {
+    match CString::new(string) {
         Ok(c_str) => {
-            let json = unsafe {
-                cJSON_ParseWithLengthOpts(
+            let success = unsafe {
+                cJSON_AddItemReferenceToObject(
+                    object as *mut cJSON,
                     c_str.as_ptr(),
-                    buffer_length,
-                    return_parse_end as *mut *const i8,
-                    if require_null_terminated { 1 } else { 0 },
+                    item as *mut cJSON,
                 )
             };
-            if json.is_null() {
-                Err(JsonError::ParseError)
+            if success == 1 {
+                Ok(true)
             } else {
-                Ok(json as *mut Json)
+                Ok(false)
             }
         }
         Err(err) => Err(JsonError::CStringError(err)),
     }
 }
 
-/// Get error message associated with the last parsing operation that failed.
+/// Add Json item of any type to Json item of type `Object` with a name/key that is a constant
+/// or reference.
+///
+/// cJSON keeps a pointer to `name` instead of copying it, so the key has to outlive the object;
+/// hence the `&'static CStr` (eg. a `c"key"` literal).
+///
+/// Args:
+/// - `object: *mut Json` - Json item of type `Object` to add the Json item to.
+/// - `name: &'static CStr` - Key to set for the item being added.
+/// - `item: *mut Json` - Json item to be added.
 ///
 /// Returns:
-/// - `Some(String)` - if an error message exists.
-/// - `None` - if there is no error message.
-pub fn cjson_get_error_ptr() -> Option<String> {
-    let c_str = unsafe { cJSON_GetErrorPtr() };
-    if !c_str.is_null() {
-        let c_str_ref = unsafe { CStr::from_ptr(c_str) };
-        Some(c_str_ref.to_str().unwrap_or_default().to_string())
+/// - `Ok(bool)` - a boolean value indicating whether or not the operation was successful.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be added to is not of type `Object`.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let test_item = cjson_create_null();
+///     let object = cjson_create_object();
+///     assert_eq!(
+///         cjson_add_item_to_object_cs(object, c"test", test_item).unwrap(),
+///         true
+///     );
+///
+///     for i in 0..100 {
+///         cjson_add_number_to_object(object, &format!("n{}", i), i as f64).unwrap();
+///     }
+///     assert!(cjson_get_object_item(object, "test").unwrap().is_type_null());
+///     assert!(object.print_unformatted().unwrap().starts_with(r#"{"test":null,"n0":0"#));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_add_item_to_object_cs(
+    object: *mut Json,
+    name: &'static CStr,
+    item: *mut Json,
+) -> Result<bool, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot add item to a non-object Json item".to_string(),
+        ));
+    }
+
+    let success =
+        unsafe { cJSON_AddItemToObjectCS(object as *mut cJSON, name.as_ptr(), item as *mut cJSON) };
+    if success == 1 {
+        Ok(true)
     } else {
-        None
+        Ok(false)
     }
 }
 
-/// Create Json item of type `Raw`.
+/// Check whether or not the Json item of type `Object` contains an item with the specified key.
 ///
 /// Args:
-/// - `raw: String` - Raw string (JSON or otherwise).
+/// - `object: *mut Json` - The Json item of type `Object` to inspect for an item with the specified key.
+/// - `string: &str` - String slice specifying the key of the item being looked for.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the created Json item of type `Raw`.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+/// - `Ok(bool)` - a boolean value indicating whether or not an item with the specified key exists within
+/// the object if the lookup is successful.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice (representing the key)
+/// contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let json = cjson_create_raw("\"count\": 5".to_string()).unwrap();
-///     println!("{}", json.print().unwrap()); // output: "count": 5
+///     let object = cjson_create_object();
+///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
+///
+///     assert_eq!(cjson_has_object_item(object, "name").unwrap(), true);
+///     assert_eq!(cjson_has_object_item(object, "age").unwrap(), false);
+///
+///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_raw(raw: String) -> Result<*mut Json, JsonError> {
-    match CString::new(raw) {
+pub fn cjson_has_object_item(object: *mut Json, string: &str) -> Result<bool, JsonError> {
+    match CString::new(string) {
         Ok(c_str) => {
-            let json = unsafe { cJSON_CreateRaw(c_str.as_ptr()) as *mut Json };
-            Ok(json)
+            let result = unsafe { cJSON_HasObjectItem(object as *const cJSON, c_str.as_ptr()) };
+            if result == 1 {
+                Ok(true)
+            } else {
+                Ok(false)
+            }
         }
         Err(err) => Err(JsonError::CStringError(err)),
     }
 }
 
-/// Create Json item of type `Null`.
+/// Get item within the object with the specified key.
+///
+/// Args:
+/// - `object: *mut Json` - Json item of type `Object` from which we want to get an item.
+/// - `string: &str` - Key of the Json item that we want to get.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the created Json item of type `Null`.
+/// - `Ok(*mut Json)` - a mutable pointer to the Json item with the provided key if gotten successfully.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let json = cjson_create_null();
-///     assert_eq!(json.is_type_null(), true);
+///     let object = cjson_create_object();
+///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
+///
+///     let item = cjson_get_object_item(object, "name").unwrap();
+///     assert_eq!(item.is_type_string(), true);
+///     assert_eq!(cjson_get_string_value(item).unwrap(), "Nemuel");
+///
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_null() -> *mut Json {
-    unsafe { cJSON_CreateNull() as *mut Json }
+pub fn cjson_get_object_item(object: *mut Json, string: &str) -> Result<*mut Json, JsonError> {
+    match CString::new(string) {
+        Ok(c_str) => {
+            let result =
+                unsafe { cJSON_GetObjectItem(object as *const cJSON, c_str.as_ptr()) as *mut Json };
+            Ok(result)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
 }
 
-/// Create a new JSON object (instance of the Json struct).
+/// Get item within the object with the specified key, without allocating for short keys.
 ///
-/// Example:
-/// ```rust
-/// use cjson_rs::{cjson_create_object, Json};
+/// Behaves like `cjson_get_object_item`, but keys shorter than 64 bytes are null-terminated in a
+/// stack buffer instead of a heap-allocated `CString`, which matters for lookups in tight loops.
 ///
-/// fn main() {
-///     let json: *mut Json = cjson_create_object();
-/// }
-/// ```
-pub fn cjson_create_object() -> *mut Json {
-    unsafe { cJSON_CreateObject() as *mut Json }
-}
-
-/// Create Json item of type `True`.
+/// Args:
+/// - `object: *mut Json` - Json item of type `Object` from which we want to get an item.
+/// - `key: &str` - Key of the Json item that we want to get.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the created Json item of type `True`.
+/// - `Ok(*mut Json)` - a mutable pointer to the Json item with the provided key (null if there is
+/// none).
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let json = cjson_create_true();
-///     assert_eq!(json.is_type_true(), true);
+///     let long_key = "k".repeat(200);
+///     let object = cjson_create_object();
+///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
+///     cjson_add_number_to_object(object, &long_key, 20.0).unwrap();
+///
+///     let name = cjson_get_object_item_borrowed(object, "name").unwrap();
+///     assert_eq!(cjson_get_string_value(name).unwrap(), "Nemuel");
+///     let long = cjson_get_object_item_borrowed(object, &long_key).unwrap();
+///     assert_eq!(cjson_get_number_value(long).unwrap(), 20.0);
+///     assert!(cjson_get_object_item_borrowed(object, "age").unwrap().is_null());
+///     assert!(cjson_get_object_item_borrowed(object, "na\0me").is_err());
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_true() -> *mut Json {
-    unsafe { cJSON_CreateTrue() as *mut Json }
+pub fn cjson_get_object_item_borrowed(
+    object: *mut Json,
+    key: &str,
+) -> Result<*mut Json, JsonError> {
+    const STACK_KEY_CAPACITY: usize = 64;
+
+    if key.len() >= STACK_KEY_CAPACITY || key.as_bytes().contains(&0) {
+        return cjson_get_object_item(object, key);
+    }
+
+    let mut buffer = [0u8; STACK_KEY_CAPACITY];
+    buffer[..key.len()].copy_from_slice(key.as_bytes());
+    let result = unsafe {
+        cJSON_GetObjectItem(object as *const cJSON, buffer.as_ptr() as *const c_char) as *mut Json
+    };
+    Ok(result)
 }
 
-/// Create Json item of type `False`.
+/// Get the first member of a Json item of type `Object` whose key matches regardless of case, for
+/// keys outside of ASCII as well.
+///
+/// `cjson_get_object_item` only ignores the case of ASCII letters. Here both keys are compared after
+/// Unicode lowercasing (`str::to_lowercase`), so eg. `"ÉTÉ"` matches `"été"`. This is simple case
+/// mapping rather than full case folding: `"STRASSE"` does not match `"straße"`. Keys that are not
+/// valid UTF-8 have the invalid sequences replaced by `U+FFFD` before comparing.
+///
+/// Args:
+/// - `object: *mut Json` - Json item of type `Object` from which we want to get an item.
+/// - `key: &str` - Key of the Json item that we want to get.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the created Json item of type `False`.
+/// - `Ok(Some(*mut Json))` - a mutable pointer to the first matching member.
+/// - `Ok(None)` - if no key matches.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `object` value provided is not of type
+/// `Object`.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let json = cjson_create_false();
-///     assert_eq!(json.is_type_false(), true);
+///     let object = cjson_parse_json(r#"{"name":"Nemuel","été":"summer"}"#.to_string()).unwrap();
+///
+///     let name = cjson_get_object_item_folded(object, "NAME").unwrap().unwrap();
+///     assert_eq!(cjson_get_string_value(name).unwrap(), "Nemuel");
+///     let summer = cjson_get_object_item_folded(object, "ÉTÉ").unwrap().unwrap();
+///     assert_eq!(cjson_get_string_value(summer).unwrap(), "summer");
+///     assert!(cjson_get_object_item(object, "ÉTÉ").unwrap().is_null());
+///     assert!(cjson_get_object_item_folded(object, "age").unwrap().is_none());
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_false() -> *mut Json {
-    unsafe { cJSON_CreateFalse() as *mut Json }
+pub fn cjson_get_object_item_folded(
+    object: *mut Json,
+    key: &str,
+) -> Result<Option<*mut Json>, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot get item from a non-object Json item".to_string(),
+        ));
+    }
+
+    let folded = key.to_lowercase();
+    Ok(child_items(object)
+        .into_iter()
+        .find(|&member| match unsafe { (*member).string.as_ref() } {
+            Some(member_key) => {
+                unsafe { CStr::from_ptr(member_key) }
+                    .to_string_lossy()
+                    .to_lowercase()
+                    == folded
+            }
+            None => false,
+        }))
 }
 
-/// Create Json item of type `Bool`.
+/// Get item within the object with the specified key, with a case-sensitive comparison of keys.
 ///
 /// Args:
-/// - `boolean: bool`: Boolean value for the Json item to create (true or false).
+/// - `object: *mut Json` - Json item of type `Object` from which we want to get an item.
+/// - `string: &str` - Key of the Json item that we want to get.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the created Json item of type `Bool`.
+/// - `Ok(*mut Json)` - a mutable pointer to the Json item with the provided key if gotten successfully.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let json = cjson_create_bool(true);
-///     assert_eq!(json.is_type_bool(), true);
+///     let object = cjson_create_object();
+///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
+///
+///     let item = cjson_get_object_item_case_sensitive(object, "Name").unwrap();
+///     assert_eq!(item.is_null(), true);
+///     let item = cjson_get_object_item_case_sensitive(object, "name").unwrap();
+///     assert_eq!(item.is_null(), false);
+///     assert_eq!(item.is_type_string(), true);
+///
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_bool(boolean: bool) -> *mut Json {
-    unsafe { cJSON_CreateBool(if boolean { 1 } else { 0 }) as *mut Json }
+pub fn cjson_get_object_item_case_sensitive(
+    object: *mut Json,
+    string: &str,
+) -> Result<*mut Json, JsonError> {
+    match CString::new(string) {
+        Ok(c_str) => {
+            let result = unsafe {
+                cJSON_GetObjectItemCaseSensitive(object as *const cJSON, c_str.as_ptr())
+                    as *mut Json
+            };
+            Ok(result)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
 }
 
-/// Create Json item of type `Number`.
+/// A view into a single member of Json item of type `Object`, which may either be present or absent.
+///
+/// Returned by `cjson_entry`, mirroring `HashMap::entry` from the standard library.
+///
+/// Variants:
+/// - `Occupied(*mut Json)`: The key is present; holds a mutable pointer to its value.
+/// - `Vacant(*mut Json, CString)`: The key is absent; holds the object and the key to insert under.
+pub enum Entry {
+    Occupied(*mut Json),
+    Vacant(*mut Json, CString),
+}
+
+impl Entry {
+    /// Insert the item if the key is absent, then return a pointer to the value under the key.
+    ///
+    /// If the key is already present, `item` is **not** added (nor deleted) and the caller
+    /// remains responsible for it. If the key is absent but adding `item` fails, `item` is deleted.
+    ///
+    /// Args:
+    /// - `item: *mut Json` - The Json item to add under the key if it is absent.
+    ///
+    /// Returns:
+    /// - `*mut Json` - a mutable pointer to the existing or newly added value (null if adding the item
+    /// failed, in which case the item has been deleted).
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     let object = cjson_create_object();
+    ///
+    ///     let item = cjson_entry(object, "count")
+    ///         .unwrap()
+    ///         .or_insert(cjson_create_number(1.0));
+    ///     assert_eq!(cjson_get_number_value(item).unwrap(), 1.0);
+    ///     assert_eq!(object.print_unformatted().unwrap(), r#"{"count":1}"#);
+    ///
+    ///     let failed = cjson_entry(object, "missing").unwrap().or_insert(std::ptr::null_mut());
+    ///     assert!(failed.is_null());
+    ///     println!("Test passed"); // output: Test passed
+    /// }
+    /// ```
+    pub fn or_insert(self, item: *mut Json) -> *mut Json {
+        match self {
+            Entry::Occupied(existing) => existing,
+            Entry::Vacant(object, key) => {
+                let success = unsafe {
+                    cJSON_AddItemToObject(object as *mut cJSON, key.as_ptr(), item as *mut cJSON)
+                };
+                if success == 1 {
+                    item
+                } else {
+                    // cJSON refuses to add an object to itself, which must not be deleted here
+                    if item != object {
+                        unsafe { cJSON_Delete(item as *mut cJSON) };
+                    }
+                    std::ptr::null_mut()
+                }
+            }
+        }
+    }
+
+    /// Run the closure on the value under the key if it is present.
+    ///
+    /// Args:
+    /// - `f: F` - Closure receiving a mutable pointer to the existing value.
+    ///
+    /// Returns:
+    /// - `Entry` - the same entry, so that it can be chained with `or_insert`.
+    ///
+    /// Example:
+    /// ```rust
+    /// use cjson_rs::*;
+    ///
+    /// fn main() {
+    ///     let object = cjson_create_object();
+    ///     cjson_add_number_to_object(object, "count", 1.0).unwrap();
+    ///
+    ///     let item = cjson_entry(object, "count")
+    ///         .unwrap()
+    ///         .and_modify(|item| {
+    ///             let count = cjson_get_number_value(item).unwrap();
+    ///             cjson_set_number_helper(item, count + 1.0).unwrap();
+    ///         })
+    ///         .or_insert(cjson_create_number(1.0));
+    ///     assert_eq!(cjson_get_number_value(item).unwrap(), 2.0);
+    ///     assert_eq!(object.print_unformatted().unwrap(), r#"{"count":2}"#);
+    ///     println!("Test passed"); // output: Test passed
+    /// }
+    /// ```
+    pub fn and_modify<F: FnOnce(*mut Json)>(self, f: F) -> Entry {
+        if let Entry::Occupied(existing) = self {
+            f(existing);
+        }
+        self
+    }
+}
+
+/// Get the entry for the specified key in Json item of type `Object` (case-sensitive comparison of
+/// keys), for in-place manipulation.
 ///
 /// Args:
-/// - `num: f64`: Numeric value for the Json item to create.
+/// - `object: *mut Json` - Json item of type `Object` in which to look for the key.
+/// - `key: &str` - Key of the member whose entry we want.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the created Json item of type `Number`.
+/// - `Ok(Entry)` - `Entry::Occupied` if the key is present, `Entry::Vacant` otherwise.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let json = cjson_create_number(254.0);
-///     assert_eq!(json.is_type_number(), true);
+///     let object = cjson_create_object();
+///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
+///
+///     assert_eq!(matches!(cjson_entry(object, "name").unwrap(), Entry::Occupied(_)), true);
+///     assert_eq!(matches!(cjson_entry(object, "Name").unwrap(), Entry::Vacant(..)), true);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_number(num: f64) -> *mut Json {
-    unsafe { cJSON_CreateNumber(num) as *mut Json }
+pub fn cjson_entry(object: *mut Json, key: &str) -> Result<Entry, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot get entry of a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(key) {
+        Ok(c_str) => {
+            let existing = unsafe {
+                cJSON_GetObjectItemCaseSensitive(object as *const cJSON, c_str.as_ptr())
+                    as *mut Json
+            };
+            if existing.is_null() {
+                Ok(Entry::Vacant(object, c_str))
+            } else {
+                Ok(Entry::Occupied(existing))
+            }
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
 }
 
-/// Set the number value for a Json item of type `Number` to the specified value.
+/// Get a nested item by following a sequence of keys through Json items of type `Object`
+/// (case-sensitive comparison of keys).
 ///
 /// Args:
-/// - `item: *mut Json` - Mutable pointer to Json item of type `Number` whose number value is to be set.
-/// - `number: f64` - The number value to set for the Json item of type `Number`.
+/// - `root: *mut Json` - Json item of type `Object` to start the lookup from.
+/// - `segments: &[&str]` - Keys to follow, from the outermost to the innermost. An empty slice yields
+/// the root itself.
 ///
 /// Returns:
-/// - `Ok(f64)` - if the operation happens successfully.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the provided Json item is not of type `Number`.
+/// - `Ok(Some(*mut Json))` - a mutable pointer to the item at the end of the path.
+/// - `Ok(None)` - if one of the keys is missing along the way.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the root or an intermediate value is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if one of the keys contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let number_item = cjson_create_number(1.0);
-///     assert_eq!(cjson_get_number_value(number_item).unwrap(), 1.0);
+///     let value = r#"{"user":{"address":{"city":"Nairobi"}}}"#.to_string();
+///     let root = cjson_parse_json(value).unwrap();
 ///
-///     let new_number_value = cjson_set_number_helper(number_item, 2.0).unwrap();
-///     assert_eq!(new_number_value, 2.0);
+///     let city = cjson_get_path(root, &["user", "address", "city"]).unwrap().unwrap();
+///     assert_eq!(cjson_get_string_value(city).unwrap(), "Nairobi");
 ///
-///     assert_eq!(cjson_get_number_value(number_item).unwrap(), 2.0);
+///     let missing = cjson_get_path(root, &["user", "phone", "number"]).unwrap();
+///     assert_eq!(missing.is_none(), true);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_set_number_helper(object: *mut Json, number: f64) -> Result<f64, JsonError> {
-    if !object.is_type_number() {
-        Err(JsonError::InvalidTypeError(
-            "cannot set number value for a non-number Json item".to_string(),
-        ))
-    } else {
-        Ok(unsafe { cJSON_SetNumberHelper(object as *mut cJSON, number) })
+pub fn cjson_get_path(root: *mut Json, segments: &[&str]) -> Result<Option<*mut Json>, JsonError> {
+    let mut current = root;
+    for &segment in segments {
+        if !current.is_type_object() {
+            return Err(JsonError::InvalidTypeError(format!(
+                "cannot look up \"{}\" in a non-object Json item",
+                segment
+            )));
+        }
+
+        current = match CString::new(segment) {
+            Ok(c_str) => unsafe {
+                cJSON_GetObjectItemCaseSensitive(current as *const cJSON, c_str.as_ptr())
+                    as *mut Json
+            },
+            Err(err) => return Err(JsonError::CStringError(err)),
+        };
+        if current.is_null() {
+            return Ok(None);
+        }
     }
+    Ok(Some(current))
 }
 
-/// Create Json item of type `String` (copies the string).
+/// Apply a JSON Patch (RFC 6902) to a copy of a Json item.
+///
+/// The `add`, `remove`, `replace`, `move`, `copy` and `test` operations are supported, with their
+/// targets given as JSON Pointers (RFC 6901). Operations are applied in order to a recursive
+/// duplicate of `doc`; if any of them fails the copy is deleted, so `doc` and `patch` are never
+/// modified.
 ///
 /// Args:
-/// - `string: String`: String value for the Json item to create.
+/// - `doc: *mut Json` - Mutable pointer to the Json item to be patched.
+/// - `patch: *mut Json` - Json item of type `Array` holding the patch operations.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the created Json item of type `String`.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+/// - `Ok(*mut Json)` - a mutable pointer to the patched copy (to be deleted by the caller).
+/// - `Err(JsonError::NullPointer)` - if `doc` is null or could not be duplicated.
+/// - `Err(JsonError::InvalidTypeError(String))` - if `patch` is not of type `Array`.
+/// - `Err(JsonError::InvalidPatchError(String))` - if an operation is malformed.
+/// - `Err(JsonError::InvalidPointerError(String))` - if a pointer is malformed or its target does
+/// not exist.
+/// - `Err(JsonError::PatchTestFailed(String))` - if a `test` operation fails (holds its path).
+/// - `Err(JsonError::CStringError(NulError))` - if an object key contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let json = cjson_create_string("Nemuel".to_string()).unwrap();
-///     assert_eq!(json.is_type_string(), true);
+///     let doc = cjson_parse_json(r#"{"a":1,"b":{"c":[1,2]},"d":"x"}"#.to_string()).unwrap();
+///     let patch = cjson_parse_json(
+///         r#"[
+///             {"op":"test","path":"/a","value":1},
+///             {"op":"add","path":"/b/c/1","value":5},
+///             {"op":"remove","path":"/d"},
+///             {"op":"replace","path":"/a","value":true},
+///             {"op":"move","from":"/b/c","path":"/list"},
+///             {"op":"copy","from":"/list/0","path":"/b/first"}
+///         ]"#
+///         .to_string(),
+///     )
+///     .unwrap();
+///
+///     let patched = cjson_apply_patch(doc, patch).unwrap();
+///     assert_eq!(
+///         patched.print_unformatted().unwrap(),
+///         r#"{"a":true,"b":{"first":1},"list":[1,5,2]}"#
+///     );
+///     assert_eq!(doc.print_unformatted().unwrap(), r#"{"a":1,"b":{"c":[1,2]},"d":"x"}"#);
+///
+///     let failing = cjson_parse_json(r#"[{"op":"test","path":"/d","value":"y"}]"#.to_string()).unwrap();
+///     match cjson_apply_patch(doc, failing) {
+///         Err(JsonError::PatchTestFailed(path)) => assert_eq!(path, "/d"),
+///         _ => panic!("the test operation should have failed"),
+///     }
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_string(string: String) -> Result<*mut Json, JsonError> {
-    match CString::new(string) {
-        Ok(c_str) => {
-            let json = unsafe { cJSON_CreateString(c_str.as_ptr()) as *mut Json };
-            Ok(json)
+pub fn cjson_apply_patch(doc: *mut Json, patch: *mut Json) -> Result<*mut Json, JsonError> {
+    if !patch.is_type_array() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot apply a non-array Json item as a patch".to_string(),
+        ));
+    }
+
+    let mut result = unsafe { cJSON_Duplicate(doc as *const cJSON, 1) as *mut Json };
+    if result.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    for operation in child_items(patch) {
+        if let Err(err) = apply_patch_operation(&mut result, operation) {
+            unsafe { cJSON_Delete(result as *mut cJSON) };
+            return Err(err);
         }
-        Err(err) => Err(JsonError::CStringError(err)),
     }
+    Ok(result)
 }
 
-/// Set the string value of a Json item of type `String` to the specified value.
+/// Set the value at the location referred to by a JSON Pointer (RFC 6901), replacing and deleting
+/// the item already there.
+///
+/// The parent of the location has to exist. Object members are replaced or added, array items are
+/// replaced in place, and the `-` token (or the index one past the last item) appends to an array.
+/// Ownership of `value` is always taken: it is deleted if it cannot be set.
 ///
 /// Args:
-/// - `item: *mut Json` - Mutable pointer to Json item of type `String` whose string value is to be set.
-/// - `valuestring: &str` - String slice specifying the value to set for the Json item of type `String`.
+/// - `root: *mut Json` - Mutable pointer to the document to modify.
+/// - `pointer: &str` - JSON Pointer to the location, eg. `/a/b/0` (it cannot be `""`, as the root
+/// itself cannot be replaced through this pointer).
+/// - `value: *mut Json` - The Json item to set (which must not belong to another item).
 ///
 /// Returns:
-/// - `Ok(String)` - if the operation happens successfully.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the provided Json item is not of type `String`.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+/// - `Ok(())` - if the value was set.
+/// - `Err(JsonError::NullPointer)` - if `root` or `value` is null.
+/// - `Err(JsonError::InvalidPointerError(String))` - if the pointer is malformed or empty, its
+/// parent does not exist or is not an array or object, or an array index is out of range.
+/// - `Err(JsonError::CStringError(NulError))` - if the object key contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let string_item = cjson_create_string("Nemuel".to_string()).unwrap();
-///     assert_eq!(cjson_get_string_value(string_item).unwrap(), "Nemuel");
+///     let doc = cjson_parse_json(r#"{"a":{"b":1},"arr":[1,2]}"#.to_string()).unwrap();
 ///
-///     let new_string_value = cjson_set_value_string(string_item, "Wainaina").unwrap();
-///     assert_eq!(new_string_value, "Wainaina");
+///     cjson_set_pointer(doc, "/a/b", cjson_create_string("one".to_string()).unwrap()).unwrap();
+///     cjson_set_pointer(doc, "/a/c", cjson_create_true()).unwrap();
+///     cjson_set_pointer(doc, "/arr/-", cjson_create_number(3.0)).unwrap();
+///     cjson_set_pointer(doc, "/arr/0", cjson_create_null()).unwrap();
+///     assert_eq!(
+///         doc.print_unformatted().unwrap(),
+///         r#"{"a":{"b":"one","c":true},"arr":[null,2,3]}"#
+///     );
 ///
-///     assert_eq!(cjson_get_string_value(string_item).unwrap(), "Wainaina");
+///     let result = cjson_set_pointer(doc, "/missing/key", cjson_create_null());
+///     assert!(matches!(result, Err(JsonError::InvalidPointerError(_))));
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_set_value_string(object: *mut Json, valuestring: &str) -> Result<String, JsonError> {
-    if !object.is_type_string() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot set string value for a non-string Json item".to_string(),
-        ));
+pub fn cjson_set_pointer(
+    root: *mut Json,
+    pointer: &str,
+    value: *mut Json,
+) -> Result<(), JsonError> {
+    if root.is_null() || value.is_null() {
+        unsafe { cJSON_Delete(value as *mut cJSON) };
+        return Err(JsonError::NullPointer);
     }
-
-    match CString::new(valuestring) {
-        Ok(c_str) => {
-            let c_str_ptr = unsafe { cJSON_SetValuestring(object as *mut cJSON, c_str.as_ptr()) };
-            let str = unsafe { CStr::from_ptr(c_str_ptr).to_string_lossy().into_owned() };
-            Ok(str)
+    let tokens = match parse_json_pointer(pointer) {
+        Ok(tokens) if !tokens.is_empty() => tokens,
+        Ok(_) => {
+            unsafe { cJSON_Delete(value as *mut cJSON) };
+            return Err(JsonError::InvalidPointerError(
+                "cannot replace the whole document".to_string(),
+            ));
         }
-        Err(err) => Err(JsonError::CStringError(err)),
+        Err(err) => {
+            unsafe { cJSON_Delete(value as *mut cJSON) };
+            return Err(err);
+        }
+    };
+
+    // the pointer is not empty, so the root itself is never swapped out
+    let mut root = root;
+    if resolve_pointer(root, &tokens).is_some() {
+        replace_at_pointer(&mut root, &tokens, value)
+    } else {
+        add_at_pointer(&mut root, &tokens, value)
     }
 }
 
-/// Create Json item of type `Array`.
+/// Detach the item at the location referred to by a JSON Pointer (RFC 6901) and hand it over.
+///
+/// Args:
+/// - `root: *mut Json` - Mutable pointer to the document to modify.
+/// - `pointer: &str` - JSON Pointer to the object member or array item to remove, eg. `/a/0` (it
+/// cannot be `""`, as the root has no parent to be detached from).
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the created Json item of type `Array`.
+/// - `Ok(Some(*mut Json))` - the detached item (to be deleted by the caller).
+/// - `Ok(None)` - if nothing exists at the location.
+/// - `Err(JsonError::NullPointer)` - if `root` is null.
+/// - `Err(JsonError::InvalidPointerError(String))` - if the pointer is malformed or empty.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let json = cjson_create_array();
-///     assert_eq!(json.is_type_array(), true);
+///     let doc = cjson_parse_json(r#"{"a":{"b":1,"c":2},"arr":[1,2,3]}"#.to_string()).unwrap();
+///
+///     let mut member = cjson_remove_pointer(doc, "/a/b").unwrap().unwrap();
+///     assert_eq!(cjson_get_number_value(member).unwrap(), 1.0);
+///     cjson_delete(&mut member);
+///
+///     let mut item = cjson_remove_pointer(doc, "/arr/1").unwrap().unwrap();
+///     assert_eq!(cjson_get_number_value(item).unwrap(), 2.0);
+///     cjson_delete(&mut item);
+///
+///     assert!(cjson_remove_pointer(doc, "/a/missing").unwrap().is_none());
+///     assert!(cjson_remove_pointer(doc, "/arr/7").unwrap().is_none());
+///     assert_eq!(doc.print_unformatted().unwrap(), r#"{"a":{"c":2},"arr":[1,3]}"#);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_array() -> *mut Json {
-    unsafe { cJSON_CreateArray() as *mut Json }
+pub fn cjson_remove_pointer(
+    root: *mut Json,
+    pointer: &str,
+) -> Result<Option<*mut Json>, JsonError> {
+    if root.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+    let tokens = parse_json_pointer(pointer)?;
+    if tokens.is_empty() {
+        return Err(JsonError::InvalidPointerError(
+            "cannot remove the whole document".to_string(),
+        ));
+    }
+
+    if resolve_pointer(root, &tokens).is_none() {
+        return Ok(None);
+    }
+    detach_at_pointer(root, &tokens).map(Some)
 }
 
-/// Create Json item of type `String`.
+/// Compute a JSON Patch (RFC 6902) that turns one Json item into another.
 ///
-/// It points directly to the string. This means the `valuestring` field of the Json struct will
-/// not be deleted by `cjson_delete`, and you are therefore responsible for its lifetime (useful
-/// for constants).
+/// Objects and arrays are compared recursively: object members missing from `to` are removed,
+/// new ones are added, and array items are compared by index (surplus items are removed from the
+/// back, missing ones are appended). Any other difference is emitted as a `replace` of the whole
+/// value at that location.
 ///
 /// Args:
-/// - `string: String`: String value for the Json item to create.
+/// - `from: *mut Json` - Mutable pointer to the source Json item.
+/// - `to: *mut Json` - Mutable pointer to the target Json item.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the created Json item of type `String`.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string contains a null byte.
+/// - `Ok(*mut Json)` - a Json item of type `Array` holding the patch operations (to be deleted by the
+/// caller); it is empty if both items are equivalent.
+/// - `Err(JsonError::NullPointer)` - if either pointer is null or the patch could not be allocated.
+/// - `Err(JsonError::CStringError(NulError))` - if a generated path contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let json = cjson_create_string_reference("Nemuel".to_string()).unwrap();
-///     assert_eq!(json.is_type_string(), true);
+///     let from = cjson_parse_json(r#"{"a":1,"b":{"c":"x","d":[1,2,3]},"e":null}"#.to_string()).unwrap();
+///     let to = cjson_parse_json(r#"{"a":2,"b":{"c":"x","d":[1,4]},"f":[true]}"#.to_string()).unwrap();
+///
+///     let patch = cjson_diff(from, to).unwrap();
+///     assert_eq!(
+///         patch.print_unformatted().unwrap(),
+///         concat!(
+///             r#"[{"op":"replace","path":"/a","value":2},"#,
+///             r#"{"op":"replace","path":"/b/d/1","value":4},"#,
+///             r#"{"op":"remove","path":"/b/d/2"},"#,
+///             r#"{"op":"remove","path":"/e"},"#,
+///             r#"{"op":"add","path":"/f","value":[true]}]"#
+///         )
+///     );
+///
+///     let patched = cjson_apply_patch(from, patch).unwrap();
+///     assert_eq!(cjson_compare(patched, to, true), true);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_string_reference(string: String) -> Result<*mut Json, JsonError> {
-    match CString::new(string) {
-        Ok(c_str) => {
-            let json = unsafe { cJSON_CreateStringReference(c_str.as_ptr()) as *mut Json };
-            Ok(json)
+pub fn cjson_diff(from: *mut Json, to: *mut Json) -> Result<*mut Json, JsonError> {
+    if from.is_null() || to.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    let patch = unsafe { cJSON_CreateArray() as *mut Json };
+    if patch.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    match diff_items(from, to, &mut Vec::new(), patch) {
+        Ok(()) => Ok(patch),
+        Err(err) => {
+            unsafe { cJSON_Delete(patch as *mut cJSON) };
+            Err(err)
         }
-        Err(err) => Err(JsonError::CStringError(err)),
     }
 }
 
-/// Create Json item of type `Array` that doesn't "own" its content.
+/// Replace item with specified key in Json item of type `Object`.
 ///
 /// Args:
-/// - `child: *mut Json`: Json item of type `Array` to create a reference to.
+/// - `object: *mut Json` - Json item of type `Object` within which the replacement is to happen.
+/// - `string: &str` - The key of the Json item to be replaced.
+/// - `newitem: *mut Json` - Item replacing the original one.
 ///
 /// Returns:
-/// - `*mut Json` - a reference to the provided Json item of type `Array`.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the provided Json item is not of type `Array`.
+/// - `Ok(bool)` - a boolean value indicating whether or not the operation was successful.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item being operated on is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let child = cjson_create_array();
-///     let reference = cjson_create_array_reference(child).unwrap();
-///     assert_eq!(reference.is_type_array(), true);
+///     let object = cjson_create_object();
+///     let original_item = cjson_create_string("Nemuel".to_string()).unwrap();
+///     cjson_add_item_to_object(object, "name", original_item).unwrap();
+///
+///     let new_item = cjson_create_string("Wainaina".to_string()).unwrap();
+///     let result = cjson_replace_item_in_object(object, "name", new_item).unwrap();
+///     assert_eq!(result, true);
+///     assert_eq!(
+///         cjson_get_string_value(cjson_get_object_item(object, "name").unwrap()).unwrap(),
+///         "Wainaina"
+///     );
+///
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_array_reference(child: *mut Json) -> Result<*mut Json, JsonError> {
-    if !child.is_type_array() {
-        Err(JsonError::InvalidTypeError(
-            "cannot create array reference to a non-array Json item".to_string(),
-        ))
-    } else {
-        let reference = unsafe { cJSON_CreateArrayReference(child as *mut cJSON) as *mut Json };
-        Ok(reference)
+pub fn cjson_replace_item_in_object(
+    object: *mut Json,
+    string: &str,
+    newitem: *mut Json,
+) -> Result<bool, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot replace item in a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(string) {
+        Ok(c_str) => {
+            let result = unsafe {
+                cJSON_ReplaceItemInObject(
+                    object as *mut cJSON,
+                    c_str.as_ptr(),
+                    newitem as *mut cJSON,
+                )
+            };
+            if result == 1 {
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
     }
 }
 
-/// Create Json item of type `Array` that doesn't "own" its content.
+/// Replace item with specified key in Json item of type `Object`, with a case-sensitive comparison of
+/// keys.
 ///
 /// Args:
-/// - `child: *mut Json`: Json item of type `Array` to create a reference to.
+/// - `object: *mut Json` - Json item of type `Object` within which the replacement is to happen.
+/// - `string: &str` - The key of the Json item to be replaced.
+/// - `newitem: *mut Json` - Item replacing the original one.
 ///
 /// Returns:
-/// - `*mut Json` - a reference to the provided Json item of type `Array`.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the provided Json item is not of type `Array`.
+/// - `Ok(bool)` - a boolean value indicating whether or not the operation was successful.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item being operated on is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let child = cjson_create_object();
-///     let reference = cjson_create_object_reference(child).unwrap();
-///     assert_eq!(reference.is_type_object(), true);
+///     let object = cjson_create_object();
+///     let original_item = cjson_create_string("Nemuel".to_string()).unwrap();
+///     cjson_add_item_to_object(object, "name", original_item).unwrap();
+///
+///     let new_item = cjson_create_string("Wainaina".to_string()).unwrap();
+///     let mut result = cjson_replace_item_in_object_case_sensitive(object, "Name", new_item).unwrap();
+///     assert_eq!(result, false);
+///     result = cjson_replace_item_in_object_case_sensitive(object, "name", new_item).unwrap();
+///     assert_eq!(result, true);
+///     assert_eq!(
+///         cjson_get_string_value(cjson_get_object_item(object, "name").unwrap()).unwrap(),
+///         "Wainaina"
+///     );
+///
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_object_reference(child: *mut Json) -> Result<*mut Json, JsonError> {
-    if !child.is_type_object() {
-        Err(JsonError::InvalidTypeError(
-            "cannot create object reference to a non-object Json item".to_string(),
-        ))
-    } else {
-        let reference = unsafe { cJSON_CreateObjectReference(child as *mut cJSON) as *mut Json };
-        Ok(reference)
+pub fn cjson_replace_item_in_object_case_sensitive(
+    object: *mut Json,
+    string: &str,
+    newitem: *mut Json,
+) -> Result<bool, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot replace item in a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(string) {
+        Ok(c_str) => {
+            let result = unsafe {
+                cJSON_ReplaceItemInObjectCaseSensitive(
+                    object as *mut cJSON,
+                    c_str.as_ptr(),
+                    newitem as *mut cJSON,
+                )
+            };
+            if result == 1 {
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
     }
 }
 
-/// Create Json item of type `Array` containing integers.
+/// Detach item from Json item of type `Object`.
 ///
 /// Args:
-/// - `numbers: *const i32` - Pointer to a signed 32-bit integer (start of the numbers array).
-/// - `count: i32` - Number of array elements to include in the `Array` being created (typically just the
-/// size of the `numbers` array).
+/// - `object: *mut Json` - Mutable pointer to the Json item of type `Object` from which an item is to
+/// be detached.
+/// - `string: &str` - The key value for the item that is to be detached from the object.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` containing integers.
+/// - `Ok(*mut Json)` - a mutable pointer to the detached item if the operation happens.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be operated on is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let numbers: [i32; 5] = [1, 2, 3, 4, 5];
-///     let json = cjson_create_int_array(&numbers[0], numbers.len() as i32);
-///     assert_eq!(json.is_type_array(), true);
+///     let object = cjson_create_object();
+///     let string_item = cjson_create_string("Nemuel".to_string()).unwrap();
+///
+///     cjson_add_item_to_object(object, "name", string_item).unwrap();
+///     assert_eq!(cjson_has_object_item(object, "name").unwrap(), true);
+///
+///     let detached_item = cjson_detach_item_from_object(object, "name").unwrap();
+///     assert_eq!(detached_item.is_type_string(), true);
+///     assert_eq!(cjson_has_object_item(object, "name").unwrap(), false);
+///
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_int_array(numbers: *const i32, count: i32) -> *mut Json {
-    unsafe { cJSON_CreateIntArray(numbers, count) as *mut Json }
+pub fn cjson_detach_item_from_object(
+    object: *mut Json,
+    string: &str,
+) -> Result<*mut Json, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot detach item from a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(string) {
+        Ok(c_str) => {
+            let detached_item = unsafe {
+                cJSON_DetachItemFromObject(object as *mut cJSON, c_str.as_ptr()) as *mut Json
+            };
+            Ok(detached_item)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
 }
 
-/// Create Json item of type `Array` containing single-precision floating-point values.
+/// Detach item from Json item of type `Object`, with a case-sensitive comparison of keys.
 ///
 /// Args:
-/// - `numbers: *const i32` - Pointer to a signed single-precision floating-point value (start of the
-/// numbers array).
-/// - `count: i32` - Number of array elements to include in the `Array` being created (typically just the
-/// size of the `numbers` array).
+/// - `object: *mut Json` - Mutable pointer to the Json item of type `Object` from which an item is to
+/// be detached.
+/// - `string: &str` - The key value for the item that is to be detached from the object.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` containing single-precision
-/// floating-point values.
-///
-/// Example:
-/// ```rust
-/// use cjson_rs::*;
-///
-/// fn main() {
-///     let numbers: [f32; 5] = [1.0, 2.0, 3.0, 4.0, 5.0];
-///     let json = cjson_create_float_array(&numbers[0], numbers.len() as i32);
-///     assert_eq!(json.is_type_array(), true);
-///     println!("Test passed"); // output: Test passed
-/// }
-/// ```
-pub fn cjson_create_float_array(numbers: *const f32, count: i32) -> *mut Json {
-    unsafe { cJSON_CreateFloatArray(numbers, count) as *mut Json }
+/// - `Ok(*mut Json)` - a mutable pointer to the detached item if the operation happens.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be operated on is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
+pub fn cjson_detach_item_from_object_case_sensitive(
+    object: *mut Json,
+    string: &str,
+) -> Result<*mut Json, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot detach item from a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(string) {
+        Ok(c_str) => {
+            let detached_item = unsafe {
+                cJSON_DetachItemFromObjectCaseSensitive(object as *mut cJSON, c_str.as_ptr())
+                    as *mut Json
+            };
+            Ok(detached_item)
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
 }
 
-/// Create Json item of type `Array` containing double-precision floating-point values.
+/// Detach item with the specified key from Json item of type `Object`, wrapped in a guard that
+/// deletes it unless it is re-attached or released.
 ///
 /// Args:
-/// - `numbers: *const i64` - Pointer to a signed double-precision floating-point value (start of the
-/// numbers array).
-/// - `count: i32` - Number of array elements to include in the `Array` being created (typically just the
-/// size of the `numbers` array).
+/// - `object: *mut Json` - Mutable pointer to the Json item of type `Object`.
+/// - `string: &str` - The key value for the item that is to be detached from the object.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` containing double-precision
-/// floating-point values.
+/// - `Ok(Some(DetachedJson))` - the guard owning the detached item.
+/// - `Ok(None)` - if the object has no item with that key.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be operated on is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let numbers: [f64; 5] = [1.0, 2.0, 3.0, 4.0, 5.0];
-///     let json = cjson_create_double_array(&numbers[0], numbers.len() as i32);
-///     assert_eq!(json.is_type_array(), true);
+///     let from = cjson_parse_json(r#"{"name":"Nemuel","age":20}"#.to_string()).unwrap();
+///     let to = cjson_create_object();
+///
+///     let detached = cjson_detach_item_from_object_owned(from, "name").unwrap().unwrap();
+///     detached.reattach(to).unwrap();
+///     assert_eq!(from.print_unformatted().unwrap(), r#"{"age":20}"#);
+///     assert_eq!(to.print_unformatted().unwrap(), r#"{"name":"Nemuel"}"#);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_double_array(numbers: *const f64, count: i32) -> *mut Json {
-    unsafe { cJSON_CreateDoubleArray(numbers, count) as *mut Json }
+pub fn cjson_detach_item_from_object_owned(
+    object: *mut Json,
+    string: &str,
+) -> Result<Option<DetachedJson>, JsonError> {
+    match cjson_detach_item_from_object(object, string) {
+        Ok(item) => Ok(NonNull::new(item).map(DetachedJson)),
+        Err(err) => Err(err),
+    }
 }
 
-/// Create Json item of type `Array` containing string values.
+/// Delete item with the specified key from Json item of type `Object`.
 ///
 /// Args:
-/// - `strings: &[&str]` - Reference to an array of string slices.
-/// - `count: i32` - Number of array elements to include in the `Array` being created (typically just the
-/// size of the `strings` array).
+/// - `object: *mut Json` - Mutable pointer to the Json item of type `Object` from which an item is to
+/// be deleted.
+/// - `string: &str` - The key value for the item that is to be deleted from the object.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the created Json item of type `Array` containing string values.
+/// - `Ok(())` - a mutable pointer to the detached item if the deletion operation happens.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be operated on is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let strings = ["Alice", "Bob", "Chloe"];
-///     let arr = cjson_create_string_array(&strings, strings.len() as i32).unwrap();
-///     assert_eq!(arr.is_type_array(), true);
+///     let object = cjson_create_object();
+///     let string_item = cjson_create_string("Nemuel".to_string()).unwrap();
+///
+///     cjson_add_item_to_object(object, "name", string_item).unwrap();
+///     assert_eq!(cjson_has_object_item(object, "name").unwrap(), true);
+///
+///     cjson_delete_item_from_object(object, "name").unwrap();
+///     assert_eq!(cjson_has_object_item(object, "name").unwrap(), false);
+///
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_create_string_array(strings: &[&str], count: i32) -> Result<*mut Json, JsonError> {
-    let mut c_strings: Vec<CString> = Vec::with_capacity(strings.len());
+pub fn cjson_delete_item_from_object(object: *mut Json, string: &str) -> Result<(), JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot delete item from a non-object Json item".to_string(),
+        ));
+    }
 
-    for &s in strings {
-        match CString::new(s) {
-            Ok(c_str) => c_strings.push(c_str),
-            Err(err) => return Err(JsonError::CStringError(err)),
+    match CString::new(string) {
+        Ok(c_str) => {
+            unsafe { cJSON_DeleteItemFromObject(object as *mut cJSON, c_str.as_ptr()) };
+            Ok(())
         }
+        Err(err) => Err(JsonError::CStringError(err)),
     }
+}
 
-    let pointers: Vec<*const c_void> = c_strings
-        .iter()
-        .map(|s| s.as_ptr() as *const c_void)
-        .collect();
+/// Delete item with the specified key from Json item of type `Object`, with a case-sensitive comparison
+/// of keys.
+///
+/// Args:
+/// - `object: *mut Json` - Mutable pointer to the Json item of type `Object` from which an item is to
+/// be deleted.
+/// - `string: &str` - The key value for the item that is to be deleted from the object.
+///
+/// Returns:
+/// - `Ok(())` - a mutable pointer to the detached item if the deletion operation happens.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be operated on is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
+pub fn cjson_delete_item_from_object_case_sensitive(
+    object: *mut Json,
+    string: &str,
+) -> Result<(), JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot delete item from a non-object Json item".to_string(),
+        ));
+    }
 
-    let array = unsafe {
-        cJSON_CreateStringArray(pointers.as_ptr() as *const *const c_char, count) as *mut Json
-    };
-    Ok(array)
+    match CString::new(string) {
+        Ok(c_str) => {
+            unsafe {
+                cJSON_DeleteItemFromObjectCaseSensitive(object as *mut cJSON, c_str.as_ptr())
+            };
+            Ok(())
+        }
+        Err(err) => Err(JsonError::CStringError(err)),
+    }
 }
 
-/// Get the size of Json item of type `Array`.
+/// Rename the key of a member of Json item of type `Object` (case-sensitive comparison of keys),
+/// keeping the member's value and position.
+///
+/// If a member with the new key already exists, it gets **overwritten**: that member is deleted
+/// before the rename happens.
 ///
 /// Args:
-/// - `array: *mut Json` - The Json item of type `Array` whose size we want.
+/// - `object: *mut Json` - Mutable pointer to the Json item of type `Object` whose member is to be renamed.
+/// - `old_key: &str` - The current key of the member.
+/// - `new_key: &str` - The key to give the member.
 ///
 /// Returns:
-/// - `Ok(i32)` - if the size of the Json item of type `Array` is successfully determined.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Ok(bool)` - `true` if the member was renamed, `false` if no member has the old key.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be operated on is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if either of the provided keys contains a null byte.
+/// - `Err(JsonError::NullPointer)` - if allocating memory for the new key fails.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let strings = ["Alice", "Bob", "Chloe", "Dan", "Eyal"];
-///     let arr = cjson_create_string_array(&strings, strings.len() as i32).unwrap();
-///     assert_eq!(cjson_get_array_size(arr).unwrap(), 5);
+///     let object = cjson_create_object();
+///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
+///     cjson_add_number_to_object(object, "age", 20.0).unwrap();
+///
+///     assert_eq!(cjson_rename_object_key(object, "name", "first_name").unwrap(), true);
+///     assert_eq!(
+///         object.print_unformatted().unwrap(),
+///         r#"{"first_name":"Nemuel","age":20}"#
+///     );
+///
+///     assert_eq!(cjson_rename_object_key(object, "surname", "last_name").unwrap(), false);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_get_array_size(array: *mut Json) -> Result<i32, JsonError> {
-    if !array.is_type_array() {
-        Err(JsonError::InvalidTypeError(
-            "cannot get array size for a non-array Json item".to_string(),
-        ))
-    } else {
-        Ok(unsafe { cJSON_GetArraySize(array as *const cJSON) })
+pub fn cjson_rename_object_key(
+    object: *mut Json,
+    old_key: &str,
+    new_key: &str,
+) -> Result<bool, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot rename key in a non-object Json item".to_string(),
+        ));
+    }
+
+    match CString::new(old_key) {
+        Ok(old_c_str) => match CString::new(new_key) {
+            Ok(new_c_str) => {
+                let item = unsafe {
+                    cJSON_GetObjectItemCaseSensitive(object as *const cJSON, old_c_str.as_ptr())
+                        as *mut Json
+                };
+                if item.is_null() {
+                    return Ok(false);
+                }
+                if old_key == new_key {
+                    return Ok(true);
+                }
+
+                // the key is freed by cJSON later on, so it must come from cJSON's allocator
+                let bytes = new_c_str.as_bytes_with_nul();
+                let key_copy = unsafe { cJSON_malloc(bytes.len()) as *mut i8 };
+                if key_copy.is_null() {
+                    return Err(JsonError::NullPointer);
+                }
+
+                unsafe {
+                    std::ptr::copy_nonoverlapping(
+                        bytes.as_ptr() as *const i8,
+                        key_copy,
+                        bytes.len(),
+                    );
+                    cJSON_DeleteItemFromObjectCaseSensitive(
+                        object as *mut cJSON,
+                        new_c_str.as_ptr(),
+                    );
+
+                    let is_const_key = (*item).type_ & cJSON_StringIsConst as i32 != 0;
+                    if !is_const_key && !(*item).string.is_null() {
+                        cJSON_free((*item).string as *mut c_void);
+                    }
+                    (*item).string = key_copy;
+                    (*item).type_ &= !(cJSON_StringIsConst as i32);
+                }
+                Ok(true)
+            }
+            Err(err) => Err(JsonError::CStringError(err)),
+        },
+        Err(err) => Err(JsonError::CStringError(err)),
     }
 }
 
-/// Get the item at the provided index of a Json item of type `Array`.
+/// Get the member values of Json item of type `Object`, in insertion order.
 ///
 /// Args:
-/// - `array: *mut Json` - The Json item of type `Array` from which we want to get an item.
-/// - `index: i32` - Index of the item we want to get from the Json item of type `Array`.
+/// - `object: *mut Json` - Mutable pointer to the Json item of type `Object`.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - mutable pointer to the item at the specified index.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Ok(Vec<*mut Json>)` - mutable pointers to the member values (still owned by the object); the
+/// key of each value is available through its `string` field.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item is not of type `Object`.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
+/// use std::ffi::CStr;
 ///
 /// fn main() {
-///     let strings = ["Alice", "Bob", "Chloe", "Dan", "Eyal"];
-///     let arr = cjson_create_string_array(&strings, strings.len() as i32).unwrap();
-///     match cjson_get_array_item(arr, 2) {
-///         Ok(item) => {
-///             println!("{}", item.print().unwrap()); // output: "Chloe"
-///         }
-///         Err(err) => eprintln!("{}", err),
-///     }
+///     let object = cjson_parse_json(r#"{"name":"Nemuel","age":20,"admin":false}"#.to_string()).unwrap();
+///     let values = cjson_object_values(object).unwrap();
+///     assert_eq!(values.len(), 3);
+///
+///     let keys: Vec<&str> = values
+///         .iter()
+///         .map(|&value| unsafe { CStr::from_ptr((*value).string) }.to_str().unwrap())
+///         .collect();
+///     assert_eq!(keys, ["name", "age", "admin"]);
+///
+///     assert_eq!(values[0].as_str(), Some("Nemuel"));
+///     assert_eq!(values[1].as_f64(), Some(20.0));
+///     assert_eq!(values[2].as_bool(), Some(false));
+///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_get_array_item(array: *mut Json, index: i32) -> Result<*mut Json, JsonError> {
-    if !array.is_type_array() {
-        Err(JsonError::InvalidTypeError(
-            "cannot get array item from a non-array Json item".to_string(),
-        ))
-    } else {
-        Ok(unsafe { cJSON_GetArrayItem(array as *const cJSON, index) as *mut Json })
+pub fn cjson_object_values(object: *mut Json) -> Result<Vec<*mut Json>, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot get the values of a non-object Json item".to_string(),
+        ));
     }
+    Ok(child_items(object))
 }
 
-/// Add an item to Json item of type `Array`.
+/// Sort the members of Json item of type `Object` by key in place (byte-wise, so uppercase before
+/// lowercase), by relinking the members rather than duplicating them.
+///
+/// The sort is stable, so members with equal keys keep their relative order. Members without a
+/// key (which cJSON never creates for objects it builds itself) are ordered first.
 ///
 /// Args:
-/// - `array: *mut Json` - The Json item of type `Array` where the item will be added.
-/// - `item: *mut Json` - The item to add to the Json item of type `Array`.
+/// - `object: *mut Json` - Mutable pointer to the Json item of type `Object` to be sorted.
 ///
 /// Returns:
-/// - `Ok(bool)` - indicating success or failure in adding the item to the Json item of type `Array`.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Ok(())` - if the members were sorted.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item is not of type `Object`.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let numbers = [1, 2, 3, 4];
-///     let arr = cjson_create_int_array(&numbers[0], 4);
-///     let item = cjson_create_number(5.0);
-///     let success = cjson_add_item_to_array(arr, item).unwrap();
-///     assert_eq!(success, true);
-///     assert_eq!(cjson_get_array_size(arr).unwrap(), 5);
+///     let object = cjson_parse_json(r#"{"pear":1,"apple":2,"Zebra":3,"mango":{"b":1,"a":2}}"#.to_string()).unwrap();
+///     cjson_sort_object(object).unwrap();
+///     assert_eq!(
+///         object.print_unformatted().unwrap(),
+///         r#"{"Zebra":3,"apple":2,"mango":{"b":1,"a":2},"pear":1}"#
+///     );
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_item_to_array(array: *mut Json, item: *mut Json) -> Result<bool, JsonError> {
-    if !array.is_type_array() {
-        Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-array Json item".to_string(),
-        ))
-    } else {
-        let result = unsafe { cJSON_AddItemToArray(array as *mut cJSON, item as *mut cJSON) };
-        if result == 1 {
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+pub fn cjson_sort_object(object: *mut Json) -> Result<(), JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot sort a non-object Json item".to_string(),
+        ));
     }
+
+    let mut members = child_items(object);
+    // None (no key) sorts before any key
+    members.sort_by_key(|&member| {
+        unsafe { (*member).string.as_ref() }.map(|key| unsafe { CStr::from_ptr(key) }.to_bytes())
+    });
+    relink_child_items(object, &members);
+    Ok(())
 }
 
-/// Add an item to Json item of type `Array` while maintaining a reference to the original item rather
-/// than copying it.
+/// Copy the value of a member (deeply) into another Json item of type `Object` under a new key.
 ///
 /// Args:
-/// - `array: *mut Json` - The Json item of type `Array` where the item will be added.
-/// - `item: *mut Json` - The item to add to the Json item of type `Array`.
+/// - `src_object: *mut Json` - The Json item of type `Object` holding the member to copy.
+/// - `src_key: &str` - Key (case-sensitive) of the member to copy.
+/// - `dst_object: *mut Json` - The Json item of type `Object` to add the copy to (it may be
+/// `src_object` itself).
+/// - `dst_key: &str` - Key under which the copy is added.
 ///
 /// Returns:
-/// - `Ok(bool)` - boolean value indicating success or failure of the operation.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Ok(true)` - if the member was copied.
+/// - `Ok(false)` - if `src_object` has no member with the key `src_key`.
+/// - `Err(JsonError::InvalidTypeError(String))` - if `src_object` or `dst_object` is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if `dst_key` contains a null byte.
+/// - `Err(JsonError::NullPointer)` - if the copy could not be allocated.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let numbers = [1, 2, 3, 4];
-///     let arr = cjson_create_int_array(&numbers[0], 4);
-///     let item = cjson_create_number(5.0);
-///     let success = cjson_add_item_reference_to_array(arr, item).unwrap();
-///     assert_eq!(success, true);
-///     assert_eq!(cjson_get_array_size(arr).unwrap(), 5);
+///     let src = cjson_parse_json(r#"{"user":{"name":"Nemuel","langs":["rust","c"]}}"#.to_string()).unwrap();
+///     let dst = cjson_create_object();
+///
+///     assert_eq!(cjson_copy_member(src, "user", dst, "owner").unwrap(), true);
+///     assert_eq!(dst.print_unformatted().unwrap(), r#"{"owner":{"name":"Nemuel","langs":["rust","c"]}}"#);
+///
+///     // the copy is independent of the source
+///     cjson_delete_item_from_object(src, "user").unwrap();
+///     assert_eq!(dst.print_unformatted().unwrap(), r#"{"owner":{"name":"Nemuel","langs":["rust","c"]}}"#);
+///
+///     assert_eq!(cjson_copy_member(src, "user", dst, "owner").unwrap(), false);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_item_reference_to_array(
-    array: *mut Json,
-    item: *mut Json,
+pub fn cjson_copy_member(
+    src_object: *mut Json,
+    src_key: &str,
+    dst_object: *mut Json,
+    dst_key: &str,
 ) -> Result<bool, JsonError> {
-    if !array.is_type_array() {
-        Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-array Json item".to_string(),
-        ))
-    } else {
-        let result =
-            unsafe { cJSON_AddItemReferenceToArray(array as *mut cJSON, item as *mut cJSON) };
-        if result == 1 {
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+    if !src_object.is_type_object() || !dst_object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot copy a member between non-object Json items".to_string(),
+        ));
+    }
+
+    let member = match object_member(src_object, src_key) {
+        Some(member) => member,
+        None => return Ok(false),
+    };
+    let c_key = match CString::new(dst_key) {
+        Ok(c_key) => c_key,
+        Err(err) => return Err(JsonError::CStringError(err)),
+    };
+
+    let copy = unsafe { cJSON_Duplicate(member as *const cJSON, 1) };
+    if copy.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+    if unsafe { cJSON_AddItemToObject(dst_object as *mut cJSON, c_key.as_ptr(), copy) } != 1 {
+        unsafe { cJSON_Delete(copy) };
+        return Err(JsonError::NullPointer);
     }
+    Ok(true)
 }
 
-/// Insert an item at a specific index in a Json item of type `Array`.
+/// Find the first member of a Json item of type `Object` for which a predicate holds.
 ///
 /// Args:
-/// - `array: *mut Json` - The Json item of type `Array` where the item will be added.
-/// - `which: i32` - Index specifying where to insert the new item in the array.
-/// - `newitem: *mut Json` - The item to add to the Json item of type `Array`.
+/// - `object: *mut Json` - The Json item of type `Object` whose members are to be searched.
+/// - `pred: F` - Predicate called with the key (invalid UTF-8 replaced by `U+FFFD`) and the value
+/// of each member, in order.
 ///
 /// Returns:
-/// - `Ok(bool)` - indicating success or failure of the operation.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Ok(Some((String, *mut Json)))` - the key and value of the first matching member (the value
+/// still belongs to `object`).
+/// - `Ok(None)` - if no member matches.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `object` value provided is not of type
+/// `Object`.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let numbers = [1, 3, 4, 5];
-///     let arr = cjson_create_int_array(&numbers[0], 4);
-///     let item = cjson_create_number(2.0);
-///     let success = cjson_insert_item_in_array(arr, 1, item).unwrap();
-///     assert_eq!(success, true);
-///     let new_item = cjson_get_array_item(arr, 1).unwrap();
-///     assert_eq!(cjson_get_number_value(new_item).unwrap(), 2.0);
+///     let object = cjson_parse_json(r#"{"name":"Nemuel","age":20,"score":42}"#.to_string()).unwrap();
+///
+///     let found = cjson_object_find(object, |_, value| value.as_f64().is_some_and(|n| n > 10.0)).unwrap();
+///     let (key, value) = found.unwrap();
+///     assert_eq!(key, "age");
+///     assert_eq!(cjson_get_number_value(value).unwrap(), 20.0);
+///
+///     let missing = cjson_object_find(object, |key, _| key.starts_with("email")).unwrap();
+///     assert!(missing.is_none());
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_insert_item_in_array(
-    array: *mut Json,
-    which: i32,
-    newitem: *mut Json,
-) -> Result<bool, JsonError> {
-    if !array.is_type_array() {
-        Err(JsonError::InvalidTypeError(
-            "cannot insert item in a non-array Json item".to_string(),
-        ))
-    } else {
-        let result =
-            unsafe { cJSON_InsertItemInArray(array as *mut cJSON, which, newitem as *mut cJSON) };
-        if result == 1 {
-            Ok(true)
-        } else {
-            Ok(false)
+pub fn cjson_object_find<F: Fn(&str, &Json) -> bool>(
+    object: *mut Json,
+    pred: F,
+) -> Result<Option<(String, *mut Json)>, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot search members of a non-object Json item".to_string(),
+        ));
+    }
+
+    for member in child_items(object) {
+        let key = match unsafe { (*member).string.as_ref() } {
+            Some(key) => unsafe { CStr::from_ptr(key) }.to_string_lossy(),
+            None => continue,
+        };
+        if pred(&key, unsafe { &*member }) {
+            return Ok(Some((key.into_owned(), member)));
         }
     }
+    Ok(None)
 }
 
-/// Replace item at a specific index in Json item of type `Array`.
+/// Collect the members of a Json item of type `Object` as `(key, value)` pairs.
+///
+/// The pairs are in insertion order, which is the order `print()` emits the members in. Duplicate
+/// keys are all kept.
 ///
 /// Args:
-/// - `array: *mut Json` - The Json item of type `Array` in which the replacement will happen.
-/// - `which: i32` - Index specifying the item to be replaced.
-/// - `newitem: *mut Json` - The item replacing the one at the specified index.
+/// - `object: *mut Json` - The Json item of type `Object` whose members are to be collected.
 ///
 /// Returns:
-/// - `Ok(bool)` - indicating success or faanilure of the operation.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Ok(Vec<(String, *mut Json)>)` - the key (invalid UTF-8 replaced by `U+FFFD`) and value of
+/// each member (the values still belong to `object`).
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `object` value provided is not of type
+/// `Object`.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let names = ["Alice", "Bob", "Chloe", "Dan"];
-///     let array = cjson_create_string_array(&names, 4).unwrap();
-///
-///     let original_item = cjson_get_array_item(array, 3).unwrap();
-///     assert_eq!(
-///         cjson_get_string_value(original_item).unwrap(),
-///         "Dan"
-///     );
-///
-///     let newitem = cjson_create_string("Diana".to_string()).unwrap();
-///     let success = cjson_replace_item_in_array(array, 3, newitem).unwrap();
-///     assert_eq!(success, true);
+///     let object = cjson_create_object();
+///     for key in ["zeta", "alpha", "mu", "beta", "omega"] {
+///         cjson_add_string_to_object(object, key, key).unwrap();
+///     }
 ///
-///     let new_item = cjson_get_array_item(array, 3).unwrap();
-///     assert_eq!(
-///         cjson_get_string_value(new_item).unwrap(),
-///         "Diana"
-///     );
+///     let entries = cjson_object_entries(object).unwrap();
+///     let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+///     assert_eq!(keys, ["zeta", "alpha", "mu", "beta", "omega"]);
 ///
+///     let serialized: String = entries
+///         .iter()
+///         .map(|(key, value)| format!("\"{}\":{}", key, value.print_unformatted().unwrap()))
+///         .collect::<Vec<_>>()
+///         .join(",");
+///     assert_eq!(format!("{{{}}}", serialized), object.print_unformatted().unwrap());
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_replace_item_in_array(
-    array: *mut Json,
-    which: i32,
-    newitem: *mut Json,
-) -> Result<bool, JsonError> {
-    if !array.is_type_array() {
-        Err(JsonError::InvalidTypeError(
-            "cannot replace item in a non-array Json item".to_string(),
-        ))
-    } else {
-        let result =
-            unsafe { cJSON_ReplaceItemInArray(array as *mut cJSON, which, newitem as *mut cJSON) };
-        if result == 1 {
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+pub fn cjson_object_entries(object: *mut Json) -> Result<Vec<(String, *mut Json)>, JsonError> {
+    if !object.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot collect members of a non-object Json item".to_string(),
+        ));
     }
+
+    Ok(child_items(object)
+        .into_iter()
+        .filter_map(|member| {
+            let key = unsafe { (*member).string.as_ref() }?;
+            let key = unsafe { CStr::from_ptr(key) }.to_string_lossy();
+            Some((key.into_owned(), member))
+        })
+        .collect())
 }
 
-/// Detach item at a specific index from Json item of type `Array`.
+/// Walk down a path of object keys, creating the Json items of type `Object` that are missing.
 ///
 /// Args:
-/// - `array: *mut Json` - The Json item of type `Array` from which an item is to be detached.
-/// - `which: i32` - The index of the item to be detached from the array.
+/// - `root: *mut Json` - The Json item of type `Object` to start from.
+/// - `segments: &[&str]` - Keys (case-sensitive) to follow, outermost first.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the detached item if the operation is successful.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Ok(*mut Json)` - the object at the end of the path (`root` itself if `segments` is empty).
+/// - `Err(JsonError::InvalidTypeError(String))` - if `root` or an existing value along the path is
+/// not of type `Object` (the objects created before it are kept).
+/// - `Err(JsonError::CStringError(NulError))` - if a segment contains a null byte.
+/// - `Err(JsonError::NullPointer)` - if an object could not be allocated.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let names = ["Alice", "Bob", "Chloe"];
-///     let array = cjson_create_string_array(&names, 3).unwrap();
-///     assert_eq!(cjson_get_array_size(array).unwrap(), 3);
+///     let root = cjson_parse_json(r#"{"a":{"x":1}}"#.to_string()).unwrap();
 ///
-///     let detached_item = cjson_detach_item_from_array(array, 2).unwrap();
+///     let c = cjson_ensure_path(root, &["a", "b", "c"]).unwrap();
+///     cjson_add_string_to_object(c, "leaf", "value").unwrap();
+///     assert_eq!(root.print_unformatted().unwrap(), r#"{"a":{"x":1,"b":{"c":{"leaf":"value"}}}}"#);
 ///
-///     assert_eq!(cjson_get_string_value(detached_item).unwrap(), "Chloe");
-///     assert_eq!(cjson_get_array_size(array).unwrap(), 2);
+///     // existing objects are reused
+///     assert_eq!(cjson_ensure_path(root, &["a", "b", "c"]).unwrap(), c);
+///
+///     let result = cjson_ensure_path(root, &["a", "x", "y"]);
+///     assert!(matches!(result, Err(JsonError::InvalidTypeError(_))));
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_detach_item_from_array(array: *mut Json, which: i32) -> Result<*mut Json, JsonError> {
-    if !array.is_type_array() {
-        Err(JsonError::InvalidTypeError(
-            "cannot detach item from a non-array Json item".to_string(),
-        ))
-    } else {
-        Ok(unsafe { cJSON_DetachItemFromArray(array as *mut cJSON, which) as *mut Json })
+pub fn cjson_ensure_path(root: *mut Json, segments: &[&str]) -> Result<*mut Json, JsonError> {
+    if !root.is_type_object() {
+        return Err(JsonError::InvalidTypeError(
+            "cannot walk a path from a non-object Json item".to_string(),
+        ));
+    }
+
+    let mut current = root;
+    for &segment in segments {
+        current = match object_member(current, segment) {
+            Some(member) if member.is_type_object() => member,
+            Some(_) => {
+                return Err(JsonError::InvalidTypeError(format!(
+                    "the value at \"{}\" is not an object",
+                    segment
+                )))
+            }
+            None => {
+                let c_key = match CString::new(segment) {
+                    Ok(c_key) => c_key,
+                    Err(err) => return Err(JsonError::CStringError(err)),
+                };
+                let object =
+                    unsafe { cJSON_AddObjectToObject(current as *mut cJSON, c_key.as_ptr()) };
+                if object.is_null() {
+                    return Err(JsonError::NullPointer);
+                }
+                object as *mut Json
+            }
+        };
     }
+    Ok(current)
 }
 
-/// Delete item at a specific index from Json item of type `Array`.
+// set the value of the first member of an object with the exact key `key` to `item`, adding the
+// member if there is none (`item` is deleted on failure)
+fn set_member(object: *mut Json, key: &str, item: *mut cJSON) -> Result<(), JsonError> {
+    if !object.is_type_object() {
+        unsafe { cJSON_Delete(item) };
+        return Err(JsonError::InvalidTypeError(
+            "cannot set a member of a non-object Json item".to_string(),
+        ));
+    }
+    if item.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    let result = replace_or_add_member(object, key, item);
+    if result.is_err() {
+        unsafe { cJSON_Delete(item) };
+    }
+    result
+}
+
+// put `item` under the exact key `key`, in place of the first member with that key or as a new
+// last member (`item` is left to the caller on failure)
+fn replace_or_add_member(object: *mut Json, key: &str, item: *mut cJSON) -> Result<(), JsonError> {
+    let c_key = match CString::new(key) {
+        Ok(c_key) => c_key,
+        Err(err) => return Err(JsonError::CStringError(err)),
+    };
+
+    let done = unsafe {
+        match object_member(object, key) {
+            Some(_) => {
+                cJSON_ReplaceItemInObjectCaseSensitive(object as *mut cJSON, c_key.as_ptr(), item)
+            }
+            None => cJSON_AddItemToObject(object as *mut cJSON, c_key.as_ptr(), item),
+        }
+    };
+    if done != 1 {
+        return Err(JsonError::NullPointer);
+    }
+    Ok(())
+}
+
+/// Set a member of type `Number` in a Json item of type `Object`, replacing the value of an existing
+/// member with the same key (case-sensitive) or adding a new member.
 ///
 /// Args:
-/// - `array: *mut Json` - The Json item of type `Array` from which an item is to be deleted.
-/// - `which: i32` - The index of the item to be deleted from the array.
+/// - `object: *mut Json` - The Json item of type `Object` in which the member is to be set.
+/// - `key: &str` - The key of the member.
+/// - `num: f64` - The number value to set.
 ///
 /// Returns:
-/// - `Ok()` - if the operation is successful.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the `array` value provided is not of type `Array`.
+/// - `Ok(())` - if the member was set.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `object` value provided is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if `key` contains a null byte.
+/// - `Err(JsonError::NullPointer)` - if the value could not be allocated.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let names = ["Alice", "Bob", "Chloe"];
-///     let array = cjson_create_string_array(&names, 3).unwrap();
-///     assert_eq!(cjson_get_array_size(array).unwrap(), 3);
+///     let object = cjson_parse_json(r#"{"name":"Nemuel","age":20}"#.to_string()).unwrap();
 ///
-///     cjson_delete_item_from_array(array, 2).unwrap();
-///
-///     assert_eq!(cjson_get_array_size(array).unwrap(), 2);
+///     cjson_object_set_number(object, "age", 21.0).unwrap();
+///     cjson_object_set_number(object, "height", 1.8).unwrap();
+///     assert_eq!(object.print_unformatted().unwrap(), r#"{"name":"Nemuel","age":21,"height":1.8}"#);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_delete_item_from_array(array: *mut Json, which: i32) -> Result<(), JsonError> {
-    if !array.is_type_array() {
-        Err(JsonError::InvalidTypeError(
-            "cannot delete item from a non-array Json item".to_string(),
-        ))
-    } else {
-        unsafe { cJSON_DeleteItemFromArray(array as *mut cJSON, which) };
-        Ok(())
-    }
+pub fn cjson_object_set_number(object: *mut Json, key: &str, num: f64) -> Result<(), JsonError> {
+    set_member(object, key, unsafe { cJSON_CreateNumber(num) })
 }
 
-/// Get the string value of a Json item of type `String`.
+/// Set a member of type `String` in a Json item of type `Object`, replacing the value of an existing
+/// member with the same key (case-sensitive) or adding a new member.
 ///
 /// Args:
-/// - `item: *mut Json` - Mutable pointer to the Json item of type `String` whose string value we
-/// want to get.
+/// - `object: *mut Json` - The Json item of type `Object` in which the member is to be set.
+/// - `key: &str` - The key of the member.
+/// - `value: &str` - The string value to set.
 ///
 /// Returns:
-/// - `Ok(String)` - if the string value is successfully gotten.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `String`.
+/// - `Ok(())` - if the member was set.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `object` value provided is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if `key` or `value` contains a null byte.
+/// - `Err(JsonError::NullPointer)` - if the value could not be allocated.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let json = cjson_create_string("Nemuel".to_string()).unwrap();
-///     assert_eq!(cjson_get_string_value(json).unwrap(), "Nemuel".to_string());
+///     let object = cjson_parse_json(r#"{"name":"Nemuel"}"#.to_string()).unwrap();
+///
+///     cjson_object_set_string(object, "name", "Wainaina").unwrap();
+///     cjson_object_set_string(object, "city", "Nairobi").unwrap();
+///     assert_eq!(object.print_unformatted().unwrap(), r#"{"name":"Wainaina","city":"Nairobi"}"#);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_get_string_value(item: *mut Json) -> Result<String, JsonError> {
-    if !item.is_type_string() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot get string value from a non-string Json item".to_string(),
-        ));
+pub fn cjson_object_set_string(object: *mut Json, key: &str, value: &str) -> Result<(), JsonError> {
+    match CString::new(value) {
+        Ok(c_str) => set_member(object, key, unsafe { cJSON_CreateString(c_str.as_ptr()) }),
+        Err(err) => Err(JsonError::CStringError(err)),
     }
-
-    let c_str = unsafe { cJSON_GetStringValue(item as *mut cJSON) };
-    Ok(unsafe {
-        CStr::from_ptr(c_str)
-            .to_str()
-            .unwrap_or_default()
-            .to_string()
-    })
 }
 
-/// Get the number value of a Json item of type `Number`.
+/// Set a member of type `True` or `False` in a Json item of type `Object`, replacing the value of an
+/// existing member with the same key (case-sensitive) or adding a new member.
 ///
 /// Args:
-/// - `item: *mut Json` - Mutable pointer to the Json item of type `Number` whose number value we
-/// want to get.
+/// - `object: *mut Json` - The Json item of type `Object` in which the member is to be set.
+/// - `key: &str` - The key of the member.
+/// - `boolean: bool` - The boolean value to set.
 ///
 /// Returns:
-/// - `Ok(i32)` - if the number value is successfully gotten.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Number`.
+/// - `Ok(())` - if the member was set.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `object` value provided is not of type
+/// `Object`.
+/// - `Err(JsonError::CStringError(NulError))` - if `key` contains a null byte.
+/// - `Err(JsonError::NullPointer)` - if the value could not be allocated.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let json = cjson_create_number(254.0);
-///     assert_eq!(cjson_get_number_value(json).unwrap(), 254.0);
+///     let object = cjson_parse_json(r#"{"active":false}"#.to_string()).unwrap();
+///
+///     cjson_object_set_bool(object, "active", true).unwrap();
+///     cjson_object_set_bool(object, "admin", false).unwrap();
+///     assert_eq!(object.print_unformatted().unwrap(), r#"{"active":true,"admin":false}"#);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_get_number_value(item: *mut Json) -> Result<f64, JsonError> {
-    if !item.is_type_number() {
-        Err(JsonError::InvalidTypeError(
-            "cannot get number value from a non-number Json item".to_string(),
-        ))
-    } else {
-        Ok(unsafe { cJSON_GetNumberValue(item as *const cJSON) })
-    }
+pub fn cjson_object_set_bool(object: *mut Json, key: &str, boolean: bool) -> Result<(), JsonError> {
+    set_member(object, key, unsafe {
+        cJSON_CreateBool(if boolean { 1 } else { 0 })
+    })
 }
 
-/// Add Json item of type `Null` to Json item of type `Object`.
+/// Get the value of a member of type `Number` of a Json item of type `Object`, falling back to a
+/// default.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `Null` to.
-/// - `name: &str` - Key to set for the item being added.
+/// - `object: *mut Json` - The Json item of type `Object` to read from.
+/// - `key: &str` - The key (case-sensitive) of the member.
+/// - `default: f64` - The value to return when there is no such number.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `Null` that has been added.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+/// - `f64` - the number value of the member, or `default` if `object` is not of type `Object`, has no
+/// member with the key `key` or that member is not of type `Number`.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object = cjson_create_object();
-///     cjson_add_null_to_object(object, "test").unwrap();
-///     let test_null_item = cjson_get_object_item(object, "test").unwrap();
-///     assert_eq!(test_null_item.is_type_null(), true);
+///     let config = cjson_parse_json(r#"{"port":8080,"host":"localhost"}"#.to_string()).unwrap();
+///     assert_eq!(cjson_object_get_number_or(config, "port", 80.0), 8080.0);
+///     assert_eq!(cjson_object_get_number_or(config, "timeout", 30.0), 30.0);
+///     assert_eq!(cjson_object_get_number_or(config, "host", 0.0), 0.0);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_null_to_object(object: *mut Json, name: &str) -> Result<*mut Json, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-object Json item".to_string(),
-        ));
-    }
-
-    match CString::new(name) {
-        Ok(c_str) => {
-            let result =
-                unsafe { cJSON_AddNullToObject(object as *mut cJSON, c_str.as_ptr()) as *mut Json };
-            Ok(result)
-        }
-        Err(err) => Err(JsonError::CStringError(err)),
-    }
+pub fn cjson_object_get_number_or(object: *mut Json, key: &str, default: f64) -> f64 {
+    object_scalar(object, key)
+        .and_then(|member| member.as_f64())
+        .unwrap_or(default)
 }
 
-/// Add Json item of type `True` to Json item of type `Object`.
+/// Get the value of a member of type `String` of a Json item of type `Object`, falling back to a
+/// default.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `True` to.
-/// - `name: &str` - Key to set for the item being added.
+/// - `object: *mut Json` - The Json item of type `Object` to read from.
+/// - `key: &str` - The key (case-sensitive) of the member.
+/// - `default: &str` - The value to return when there is no such string.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `True` that has been added.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+/// - `String` - the string value of the member, or `default` if `object` is not of type `Object`,
+/// has no member with the key `key` or that member is not of type `String` (or not valid UTF-8).
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object = cjson_create_object();
-///     cjson_add_true_to_object(object, "test").unwrap();
-///     let test_true_item = cjson_get_object_item(object, "test").unwrap();
-///     assert_eq!(test_true_item.is_type_true(), true);
+///     let config = cjson_parse_json(r#"{"port":8080,"host":"example.com"}"#.to_string()).unwrap();
+///     assert_eq!(cjson_object_get_string_or(config, "host", "localhost"), "example.com");
+///     assert_eq!(cjson_object_get_string_or(config, "user", "nobody"), "nobody");
+///     assert_eq!(cjson_object_get_string_or(config, "port", "80"), "80");
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_true_to_object(object: *mut Json, name: &str) -> Result<*mut Json, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-object Json item".to_string(),
-        ));
-    }
-
-    match CString::new(name) {
-        Ok(c_str) => {
-            let result =
-                unsafe { cJSON_AddTrueToObject(object as *mut cJSON, c_str.as_ptr()) as *mut Json };
-            Ok(result)
-        }
-        Err(err) => Err(JsonError::CStringError(err)),
-    }
+pub fn cjson_object_get_string_or(object: *mut Json, key: &str, default: &str) -> String {
+    object_scalar(object, key)
+        .and_then(|member| member.as_str())
+        .unwrap_or(default)
+        .to_string()
 }
 
-/// Add Json item of type `False` to Json item of type `Object`.
+/// Get the value of a member of type `True` or `False` of a Json item of type `Object`, falling back
+/// to a default.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `False` to.
-/// - `name: &str` - Key to set for the item being added.
+/// - `object: *mut Json` - The Json item of type `Object` to read from.
+/// - `key: &str` - The key (case-sensitive) of the member.
+/// - `default: bool` - The value to return when there is no such boolean.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `False` that has been added.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+/// - `bool` - the boolean value of the member, or `default` if `object` is not of type `Object`, has
+/// no member with the key `key` or that member is not of type `True` or `False`.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object = cjson_create_object();
-///     cjson_add_false_to_object(object, "test").unwrap();
-///     let test_false_item = cjson_get_object_item(object, "test").unwrap();
-///     assert_eq!(test_false_item.is_type_false(), true);
+///     let config = cjson_parse_json(r#"{"debug":true,"verbose":"yes"}"#.to_string()).unwrap();
+///     assert_eq!(cjson_object_get_bool_or(config, "debug", false), true);
+///     assert_eq!(cjson_object_get_bool_or(config, "tls", true), true);
+///     assert_eq!(cjson_object_get_bool_or(config, "verbose", false), false);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_false_to_object(object: *mut Json, name: &str) -> Result<*mut Json, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-object Json item".to_string(),
-        ));
-    }
+pub fn cjson_object_get_bool_or(object: *mut Json, key: &str, default: bool) -> bool {
+    object_scalar(object, key)
+        .and_then(|member| member.as_bool())
+        .unwrap_or(default)
+}
 
-    match CString::new(name) {
-        Ok(c_str) => {
-            let result = unsafe {
-                cJSON_AddFalseToObject(object as *mut cJSON, c_str.as_ptr()) as *mut Json
-            };
-            Ok(result)
-        }
-        Err(err) => Err(JsonError::CStringError(err)),
+// borrow the member with the exact key `key` if `object` is a Json item of type Object
+fn object_scalar<'a>(object: *mut Json, key: &str) -> Option<&'a Json> {
+    if !object.is_type_object() {
+        return None;
     }
+    object_member(object, key).and_then(|member| unsafe { member.as_ref() })
 }
 
-/// Add Json item of type `Bool` to Json item of type `Object`.
+/// Collect the members of a Json item of type `Object` whose values are strings into a HashMap.
+///
+/// Members with any other type of value (including nested objects) are skipped rather than
+/// reported as errors. If a key appears more than once, the first string value wins, matching
+/// `cjson_get_object_item_case_sensitive`. Keys and values that are not valid UTF-8 have the
+/// invalid sequences replaced by `U+FFFD`.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `Bool` to.
-/// - `name: &str` - Key to set for the item being added.
-/// - `boolean: bool` - Boolean value for the Json item being added (true or false).
+/// - `object: *mut Json` - The Json item of type `Object` to collect from.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `Bool` that has been added.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+/// - `Ok(HashMap<String, String>)` - the string members, by key.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the `object` value provided is not of type
+/// `Object`.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object = cjson_create_object();
-///     cjson_add_bool_to_object(object, "test", true).unwrap();
-///     let test_bool_item = cjson_get_object_item(object, "test").unwrap();
-///     assert_eq!(test_bool_item.is_type_bool(), true);
-///     assert_eq!(test_bool_item.is_type_true(), true);
+///     let config = cjson_parse_json(r#"{"host":"localhost","port":8080,"user":"nemuel","tls":{"cert":"x"}}"#.to_string()).unwrap();
+///     let map = cjson_object_to_string_map(config).unwrap();
+///
+///     assert_eq!(map.len(), 2);
+///     assert_eq!(map["host"], "localhost");
+///     assert_eq!(map["user"], "nemuel");
+///     assert!(!map.contains_key("port"));
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_bool_to_object(
+pub fn cjson_object_to_string_map(
     object: *mut Json,
-    name: &str,
-    boolean: bool,
-) -> Result<*mut Json, JsonError> {
+) -> Result<std::collections::HashMap<String, String>, JsonError> {
     if !object.is_type_object() {
         return Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-object Json item".to_string(),
+            "cannot collect members of a non-object Json item".to_string(),
         ));
     }
 
-    match CString::new(name) {
-        Ok(c_str) => {
-            let result = unsafe {
-                cJSON_AddBoolToObject(
-                    object as *mut cJSON,
-                    c_str.as_ptr(),
-                    if boolean { 1 } else { 0 },
-                ) as *mut Json
-            };
-            Ok(result)
+    let mut map = std::collections::HashMap::new();
+    for member in child_items(object) {
+        let (key, value) = unsafe { ((*member).string, (*member).valuestring) };
+        if !member.is_type_string() || key.is_null() || value.is_null() {
+            continue;
         }
-        Err(err) => Err(JsonError::CStringError(err)),
+        let key = unsafe { CStr::from_ptr(key) }
+            .to_string_lossy()
+            .into_owned();
+        map.entry(key).or_insert_with(|| {
+            unsafe { CStr::from_ptr(value) }
+                .to_string_lossy()
+                .into_owned()
+        });
     }
+    Ok(map)
 }
 
-/// Add Json item of type `Number` to Json item of type `Object`.
+/// Render a flat Json item of type `Object` as a URL query string, eg. `a=1&b=two`.
+///
+/// Members are written in order. Keys and values are percent-encoded (everything but ASCII
+/// letters, digits and `-`, `_`, `.`, `~`, so a space becomes `%20`). Numbers are written the way
+/// `print_unformatted` writes them, booleans as `true`/`false`, `Raw` values as their text, and
+/// `Null` as an empty value (`key=`).
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `Number` to.
-/// - `name: &str` - Key to set for the item being added.
-/// - `number: f64` - Number value for the Json item being added.
+/// - `object: *mut Json` - The Json item of type `Object` to render.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `Number` that has been added.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+/// - `Ok(String)` - the query string (empty for an empty object).
+/// - `Err(JsonError::InvalidTypeError(String))` - if `object` is not of type `Object` or one of its
+/// members is an array or an object.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object = cjson_create_object();
-///     cjson_add_number_to_object(object, "kenya", 254.0).unwrap();
-///     let test_number_item = cjson_get_object_item(object, "kenya").unwrap();
-///     assert_eq!(test_number_item.is_type_number(), true);
+///     let object = cjson_parse_json(r#"{"q":"rust json","page":2,"exact":true,"tag":"a&b=c"}"#.to_string()).unwrap();
+///     assert_eq!(cjson_to_query_string(object).unwrap(), "q=rust%20json&page=2&exact=true&tag=a%26b%3Dc");
+///
+///     let nested = cjson_parse_json(r#"{"user":{"name":"Nemuel"}}"#.to_string()).unwrap();
+///     assert!(matches!(cjson_to_query_string(nested), Err(JsonError::InvalidTypeError(_))));
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_number_to_object(
-    object: *mut Json,
-    name: &str,
-    number: f64,
-) -> Result<*mut Json, JsonError> {
+pub fn cjson_to_query_string(object: *mut Json) -> Result<String, JsonError> {
     if !object.is_type_object() {
         return Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-object Json item".to_string(),
+            "cannot render a non-object Json item as a query string".to_string(),
         ));
     }
 
-    match CString::new(name) {
-        Ok(c_str) => {
-            let result = unsafe {
-                cJSON_AddNumberToObject(object as *mut cJSON, c_str.as_ptr(), number) as *mut Json
-            };
-            Ok(result)
+    let mut query = String::new();
+    for member in child_items(object) {
+        let json = unsafe { &*member };
+        let key = if json.string.is_null() {
+            &[][..]
+        } else {
+            unsafe { CStr::from_ptr(json.string) }.to_bytes()
+        };
+        let value = if json.is_type_array() || json.is_type_object() {
+            return Err(JsonError::InvalidTypeError(format!(
+                "the value of \"{}\" is not a scalar",
+                String::from_utf8_lossy(key)
+            )));
+        } else if json.is_type_number() {
+            format_number(json).into_bytes()
+        } else if json.is_type_bool() {
+            (if json.is_type_true() { "true" } else { "false" }).into()
+        } else if (json.is_type_string() || json.is_type_raw()) && !json.valuestring.is_null() {
+            unsafe { CStr::from_ptr(json.valuestring) }
+                .to_bytes()
+                .to_vec()
+        } else {
+            Vec::new()
+        };
+
+        if !query.is_empty() {
+            query.push('&');
         }
-        Err(err) => Err(JsonError::CStringError(err)),
+        percent_encode(key, &mut query);
+        query.push('=');
+        percent_encode(&value, &mut query);
     }
+    Ok(query)
 }
 
-/// Add Json item of type `String` to Json item of type `Object`.
+/// Parse a URL query string, eg. `a=1&b=two`, into a flat Json item of type `Object`.
+///
+/// Every value becomes a member of type `String` (`a=1` gives `"1"`, not a number). Keys and
+/// values are percent-decoded and `+` is decoded as a space; malformed escapes are kept as they
+/// are. A pair without `=` gets an empty value and empty pairs (`a=1&&b=2`) are skipped. When a key
+/// is repeated the last value wins, while the member keeps the position of the key's first
+/// appearance.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `String` to.
-/// - `name: &str` - Key to set for the item being added.
-/// - `string: &str` - String value for the Json item being added.
+/// - `input: &str` - The query string, without the leading `?`.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `String` that has been added.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+/// - `Ok(*mut Json)` - a new Json item of type `Object` (to be deleted by the caller).
+/// - `Err(JsonError::CStringError(NulError))` - if a key or value decodes to a string containing a
+/// null byte (`%00`).
+/// - `Err(JsonError::NullPointer)` - if an item could not be allocated.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object = cjson_create_object();
-///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
-///     let test_string_item = cjson_get_object_item(object, "name").unwrap();
-///     assert_eq!(test_string_item.is_type_string(), true);
-///     assert_eq!(
-///         cjson_get_string_value(test_string_item).unwrap(), "Nemuel"
-///     );
+///     let object = cjson_from_query_string("q=rust%20json&page=2&q=cjson+rs&flag").unwrap();
+///     assert_eq!(object.print_unformatted().unwrap(), r#"{"q":"cjson rs","page":"2","flag":""}"#);
+///
+///     let query = "name=Nemuel%20W&tag=a%26b%3Dc";
+///     let parsed = cjson_from_query_string(query).unwrap();
+///     assert_eq!(cjson_to_query_string(parsed).unwrap(), query);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_string_to_object(
-    object: *mut Json,
-    name: &str,
-    string: &str,
-) -> Result<*mut Json, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-object Json item".to_string(),
-        ));
+pub fn cjson_from_query_string(input: &str) -> Result<*mut Json, JsonError> {
+    let mut pairs: Vec<(CString, CString)> = Vec::new();
+    for pair in input.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let (key, value) = match (
+            CString::new(percent_decode(key)),
+            CString::new(percent_decode(value)),
+        ) {
+            (Ok(key), Ok(value)) => (key, value),
+            (Err(err), _) | (_, Err(err)) => return Err(JsonError::CStringError(err)),
+        };
+        match pairs.iter_mut().find(|(existing, _)| *existing == key) {
+            Some(existing) => existing.1 = value,
+            None => pairs.push((key, value)),
+        }
     }
 
-    match CString::new(name) {
-        Ok(name_c_str) => match CString::new(string) {
-            Ok(string_c_str) => {
-                let result = unsafe {
-                    cJSON_AddStringToObject(
-                        object as *mut cJSON,
-                        name_c_str.as_ptr(),
-                        string_c_str.as_ptr(),
-                    ) as *mut Json
-                };
-                Ok(result)
-            }
-            Err(err) => Err(JsonError::CStringError(err)),
-        },
-        Err(err) => Err(JsonError::CStringError(err)),
+    let object = unsafe { cJSON_CreateObject() };
+    if object.is_null() {
+        return Err(JsonError::NullPointer);
     }
+    for (key, value) in &pairs {
+        if unsafe { cJSON_AddStringToObject(object, key.as_ptr(), value.as_ptr()) }.is_null() {
+            unsafe { cJSON_Delete(object) };
+            return Err(JsonError::NullPointer);
+        }
+    }
+    Ok(object as *mut Json)
 }
 
-/// Add Json item of type `Raw` to Json item of type `Object`.
+/// Detach Json item from its parent via pointer (thus maintaining access to the detached item).
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `Raw` to.
-/// - `name: &str` - Key to set for the item being added.
-/// - `raw: &str` - Raw string (JSON or otherwise).
+/// - `parent: *mut Json` - Mutable pointer to the parent Json item from which an item is to be detached.
+/// - `item: *mut Json` - Mutable pointer to the Json item that is to be detached from its parent.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `Raw` that has been added.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+/// - `*mut Json` - a mutable pointer to the detached item.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object: *mut Json = cjson_create_object();
-///     cjson_add_raw_to_object(object, "name", "Nemuel").unwrap();
-///     let test_raw_item = cjson_get_object_item(object, "name").unwrap();
-///     assert_eq!(test_raw_item.is_type_raw(), true);
+///     let parent = cjson_create_object();
+///     let item = cjson_create_string("Nemuel".to_string()).unwrap();
+///
+///     cjson_add_item_to_object(parent, "name", item).unwrap();
+///     assert_eq!(cjson_has_object_item(parent, "name").unwrap(), true);
+///
+///     let detached_item = cjson_detach_item_via_pointer(parent, item);
+///     assert_eq!(detached_item.is_type_string(), true);
+///     assert_eq!(cjson_has_object_item(parent, "name").unwrap(), false);
+///
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_raw_to_object(
-    object: *mut Json,
-    name: &str,
-    raw: &str,
-) -> Result<*mut Json, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-object Json item".to_string(),
-        ));
-    }
-
-    match CString::new(name) {
-        Ok(name_c_str) => match CString::new(raw) {
-            Ok(raw_c_str) => {
-                let result = unsafe {
-                    cJSON_AddRawToObject(
-                        object as *mut cJSON,
-                        name_c_str.as_ptr(),
-                        raw_c_str.as_ptr(),
-                    ) as *mut Json
-                };
-                Ok(result)
-            }
-            Err(err) => Err(JsonError::CStringError(err)),
-        },
-        Err(err) => Err(JsonError::CStringError(err)),
-    }
+pub fn cjson_detach_item_via_pointer(parent: *mut Json, item: *mut Json) -> *mut Json {
+    unsafe { cJSON_DetachItemViaPointer(parent as *mut cJSON, item as *mut cJSON) as *mut Json }
 }
 
-/// Add Json item of type `Object` to Json item of type `Object`.
+/// Replace a Json item from its parent via pointer with a new item.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `Object` to.
-/// - `name: &str` - Key to set for the item being added.
+/// - `parent: *mut Json` - Mutable pointer to the parent Json item in which an item is to be replaced.
+/// - `item: *mut Json` - Mutable pointer to the Json item that is to be replaced with another one.
+/// - `replacement: *mut Json` - Mutable pointer to the Json item that is to replace the original one.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `Object` that has been added.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+/// - `bool` - a boolean value indicating success or failure of the operation.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object: *mut Json = cjson_create_object();
-///     cjson_add_object_to_object(object, "test").unwrap();
-///     let test_object_item = cjson_get_object_item(object, "test").unwrap();
-///     assert_eq!(test_object_item.is_type_object(), true);
+///     let parent = cjson_create_array();
+///     let item = cjson_create_string("Nemuel".to_string()).unwrap();
+///     cjson_add_item_to_array(parent, item).unwrap();
+///     assert_eq!(parent.print().unwrap(), r#"["Nemuel"]"#);
+///
+///     let replacement = cjson_create_string("Wainaina".to_string()).unwrap();
+///     cjson_replace_item_via_pointer(parent, item, replacement);
+///     assert_eq!(parent.print().unwrap(), r#"["Wainaina"]"#);
+///
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_object_to_object(object: *mut Json, name: &str) -> Result<*mut Json, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-object Json item".to_string(),
-        ));
-    }
-
-    match CString::new(name) {
-        Ok(c_str) => {
-            let result = unsafe {
-                cJSON_AddObjectToObject(object as *mut cJSON, c_str.as_ptr()) as *mut Json
-            };
-            Ok(result)
-        }
-        Err(err) => Err(JsonError::CStringError(err)),
+pub fn cjson_replace_item_via_pointer(
+    parent: *mut Json,
+    item: *mut Json,
+    replacement: *mut Json,
+) -> bool {
+    let result = unsafe {
+        cJSON_ReplaceItemViaPointer(
+            parent as *mut cJSON,
+            item as *mut cJSON,
+            replacement as *mut cJSON,
+        )
+    };
+    if result == 1 {
+        true
+    } else {
+        false
     }
 }
 
-/// Add Json item of type `Array` to Json item of type `Object`.
+/// Create a copy of a Json item.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` to add the Json item of type `Array` to.
-/// - `name: &str` - Key to set for the item being added.
+/// - `item: *mut Json` - Mutable pointer to the Json item to be duplicated.
+/// - `recurse: bool` - Boolean value specifying whether or not to duplicate nested structures as well.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the Json item of type `Array` that has been added.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item provided is not of type `Object`.
+/// - `*mut Json` - a mutable pointer to the newly created duplicate Json item.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object: *mut Json = cjson_create_object();
-///     cjson_add_array_to_object(object, "test").unwrap();
-///     let test_array_item = cjson_get_object_item(object, "test").unwrap();
-///     assert_eq!(test_array_item.is_type_array(), true);
+///     let original = cjson_create_string("Nemuel".to_string()).unwrap();
+///
+///     let copy = cjson_duplicate(original, false);
+///
+///     let result = cjson_compare(original, copy, true);
+///     assert_eq!(result, true);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_array_to_object(object: *mut Json, name: &str) -> Result<*mut Json, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-object Json item".to_string(),
-        ));
-    }
-
-    match CString::new(name) {
-        Ok(c_str) => {
-            let result = unsafe {
-                cJSON_AddArrayToObject(object as *mut cJSON, c_str.as_ptr()) as *mut Json
-            };
-            Ok(result)
-        }
-        Err(err) => Err(JsonError::CStringError(err)),
-    }
+pub fn cjson_duplicate(item: *mut Json, recurse: bool) -> *mut Json {
+    unsafe { cJSON_Duplicate(item as *const cJSON, if recurse { 1 } else { 0 }) as *mut Json }
 }
 
-/// Add Json item of any type to Json item of type `Object`.
+/// Create a copy of a Json item, reporting failure instead of returning a null pointer.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` to add the Json item to.
-/// - `string: &str` - Key to set for the item being added.
-/// - `item: *mut Json` - Json item to be added.
+/// - `item: *mut Json` - Mutable pointer to the Json item to be duplicated.
+/// - `recurse: bool` - Boolean value specifying whether or not to duplicate nested structures as well.
 ///
 /// Returns:
-/// - `Ok(bool)` - a boolean value indicating whether or not the operation was successful.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be added to is not of type `Object`.
+/// - `Ok(*mut Json)` - a mutable pointer to the newly created duplicate Json item.
+/// - `Err(JsonError::NullPointer)` - if `item` is null or cJSON fails to allocate the copy.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let test_item = cjson_create_null();
-///     let object = cjson_create_object();
-///     assert_eq!(
-///         cjson_add_item_to_object(object, "test", test_item).unwrap(),
-///         true
-///     );
+///     let original = cjson_parse_json(r#"{"name":"Nemuel","langs":["rust"]}"#.to_string()).unwrap();
+///
+///     let copy = cjson_try_duplicate(original, true).unwrap();
+///     assert_eq!(cjson_compare(original, copy, true), true);
+///
+///     assert!(matches!(cjson_try_duplicate(std::ptr::null_mut(), true), Err(JsonError::NullPointer)));
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_item_to_object(
-    object: *mut Json,
-    string: &str,
-    item: *mut Json,
-) -> Result<bool, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-object Json item".to_string(),
-        ));
-    }
-
-    match CString::new(string) {
-        Ok(c_str) => {
-            let success = unsafe {
-                cJSON_AddItemToObject(object as *mut cJSON, c_str.as_ptr(), item as *mut cJSON)
-            };
-            if success == 1 {
-                Ok(true)
-            } else {
-                Ok(false)
-            }
-        }
-        Err(err) => Err(JsonError::CStringError(err)),
+pub fn cjson_try_duplicate(item: *mut Json, recurse: bool) -> Result<*mut Json, JsonError> {
+    let copy = cjson_duplicate(item, recurse);
+    if copy.is_null() {
+        Err(JsonError::NullPointer)
+    } else {
+        Ok(copy)
     }
 }
 
-/// Add an item to Json item of type `Object` while maintaining a reference to the original item rather
-/// than copying it.
+/// Check whether 2 Json items are equivalent in structure and value.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` to add the Json item to.
-/// - `string: &str` - Key to set for the item being added.
-/// - `item: *mut Json` - Json item to be added.
+/// - `a: *mut Json` - Mutable pointer to the first Json item.
+/// - `b: *mut Json` - Mutable pointer to the second Json item.
+/// - `case_sensitive: bool` - Boolean value specifying whether or not to do case-sensitive comparison
+/// for string values.
 ///
 /// Returns:
-/// - `Ok(bool)` - a boolean value indicating whether or not the operation was successful.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be added to is not of type `Object`.
+/// - `bool` - a boolean value (true or false) indicating whether or not the 2 Json items are equivalent.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let test_item = cjson_create_null();
-///     let object = cjson_create_object();
-///     assert_eq!(
-///         cjson_add_item_reference_to_object(object, "test", test_item).unwrap(),
-///         true
-///     );
+///     let item1 = cjson_create_string("Nemuel".to_string()).unwrap();
+///     let item2 = cjson_create_string("Nemuel".to_string()).unwrap();
+///     let result = cjson_compare(item1, item2, true);
+///     assert_eq!(result, true);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_item_reference_to_object(
-    object: *mut Json,
-    string: &str,
-    item: *mut Json,
-) -> Result<bool, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-object Json item".to_string(),
-        ));
-    }
-
-    match CString::new(string) {
-        Ok(c_str) => {
-            let success = unsafe {
-                cJSON_AddItemReferenceToObject(
-                    object as *mut cJSON,
-                    c_str.as_ptr(),
-                    item as *mut cJSON,
-                )
-            };
-            if success == 1 {
-                Ok(true)
-            } else {
-                Ok(false)
-            }
-        }
-        Err(err) => Err(JsonError::CStringError(err)),
+pub fn cjson_compare(a: *mut Json, b: *mut Json, case_sensitive: bool) -> bool {
+    let result = unsafe {
+        cJSON_Compare(
+            a as *const cJSON,
+            b as *const cJSON,
+            if case_sensitive { 1 } else { 0 },
+        )
+    };
+    if result == 1 {
+        true
+    } else {
+        false
     }
 }
 
-/// Add Json item of any type to Json item of type `Object` with a name/key that is a constant
-/// or reference.
+/// Check whether 2 Json items are equivalent in structure and value, treating numbers as equal
+/// when they differ by at most `epsilon`.
+///
+/// The comparison recurses like `cjson_compare` (object members are matched by key regardless of
+/// their order, array items by index), while keys and string values are compared exactly
+/// (case-sensitive). An `epsilon` of `0.0` requires numbers to be exactly equal, unlike
+/// `cjson_compare`, which tolerates a relative difference of `f64::EPSILON`.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` to add the Json item to.
-/// - `name: &str` - Key to set for the item being added.
-/// - `item: *mut Json` - Json item to be added.
+/// - `a: *mut Json` - Mutable pointer to the first Json item.
+/// - `b: *mut Json` - Mutable pointer to the second Json item.
+/// - `epsilon: f64` - Largest absolute difference for which 2 numbers are considered equal.
 ///
 /// Returns:
-/// - `Ok(bool)` - a boolean value indicating whether or not the operation was successful.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be added to is not of type `Object`.
+/// - `bool` - a boolean value indicating whether or not the 2 Json items are equivalent.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let test_item = cjson_create_null();
-///     let object = cjson_create_object();
-///     assert_eq!(
-///         cjson_add_item_to_object_cs(object, "test", test_item).unwrap(),
-///         true
-///     );
+///     let a = cjson_create_array();
+///     cjson_add_item_to_array(a, cjson_create_number(0.1 + 0.2)).unwrap();
+///     let b = cjson_parse_json("[0.3]".to_string()).unwrap();
+///
+///     assert_eq!(cjson_compare_approx(a, b, 0.0), false);
+///     assert_eq!(cjson_compare_approx(a, b, 1e-9), true);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_add_item_to_object_cs(
-    object: *mut Json,
-    name: &str,
-    item: *mut Json,
-) -> Result<bool, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot add item to a non-object Json item".to_string(),
-        ));
-    }
-
-    match CString::new(name) {
-        Ok(c_str) => {
-            let success = unsafe {
-                cJSON_AddItemToObjectCS(object as *mut cJSON, c_str.as_ptr(), item as *mut cJSON)
-            };
-            if success == 1 {
-                Ok(true)
-            } else {
-                Ok(false)
-            }
-        }
-        Err(err) => Err(JsonError::CStringError(err)),
-    }
+pub fn cjson_compare_approx(a: *mut Json, b: *mut Json, epsilon: f64) -> bool {
+    compare_items_approx(a, b, epsilon)
 }
 
-/// Check whether or not the Json item of type `Object` contains an item with the specified key.
+/// Check whether 2 Json items are equivalent in structure and value, ignoring the order of array
+/// items.
+///
+/// Arrays (at any depth) are compared as multisets: they must hold the same items the same number
+/// of times, in any order. Objects and scalars are compared like `cjson_compare` does
+/// (case-sensitive).
 ///
 /// Args:
-/// - `object: *mut Json` - The Json item of type `Object` to inspect for an item with the specified key.
-/// - `string: &str` - String slice specifying the key of the item being looked for.
+/// - `a: *mut Json` - Mutable pointer to the first Json item.
+/// - `b: *mut Json` - Mutable pointer to the second Json item.
 ///
 /// Returns:
-/// - `Ok(bool)` - a boolean value indicating whether or not an item with the specified key exists within
-/// the object if the lookup is successful.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice (representing the key)
-/// contains a null byte.
+/// - `bool` - a boolean value indicating whether or not the 2 Json items are equivalent.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object = cjson_create_object();
-///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
+///     let a = cjson_parse_json("[1,2,3]".to_string()).unwrap();
+///     let b = cjson_parse_json("[3,2,1]".to_string()).unwrap();
+///     assert_eq!(cjson_compare(a, b, true), false);
+///     assert_eq!(cjson_compare_unordered(a, b), true);
+///
+///     let c = cjson_parse_json(r#"{"tags":["x",["y","z"]],"n":1}"#.to_string()).unwrap();
+///     let d = cjson_parse_json(r#"{"n":1,"tags":[["z","y"],"x"]}"#.to_string()).unwrap();
+///     assert_eq!(cjson_compare_unordered(c, d), true);
+///
+///     let e = cjson_parse_json("[1,1,2]".to_string()).unwrap();
+///     let f = cjson_parse_json("[1,2,2]".to_string()).unwrap();
+///     assert_eq!(cjson_compare_unordered(e, f), false);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_compare_unordered(a: *mut Json, b: *mut Json) -> bool {
+    compare_items_unordered(a, b)
+}
+
+/// Count every item in a Json tree: the root itself, plus all nested arrays, objects and scalars.
 ///
-///     assert_eq!(cjson_has_object_item(object, "name").unwrap(), true);
-///     assert_eq!(cjson_has_object_item(object, "age").unwrap(), false);
+/// The tree is walked iteratively, so arbitrarily deep trees cannot overflow the stack.
+///
+/// Args:
+/// - `root: *mut Json` - Mutable pointer to the root of the tree.
+///
+/// Returns:
+/// - `usize` - the number of items in the tree (0 if `root` is null).
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
 ///
+/// fn main() {
+///     let root = cjson_parse_json(r#"{"a":[1,2,{"b":null}],"c":"d"}"#.to_string()).unwrap();
+///     assert_eq!(cjson_count_nodes(root), 7);
+///     assert_eq!(cjson_count_nodes(std::ptr::null_mut()), 0);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_has_object_item(object: *mut Json, string: &str) -> Result<bool, JsonError> {
-    match CString::new(string) {
-        Ok(c_str) => {
-            let result = unsafe { cJSON_HasObjectItem(object as *const cJSON, c_str.as_ptr()) };
-            if result == 1 {
-                Ok(true)
-            } else {
-                Ok(false)
-            }
+pub fn cjson_count_nodes(root: *mut Json) -> usize {
+    let mut count = 0;
+    let mut pending = vec![root];
+    while let Some(item) = pending.pop() {
+        if item.is_null() {
+            continue;
         }
-        Err(err) => Err(JsonError::CStringError(err)),
+        count += 1;
+        pending.extend(child_items(item));
     }
+    count
 }
 
-/// Get item within the object with the specified key.
+/// Get the depth of a Json tree, where a lone scalar (or an empty array or object) has a depth of 1
+/// and every level of nesting adds 1.
+///
+/// The tree is walked iteratively, so arbitrarily deep trees cannot overflow the stack.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` from which we want to get an item.
-/// - `string: &str` - Key of the Json item that we want to get.
+/// - `root: *mut Json` - Mutable pointer to the root of the tree.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the Json item with the provided key if gotten successfully.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
+/// - `usize` - the depth of the tree (0 if `root` is null).
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object = cjson_create_object();
-///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
-///
-///     let item = cjson_get_object_item(object, "name").unwrap();
-///     assert_eq!(item.is_type_string(), true);
-///     assert_eq!(cjson_get_string_value(item).unwrap(), "Nemuel");
-///
+///     let root = cjson_parse_json(r#"{"a":[1,2,{"b":null}],"c":"d"}"#.to_string()).unwrap();
+///     assert_eq!(cjson_max_depth(root), 4);
+///     assert_eq!(cjson_max_depth(cjson_create_array()), 1);
+///     assert_eq!(cjson_max_depth(std::ptr::null_mut()), 0);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_get_object_item(object: *mut Json, string: &str) -> Result<*mut Json, JsonError> {
-    match CString::new(string) {
-        Ok(c_str) => {
-            let result =
-                unsafe { cJSON_GetObjectItem(object as *const cJSON, c_str.as_ptr()) as *mut Json };
-            Ok(result)
+pub fn cjson_max_depth(root: *mut Json) -> usize {
+    let mut max_depth = 0;
+    let mut pending = vec![(root, 1)];
+    while let Some((item, depth)) = pending.pop() {
+        if item.is_null() {
+            continue;
         }
-        Err(err) => Err(JsonError::CStringError(err)),
+        max_depth = max_depth.max(depth);
+        pending.extend(
+            child_items(item)
+                .into_iter()
+                .map(|child| (child, depth + 1)),
+        );
     }
+    max_depth
 }
 
-/// Get item within the object with the specified key, with a case-sensitive comparison of keys.
+/// Check whether a Json item is empty.
+///
+/// Per type:
+/// - `Array` and `Object` - empty when they have no items/members.
+/// - `String` and `Raw` - empty when their value is the empty string.
+/// - `Null` - always empty.
+/// - `Number`, `True` and `False` - never empty (`0` and `false` are values too).
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` from which we want to get an item.
-/// - `string: &str` - Key of the Json item that we want to get.
+/// - `item: *mut Json` - Mutable pointer to the Json item to check.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the Json item with the provided key if gotten successfully.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
+/// - `Ok(bool)` - whether the item is empty.
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the item is of type `Invalid`.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object = cjson_create_object();
-///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
-///
-///     let item = cjson_get_object_item_case_sensitive(object, "Name").unwrap();
-///     assert_eq!(item.is_null(), true);
-///     let item = cjson_get_object_item_case_sensitive(object, "name").unwrap();
-///     assert_eq!(item.is_null(), false);
-///     assert_eq!(item.is_type_string(), true);
-///
+///     assert_eq!(cjson_is_empty(cjson_create_array()).unwrap(), true);
+///     assert_eq!(cjson_is_empty(cjson_create_object()).unwrap(), true);
+///     assert_eq!(cjson_is_empty(cjson_create_string("".to_string()).unwrap()).unwrap(), true);
+///     assert_eq!(cjson_is_empty(cjson_create_null()).unwrap(), true);
+///     assert_eq!(cjson_is_empty(cjson_create_raw("".to_string()).unwrap()).unwrap(), true);
+///
+///     assert_eq!(cjson_is_empty(cjson_parse_json("[null]".to_string()).unwrap()).unwrap(), false);
+///     assert_eq!(cjson_is_empty(cjson_parse_json(r#"{"a":{}}"#.to_string()).unwrap()).unwrap(), false);
+///     assert_eq!(cjson_is_empty(cjson_create_string(" ".to_string()).unwrap()).unwrap(), false);
+///     assert_eq!(cjson_is_empty(cjson_create_number(0.0)).unwrap(), false);
+///     assert_eq!(cjson_is_empty(cjson_create_false()).unwrap(), false);
+///     assert_eq!(cjson_is_empty(cjson_create_raw("[]".to_string()).unwrap()).unwrap(), false);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_get_object_item_case_sensitive(
-    object: *mut Json,
-    string: &str,
-) -> Result<*mut Json, JsonError> {
-    match CString::new(string) {
-        Ok(c_str) => {
-            let result = unsafe {
-                cJSON_GetObjectItemCaseSensitive(object as *const cJSON, c_str.as_ptr())
-                    as *mut Json
-            };
-            Ok(result)
+pub fn cjson_is_empty(item: *mut Json) -> Result<bool, JsonError> {
+    if item.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    if item.is_type_array() || item.is_type_object() {
+        Ok(unsafe { cJSON_GetArraySize(item as *const cJSON) } == 0)
+    } else if item.is_type_string() {
+        let value = unsafe { cJSON_GetStringValue(item as *const cJSON) };
+        Ok(value.is_null() || unsafe { CStr::from_ptr(value) }.is_empty())
+    } else if item.is_type_raw() {
+        // cJSON has no getter for raw values, but prints them verbatim (and fails on a null one)
+        match print_bytes(item, false) {
+            Ok(raw) => Ok(raw.is_empty()),
+            Err(_) => Ok(true),
         }
-        Err(err) => Err(JsonError::CStringError(err)),
+    } else if item.is_type_null() {
+        Ok(true)
+    } else if item.is_type_number() || item.is_type_bool() {
+        Ok(false)
+    } else {
+        Err(JsonError::InvalidTypeError(
+            "cannot check whether an invalid Json item is empty".to_string(),
+        ))
     }
 }
 
-/// Replace item with specified key in Json item of type `Object`.
+/// Flatten a Json tree into a single-level object mapping paths to the leaf values.
+///
+/// Object members are joined with `separator` (eg. `a.b`) and array items are appended as
+/// bracketed indices (eg. `a.b[0]`). Leaves are scalars as well as empty arrays and empty objects,
+/// which are kept as `[]` and `{}` so that no information is lost. A scalar root is stored under
+/// the empty key. Keys that contain the separator or brackets make the paths ambiguous.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` within which the replacement is to happen.
-/// - `string: &str` - The key of the Json item to be replaced.
-/// - `newitem: *mut Json` - Item replacing the original one.
+/// - `root: *mut Json` - Mutable pointer to the root of the tree to be flattened.
+/// - `separator: &str` - The string placed between object keys.
 ///
 /// Returns:
-/// - `Ok(bool)` - a boolean value indicating whether or not the operation was successful.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item being operated on is not of type
-/// `Object`.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
+/// - `Ok(*mut Json)` - a new Json item of type `Object` (to be deleted by the caller).
+/// - `Err(JsonError::NullPointer)` - if `root` is null or an item could not be allocated.
+/// - `Err(JsonError::CStringError(NulError))` - if the separator contains a null byte.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object = cjson_create_object();
-///     let original_item = cjson_create_string("Nemuel".to_string()).unwrap();
-///     cjson_add_item_to_object(object, "name", original_item).unwrap();
-///
-///     let new_item = cjson_create_string("Wainaina".to_string()).unwrap();
-///     let result = cjson_replace_item_in_object(object, "name", new_item).unwrap();
-///     assert_eq!(result, true);
+///     let root = cjson_parse_json(r#"{"a":{"b":[1,{"c":true}],"d":{}},"e":"f","g":[]}"#.to_string()).unwrap();
+///     let flat = cjson_flatten(root, ".").unwrap();
 ///     assert_eq!(
-///         cjson_get_string_value(cjson_get_object_item(object, "name").unwrap()).unwrap(),
-///         "Wainaina"
+///         flat.print_unformatted().unwrap(),
+///         r#"{"a.b[0]":1,"a.b[1].c":true,"a.d":{},"e":"f","g":[]}"#
 ///     );
-///
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_replace_item_in_object(
-    object: *mut Json,
-    string: &str,
-    newitem: *mut Json,
-) -> Result<bool, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot replace item in a non-object Json item".to_string(),
-        ));
+pub fn cjson_flatten(root: *mut Json, separator: &str) -> Result<*mut Json, JsonError> {
+    if root.is_null() {
+        return Err(JsonError::NullPointer);
     }
 
-    match CString::new(string) {
-        Ok(c_str) => {
-            let result = unsafe {
-                cJSON_ReplaceItemInObject(
-                    object as *mut cJSON,
-                    c_str.as_ptr(),
-                    newitem as *mut cJSON,
-                )
+    let flat = unsafe { cJSON_CreateObject() as *mut Json };
+    if flat.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    let mut pending = vec![(root, String::new())];
+    while let Some((item, path)) = pending.pop() {
+        let children = child_items(item);
+        if children.is_empty() {
+            let added = match CString::new(path) {
+                Ok(c_path) => {
+                    let leaf = unsafe { cJSON_Duplicate(item as *const cJSON, 0) };
+                    if !leaf.is_null()
+                        && unsafe {
+                            cJSON_AddItemToObject(flat as *mut cJSON, c_path.as_ptr(), leaf)
+                        } == 1
+                    {
+                        Ok(())
+                    } else {
+                        unsafe { cJSON_Delete(leaf) };
+                        Err(JsonError::NullPointer)
+                    }
+                }
+                Err(err) => Err(JsonError::CStringError(err)),
             };
-            if result == 1 {
-                Ok(true)
+            if let Err(err) = added {
+                unsafe { cJSON_Delete(flat as *mut cJSON) };
+                return Err(err);
+            }
+            continue;
+        }
+
+        // children are pushed in reverse so that they get popped (and added) in document order
+        let is_array = item.is_type_array();
+        for (index, &child) in children.iter().enumerate().rev() {
+            let child_path = if is_array {
+                format!("{}[{}]", path, index)
             } else {
-                Ok(false)
+                let key = match unsafe { (*child).string.as_ref() } {
+                    Some(key) => unsafe { CStr::from_ptr(key) }.to_string_lossy(),
+                    None => "".into(),
+                };
+                if path.is_empty() {
+                    key.into_owned()
+                } else {
+                    format!("{}{}{}", path, separator, key)
+                }
+            };
+            pending.push((child, child_path));
+        }
+    }
+    Ok(flat)
+}
+
+/// How `cjson_deep_merge_with` combines two arrays found under the same key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayMergeStrategy {
+    /// Append the overlay's items after the base's items.
+    Concat,
+    /// Keep only the overlay's array, like any other conflicting value.
+    Replace,
+}
+
+// merge `overlay` into `target` (both objects, or both arrays when concatenating), copying
+// everything taken from `overlay`
+fn merge_into(
+    target: *mut Json,
+    overlay: *mut Json,
+    arrays: ArrayMergeStrategy,
+) -> Result<(), JsonError> {
+    let mergeable = |a: *mut Json, b: *mut Json| {
+        (a.is_type_object() && b.is_type_object())
+            || (arrays == ArrayMergeStrategy::Concat && a.is_type_array() && b.is_type_array())
+    };
+
+    for member in child_items(overlay) {
+        let key = unsafe { (*member).string };
+        let existing = match unsafe { key.as_ref() } {
+            Some(key) if target.is_type_object() => {
+                object_member(target, &unsafe { CStr::from_ptr(key) }.to_string_lossy())
+            }
+            _ => None,
+        };
+
+        match existing {
+            Some(existing) if mergeable(existing, member) => merge_into(existing, member, arrays)?,
+            _ => {
+                let copy = unsafe { cJSON_Duplicate(member as *const cJSON, 1) };
+                if copy.is_null() {
+                    return Err(JsonError::NullPointer);
+                }
+                let added = unsafe {
+                    match existing {
+                        Some(_) => {
+                            cJSON_ReplaceItemInObjectCaseSensitive(target as *mut cJSON, key, copy)
+                        }
+                        None if target.is_type_object() => {
+                            cJSON_AddItemToObject(target as *mut cJSON, key, copy)
+                        }
+                        None => cJSON_AddItemToArray(target as *mut cJSON, copy),
+                    }
+                };
+                if added != 1 {
+                    unsafe { cJSON_Delete(copy) };
+                    return Err(JsonError::NullPointer);
+                }
             }
         }
-        Err(err) => Err(JsonError::CStringError(err)),
     }
+    Ok(())
 }
 
-/// Replace item with specified key in Json item of type `Object`, with a case-sensitive comparison of
-/// keys.
+/// Recursively merge 2 Json items into a new one, concatenating arrays found under the same key.
+///
+/// This is `cjson_deep_merge_with` using `ArrayMergeStrategy::Concat`.
 ///
 /// Args:
-/// - `object: *mut Json` - Json item of type `Object` within which the replacement is to happen.
-/// - `string: &str` - The key of the Json item to be replaced.
-/// - `newitem: *mut Json` - Item replacing the original one.
+/// - `base: *mut Json` - Mutable pointer to the base Json item.
+/// - `overlay: *mut Json` - Mutable pointer to the Json item merged on top of `base`.
 ///
 /// Returns:
-/// - `Ok(bool)` - a boolean value indicating whether or not the operation was successful.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item being operated on is not of type
-/// `Object`.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
+/// - `Ok(*mut Json)` - a mutable pointer to the merged item (to be deleted by the caller).
+/// - `Err(JsonError::NullPointer)` - if either pointer is null or an item could not be allocated.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object = cjson_create_object();
-///     let original_item = cjson_create_string("Nemuel".to_string()).unwrap();
-///     cjson_add_item_to_object(object, "name", original_item).unwrap();
+///     let base = cjson_parse_json(r#"{"name":"Nemuel","tags":["a"],"address":{"city":"Nairobi","zip":"00100"}}"#.to_string()).unwrap();
+///     let overlay = cjson_parse_json(r#"{"tags":["b"],"address":{"zip":"00200"},"age":20}"#.to_string()).unwrap();
 ///
-///     let new_item = cjson_create_string("Wainaina".to_string()).unwrap();
-///     let mut result = cjson_replace_item_in_object_case_sensitive(object, "Name", new_item).unwrap();
-///     assert_eq!(result, false);
-///     result = cjson_replace_item_in_object_case_sensitive(object, "name", new_item).unwrap();
-///     assert_eq!(result, true);
+///     let merged = cjson_deep_merge(base, overlay).unwrap();
 ///     assert_eq!(
-///         cjson_get_string_value(cjson_get_object_item(object, "name").unwrap()).unwrap(),
-///         "Wainaina"
+///         merged.print_unformatted().unwrap(),
+///         r#"{"name":"Nemuel","tags":["a","b"],"address":{"city":"Nairobi","zip":"00200"},"age":20}"#
 ///     );
-///
+///     assert_eq!(base.print_unformatted().unwrap(), r#"{"name":"Nemuel","tags":["a"],"address":{"city":"Nairobi","zip":"00100"}}"#);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_replace_item_in_object_case_sensitive(
-    object: *mut Json,
-    string: &str,
-    newitem: *mut Json,
-) -> Result<bool, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot replace item in a non-object Json item".to_string(),
-        ));
-    }
-
-    match CString::new(string) {
-        Ok(c_str) => {
-            let result = unsafe {
-                cJSON_ReplaceItemInObjectCaseSensitive(
-                    object as *mut cJSON,
-                    c_str.as_ptr(),
-                    newitem as *mut cJSON,
-                )
-            };
-            if result == 1 {
-                Ok(true)
-            } else {
-                Ok(false)
-            }
-        }
-        Err(err) => Err(JsonError::CStringError(err)),
-    }
+pub fn cjson_deep_merge(base: *mut Json, overlay: *mut Json) -> Result<*mut Json, JsonError> {
+    cjson_deep_merge_with(base, overlay, ArrayMergeStrategy::Concat)
 }
 
-/// Detach item from Json item of type `Object`.
+/// Recursively merge 2 Json items into a new one.
+///
+/// Unlike a JSON Merge Patch (RFC 7386), nothing gets removed: objects are merged key by key
+/// (recursively), arrays are combined according to `arrays`, and any other conflicting value
+/// (including values of different types) is taken from `overlay`. Both inputs are left untouched;
+/// the result is built from copies.
 ///
 /// Args:
-/// - `object: *mut Json` - Mutable pointer to the Json item of type `Object` from which an item is to
-/// be detached.
-/// - `string: &str` - The key value for the item that is to be detached from the object.
+/// - `base: *mut Json` - Mutable pointer to the base Json item.
+/// - `overlay: *mut Json` - Mutable pointer to the Json item merged on top of `base`.
+/// - `arrays: ArrayMergeStrategy` - How arrays found at the same place are combined.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the detached item if the operation happens.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be operated on is not of type
-/// `Object`.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
+/// - `Ok(*mut Json)` - a mutable pointer to the merged item (to be deleted by the caller).
+/// - `Err(JsonError::NullPointer)` - if either pointer is null or an item could not be allocated.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let object = cjson_create_object();
-///     let string_item = cjson_create_string("Nemuel".to_string()).unwrap();
-///
-///     cjson_add_item_to_object(object, "name", string_item).unwrap();
-///     assert_eq!(cjson_has_object_item(object, "name").unwrap(), true);
+///     let base = cjson_parse_json(r#"{"tags":["a"],"nested":{"list":[1]}}"#.to_string()).unwrap();
+///     let overlay = cjson_parse_json(r#"{"tags":["b"],"nested":{"list":[2,3]}}"#.to_string()).unwrap();
 ///
-///     let detached_item = cjson_detach_item_from_object(object, "name").unwrap();
-///     assert_eq!(detached_item.is_type_string(), true);
-///     assert_eq!(cjson_has_object_item(object, "name").unwrap(), false);
+///     let concatenated = cjson_deep_merge_with(base, overlay, ArrayMergeStrategy::Concat).unwrap();
+///     assert_eq!(concatenated.print_unformatted().unwrap(), r#"{"tags":["a","b"],"nested":{"list":[1,2,3]}}"#);
 ///
+///     let replaced = cjson_deep_merge_with(base, overlay, ArrayMergeStrategy::Replace).unwrap();
+///     assert_eq!(replaced.print_unformatted().unwrap(), r#"{"tags":["b"],"nested":{"list":[2,3]}}"#);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_detach_item_from_object(
-    object: *mut Json,
-    string: &str,
+pub fn cjson_deep_merge_with(
+    base: *mut Json,
+    overlay: *mut Json,
+    arrays: ArrayMergeStrategy,
 ) -> Result<*mut Json, JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot detach item from a non-object Json item".to_string(),
-        ));
+    if base.is_null() || overlay.is_null() {
+        return Err(JsonError::NullPointer);
     }
 
-    match CString::new(string) {
-        Ok(c_str) => {
-            let detached_item = unsafe {
-                cJSON_DetachItemFromObject(object as *mut cJSON, c_str.as_ptr()) as *mut Json
-            };
-            Ok(detached_item)
+    let mergeable = (base.is_type_object() && overlay.is_type_object())
+        || (arrays == ArrayMergeStrategy::Concat
+            && base.is_type_array()
+            && overlay.is_type_array());
+    let merged = unsafe {
+        cJSON_Duplicate((if mergeable { base } else { overlay }) as *const cJSON, 1) as *mut Json
+    };
+    if merged.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    if mergeable {
+        if let Err(err) = merge_into(merged, overlay, arrays) {
+            unsafe { cJSON_Delete(merged as *mut cJSON) };
+            return Err(err);
         }
-        Err(err) => Err(JsonError::CStringError(err)),
     }
+    Ok(merged)
 }
 
-/// Detach item from Json item of type `Object`, with a case-sensitive comparison of keys.
+/// Merge 2 Json items of type `Array` holding records (objects identified by a key) into a new
+/// array.
+///
+/// Each item of `overlay` replaces the first item of `base` whose `key` member is equal to its own
+/// (compared like `cjson_compare` with case sensitivity), and is appended otherwise. Items of
+/// `overlay` without a `key` member are always appended. Both inputs are left untouched; the result
+/// is built from copies.
 ///
 /// Args:
-/// - `object: *mut Json` - Mutable pointer to the Json item of type `Object` from which an item is to
-/// be detached.
-/// - `string: &str` - The key value for the item that is to be detached from the object.
+/// - `base: *mut Json` - The base Json item of type `Array`.
+/// - `overlay: *mut Json` - The Json item of type `Array` merged on top of `base`.
+/// - `key: &str` - The key (case-sensitive) of the member identifying a record.
 ///
 /// Returns:
-/// - `Ok(*mut Json)` - a mutable pointer to the detached item if the operation happens.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be operated on is not of type
-/// `Object`.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
-pub fn cjson_detach_item_from_object_case_sensitive(
-    object: *mut Json,
-    string: &str,
+/// - `Ok(*mut Json)` - a mutable pointer to the merged Json item of type `Array` (to be deleted by
+/// the caller).
+/// - `Err(JsonError::InvalidTypeError(String))` - if either value provided is not of type `Array`.
+/// - `Err(JsonError::NullPointer)` - if an item could not be allocated.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let base = cjson_parse_json(r#"[{"id":1,"name":"a"},{"id":2,"name":"b"},{"id":3,"name":"c"}]"#.to_string()).unwrap();
+///     let overlay = cjson_parse_json(r#"[{"id":2,"name":"B"},{"id":4,"name":"d"}]"#.to_string()).unwrap();
+///
+///     let merged = cjson_merge_arrays_by_key(base, overlay, "id").unwrap();
+///     assert_eq!(
+///         merged.print_unformatted().unwrap(),
+///         r#"[{"id":1,"name":"a"},{"id":2,"name":"B"},{"id":3,"name":"c"},{"id":4,"name":"d"}]"#
+///     );
+///     assert_eq!(cjson_get_array_size(base).unwrap(), 3);
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_merge_arrays_by_key(
+    base: *mut Json,
+    overlay: *mut Json,
+    key: &str,
 ) -> Result<*mut Json, JsonError> {
-    if !object.is_type_object() {
+    if !base.is_type_array() || !overlay.is_type_array() {
         return Err(JsonError::InvalidTypeError(
-            "cannot detach item from a non-object Json item".to_string(),
+            "cannot merge non-array Json items by key".to_string(),
         ));
     }
 
-    match CString::new(string) {
-        Ok(c_str) => {
-            let detached_item = unsafe {
-                cJSON_DetachItemFromObjectCaseSensitive(object as *mut cJSON, c_str.as_ptr())
-                    as *mut Json
-            };
-            Ok(detached_item)
+    let merged = unsafe { cJSON_Duplicate(base as *const cJSON, 1) as *mut Json };
+    if merged.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    let record_id = |record: *mut Json| {
+        if record.is_type_object() {
+            object_member(record, key)
+        } else {
+            None
+        }
+    };
+    for record in child_items(overlay) {
+        let copy = unsafe { cJSON_Duplicate(record as *const cJSON, 1) };
+        let matching = record_id(record).and_then(|id| {
+            child_items(merged).into_iter().find(|&existing| {
+                record_id(existing).is_some_and(|existing_id| unsafe {
+                    cJSON_Compare(id as *const cJSON, existing_id as *const cJSON, 1) == 1
+                })
+            })
+        });
+        let added = !copy.is_null()
+            && unsafe {
+                match matching {
+                    Some(existing) => cJSON_ReplaceItemViaPointer(
+                        merged as *mut cJSON,
+                        existing as *mut cJSON,
+                        copy,
+                    ),
+                    None => cJSON_AddItemToArray(merged as *mut cJSON, copy),
+                }
+            } == 1;
+        if !added {
+            unsafe {
+                cJSON_Delete(copy);
+                cJSON_Delete(merged as *mut cJSON);
+            }
+            return Err(JsonError::NullPointer);
         }
-        Err(err) => Err(JsonError::CStringError(err)),
     }
+    Ok(merged)
 }
 
-/// Delete item with the specified key from Json item of type `Object`.
+/// Visit every Json item of a tree in pre-order (each item before its children, children in order).
+///
+/// `visit` is called with the key of the item (`None` for the root and for array items; invalid
+/// UTF-8 replaced by `U+FFFD`), the item itself and its depth (0 for the root).
 ///
 /// Args:
-/// - `object: *mut Json` - Mutable pointer to the Json item of type `Object` from which an item is to
-/// be deleted.
-/// - `string: &str` - The key value for the item that is to be deleted from the object.
+/// - `root: *mut Json` - Mutable pointer to the root of the tree.
+/// - `visit: F` - Function called for each item.
 ///
 /// Returns:
-/// - `Ok(())` - a mutable pointer to the detached item if the deletion operation happens.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be operated on is not of type
-/// `Object`.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
+/// - `Ok(())` - once every item has been visited.
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
-/// fn main() {
-///     let object = cjson_create_object();
-///     let string_item = cjson_create_string("Nemuel".to_string()).unwrap();
-///
-///     cjson_add_item_to_object(object, "name", string_item).unwrap();
-///     assert_eq!(cjson_has_object_item(object, "name").unwrap(), true);
-///
-///     cjson_delete_item_from_object(object, "name").unwrap();
-///     assert_eq!(cjson_has_object_item(object, "name").unwrap(), false);
+/// fn kind(node: &Json) -> &'static str {
+///     let node = node as *const Json as *mut Json;
+///     if node.is_type_object() {
+///         "object"
+///     } else if node.is_type_array() {
+///         "array"
+///     } else if node.is_type_number() {
+///         "number"
+///     } else {
+///         "string"
+///     }
+/// }
 ///
+/// fn main() {
+///     let json = cjson_parse_json(r#"{"name":"Nemuel","scores":[1,2],"meta":{"id":7}}"#.to_string()).unwrap();
+///
+///     let mut visited = Vec::new();
+///     cjson_walk(json, |key, node, depth| {
+///         visited.push((key.map(str::to_string), kind(node), depth))
+///     })
+///     .unwrap();
+///
+///     let expected = [
+///         (None, "object", 0),
+///         (Some("name"), "string", 1),
+///         (Some("scores"), "array", 1),
+///         (None, "number", 2),
+///         (None, "number", 2),
+///         (Some("meta"), "object", 1),
+///         (Some("id"), "number", 2),
+///     ];
+///     let expected: Vec<_> = expected
+///         .iter()
+///         .map(|&(key, kind, depth)| (key.map(str::to_string), kind, depth))
+///         .collect();
+///     assert_eq!(visited, expected);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_delete_item_from_object(object: *mut Json, string: &str) -> Result<(), JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot delete item from a non-object Json item".to_string(),
-        ));
+pub fn cjson_walk<F: FnMut(Option<&str>, &Json, usize)>(
+    root: *mut Json,
+    mut visit: F,
+) -> Result<(), JsonError> {
+    if root.is_null() {
+        return Err(JsonError::NullPointer);
     }
 
-    match CString::new(string) {
-        Ok(c_str) => {
-            unsafe { cJSON_DeleteItemFromObject(object as *mut cJSON, c_str.as_ptr()) };
-            Ok(())
-        }
-        Err(err) => Err(JsonError::CStringError(err)),
+    // (item, depth, whether the item is an object member)
+    let mut pending = vec![(root, 0, false)];
+    while let Some((item, depth, keyed)) = pending.pop() {
+        let key = unsafe { (*item).string.as_ref() }
+            .filter(|_| keyed)
+            .map(|key| unsafe { CStr::from_ptr(key) }.to_string_lossy());
+        visit(key.as_deref(), unsafe { &*item }, depth);
+
+        let is_object = item.is_type_object();
+        pending.extend(
+            child_items(item)
+                .into_iter()
+                .rev()
+                .map(|child| (child, depth + 1, is_object)),
+        );
     }
+    Ok(())
 }
 
-/// Delete item with the specified key from Json item of type `Object`, with a case-sensitive comparison
-/// of keys.
+/// A JSON value materialized into owned Rust data, independent of any cJSON tree.
+///
+/// Returned by `cjson_get_value`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    /// The members in insertion order (duplicate keys are kept).
+    Object(Vec<(String, JsonValue)>),
+    /// The content of a Json item of type `Raw`, as is.
+    Raw(String),
+}
+
+// copy a C string owned by a Json item into a String (null becomes the empty string)
+fn owned_string(c_str: *const c_char) -> Result<String, JsonError> {
+    if c_str.is_null() {
+        return Ok(String::new());
+    }
+    match unsafe { CStr::from_ptr(c_str) }.to_str() {
+        Ok(string) => Ok(string.to_string()),
+        Err(err) => Err(JsonError::Utf8Error(err)),
+    }
+}
+
+/// Copy a Json item and everything nested in it into an owned `JsonValue`.
 ///
 /// Args:
-/// - `object: *mut Json` - Mutable pointer to the Json item of type `Object` from which an item is to
-/// be deleted.
-/// - `string: &str` - The key value for the item that is to be deleted from the object.
+/// - `item: *mut Json` - Mutable pointer to the Json item to be copied.
 ///
 /// Returns:
-/// - `Ok(())` - a mutable pointer to the detached item if the deletion operation happens.
-/// - `Err(JsonError::InvalidTypeError(String))` - if the Json item to be operated on is not of type
-/// `Object`.
-/// - `Err(JsonError::CStringError(NulError))` - if the provided string slice contains a null byte.
-pub fn cjson_delete_item_from_object_case_sensitive(
-    object: *mut Json,
-    string: &str,
-) -> Result<(), JsonError> {
-    if !object.is_type_object() {
-        return Err(JsonError::InvalidTypeError(
-            "cannot delete item from a non-object Json item".to_string(),
-        ));
+/// - `Ok(JsonValue)` - the value of the item.
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
+/// - `Err(JsonError::InvalidTypeError(String))` - if the item or one of its descendants is of type
+/// `Invalid`.
+/// - `Err(JsonError::Utf8Error(Utf8Error))` - if a string, key or raw value is not valid UTF-8.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let json = cjson_parse_json(r#"{"name":"Nemuel","age":20,"tags":["a",null,true]}"#.to_string()).unwrap();
+///
+///     match cjson_get_value(json).unwrap() {
+///         JsonValue::Object(members) => {
+///             assert_eq!(members[0], ("name".to_string(), JsonValue::String("Nemuel".to_string())));
+///             assert_eq!(members[1], ("age".to_string(), JsonValue::Number(20.0)));
+///             assert_eq!(
+///                 members[2].1,
+///                 JsonValue::Array(vec![
+///                     JsonValue::String("a".to_string()),
+///                     JsonValue::Null,
+///                     JsonValue::Bool(true),
+///                 ])
+///             );
+///         }
+///         other => panic!("expected an object, got {:?}", other),
+///     }
+///
+///     let raw = cjson_create_raw("[1, 2]".to_string()).unwrap();
+///     assert_eq!(cjson_get_value(raw).unwrap(), JsonValue::Raw("[1, 2]".to_string()));
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_get_value(item: *mut Json) -> Result<JsonValue, JsonError> {
+    if item.is_null() {
+        return Err(JsonError::NullPointer);
     }
 
-    match CString::new(string) {
-        Ok(c_str) => {
-            unsafe {
-                cJSON_DeleteItemFromObjectCaseSensitive(object as *mut cJSON, c_str.as_ptr())
-            };
-            Ok(())
+    if item.is_type_null() {
+        Ok(JsonValue::Null)
+    } else if item.is_type_bool() {
+        Ok(JsonValue::Bool(item.is_type_true()))
+    } else if item.is_type_number() {
+        Ok(JsonValue::Number(unsafe {
+            cJSON_GetNumberValue(item as *const cJSON)
+        }))
+    } else if item.is_type_string() {
+        owned_string(unsafe { cJSON_GetStringValue(item as *const cJSON) }).map(JsonValue::String)
+    } else if item.is_type_raw() {
+        // cJSON has no getter for raw values, but prints them verbatim (and fails on a null one)
+        match print_bytes(item, false).map(String::from_utf8) {
+            Ok(Ok(raw)) => Ok(JsonValue::Raw(raw)),
+            Ok(Err(err)) => Err(JsonError::Utf8Error(err.utf8_error())),
+            Err(_) => Ok(JsonValue::Raw(String::new())),
         }
-        Err(err) => Err(JsonError::CStringError(err)),
+    } else if item.is_type_array() {
+        child_items(item)
+            .into_iter()
+            .map(cjson_get_value)
+            .collect::<Result<_, _>>()
+            .map(JsonValue::Array)
+    } else if item.is_type_object() {
+        child_items(item)
+            .into_iter()
+            .map(|member| {
+                Ok((
+                    owned_string(unsafe { (*member).string })?,
+                    cjson_get_value(member)?,
+                ))
+            })
+            .collect::<Result<_, _>>()
+            .map(JsonValue::Object)
+    } else {
+        Err(JsonError::InvalidTypeError(
+            "cannot get the value of an invalid Json item".to_string(),
+        ))
     }
 }
 
-/// Detach Json item from its parent via pointer (thus maintaining access to the detached item).
+/// Build a new Json item (and everything nested in it) from a `JsonValue`.
 ///
 /// Args:
-/// - `parent: *mut Json` - Mutable pointer to the parent Json item from which an item is to be detached.
-/// - `item: *mut Json` - Mutable pointer to the Json item that is to be detached from its parent.
+/// - `value: &JsonValue` - The value to be converted.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the detached item.
+/// - `Ok(*mut Json)` - a mutable pointer to the created Json item (to be deleted by the caller).
+/// - `Err(JsonError::CStringError(NulError))` - if a string, key or raw value contains a null byte
+/// (the items created so far are deleted).
+/// - `Err(JsonError::NullPointer)` - if an item could not be allocated.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let parent = cjson_create_object();
-///     let item = cjson_create_string("Nemuel".to_string()).unwrap();
-///
-///     cjson_add_item_to_object(parent, "name", item).unwrap();
-///     assert_eq!(cjson_has_object_item(parent, "name").unwrap(), true);
-///
-///     let detached_item = cjson_detach_item_via_pointer(parent, item);
-///     assert_eq!(detached_item.is_type_string(), true);
-///     assert_eq!(cjson_has_object_item(parent, "name").unwrap(), false);
+///     let value = JsonValue::Object(vec![
+///         ("name".to_string(), JsonValue::String("Nemuel".to_string())),
+///         ("age".to_string(), JsonValue::Number(20.0)),
+///         ("score".to_string(), JsonValue::Raw("1.50".to_string())),
+///         (
+///             "tags".to_string(),
+///             JsonValue::Array(vec![JsonValue::Bool(false), JsonValue::Null]),
+///         ),
+///     ]);
+///     let json = cjson_from_value(&value).unwrap();
+///     assert_eq!(
+///         json.print_unformatted().unwrap(),
+///         r#"{"name":"Nemuel","age":20,"score":1.50,"tags":[false,null]}"#
+///     );
+///     assert_eq!(cjson_get_value(json).unwrap(), value);
 ///
+///     let invalid = JsonValue::Array(vec![JsonValue::Null, JsonValue::String("a\0b".to_string())]);
+///     assert!(matches!(cjson_from_value(&invalid), Err(JsonError::CStringError(_))));
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_detach_item_via_pointer(parent: *mut Json, item: *mut Json) -> *mut Json {
-    unsafe { cJSON_DetachItemViaPointer(parent as *mut cJSON, item as *mut cJSON) as *mut Json }
+pub fn cjson_from_value(value: &JsonValue) -> Result<*mut Json, JsonError> {
+    let c_string = |string: &str| CString::new(string).map_err(JsonError::CStringError);
+
+    let item = unsafe {
+        match value {
+            JsonValue::Null => cJSON_CreateNull(),
+            JsonValue::Bool(boolean) => cJSON_CreateBool(if *boolean { 1 } else { 0 }),
+            JsonValue::Number(number) => cJSON_CreateNumber(*number),
+            JsonValue::String(string) => cJSON_CreateString(c_string(string)?.as_ptr()),
+            JsonValue::Raw(raw) => cJSON_CreateRaw(c_string(raw)?.as_ptr()),
+            JsonValue::Array(_) => cJSON_CreateArray(),
+            JsonValue::Object(_) => cJSON_CreateObject(),
+        }
+    };
+    if item.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    let filled = match value {
+        JsonValue::Array(items) => items.iter().try_for_each(|value| {
+            let child = cjson_from_value(value)? as *mut cJSON;
+            if unsafe { cJSON_AddItemToArray(item, child) } != 1 {
+                unsafe { cJSON_Delete(child) };
+                return Err(JsonError::NullPointer);
+            }
+            Ok(())
+        }),
+        JsonValue::Object(members) => members.iter().try_for_each(|(key, value)| {
+            let key = c_string(key)?;
+            let child = cjson_from_value(value)? as *mut cJSON;
+            if unsafe { cJSON_AddItemToObject(item, key.as_ptr(), child) } != 1 {
+                unsafe { cJSON_Delete(child) };
+                return Err(JsonError::NullPointer);
+            }
+            Ok(())
+        }),
+        _ => Ok(()),
+    };
+    match filled {
+        Ok(()) => Ok(item as *mut Json),
+        Err(err) => {
+            unsafe { cJSON_Delete(item) };
+            Err(err)
+        }
+    }
 }
 
-/// Replace a Json item from its parent via pointer with a new item.
+/// Replace the value of every Json item of type `String` in a tree with the result of `f`.
+///
+/// Object keys are left as they are. Values that are not valid UTF-8 are passed to `f` with
+/// invalid sequences replaced by `U+FFFD`. If an error occurs, the strings visited before it keep
+/// their new values.
 ///
 /// Args:
-/// - `parent: *mut Json` - Mutable pointer to the parent Json item in which an item is to be replaced.
-/// - `item: *mut Json` - Mutable pointer to the Json item that is to be replaced with another one.
-/// - `replacement: *mut Json` - Mutable pointer to the Json item that is to replace the original one.
+/// - `root: *mut Json` - Mutable pointer to the root of the tree.
+/// - `f: F` - Function mapping each string value to its replacement.
 ///
 /// Returns:
-/// - `bool` - a boolean value indicating success or failure of the operation.
+/// - `Ok(())` - if every string value was replaced.
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
+/// - `Err(JsonError::CStringError(NulError))` - if `f` returns a string containing a null byte.
+/// - `Err(JsonError::SetStringFailed)` - if cJSON fails to set a value (eg. on a string reference
+/// item).
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let parent = cjson_create_array();
-///     let item = cjson_create_string("Nemuel".to_string()).unwrap();
-///     cjson_add_item_to_array(parent, item).unwrap();
-///     assert_eq!(parent.print().unwrap(), r#"["Nemuel"]"#);
-///
-///     let replacement = cjson_create_string("Wainaina".to_string()).unwrap();
-///     cjson_replace_item_via_pointer(parent, item, replacement);
-///     assert_eq!(parent.print().unwrap(), r#"["Wainaina"]"#);
+///     let json = cjson_parse_json(r#"{"name":"nemuel","tags":["a",1,{"b":"c"}]}"#.to_string()).unwrap();
+///     cjson_map_strings(json, |value| value.to_uppercase()).unwrap();
+///     assert_eq!(json.print_unformatted().unwrap(), r#"{"name":"NEMUEL","tags":["A",1,{"b":"C"}]}"#);
 ///
+///     let result = cjson_map_strings(json, |_| "a\0b".to_string());
+///     assert!(matches!(result, Err(JsonError::CStringError(_))));
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_replace_item_via_pointer(
-    parent: *mut Json,
-    item: *mut Json,
-    replacement: *mut Json,
-) -> bool {
-    let result = unsafe {
-        cJSON_ReplaceItemViaPointer(
-            parent as *mut cJSON,
-            item as *mut cJSON,
-            replacement as *mut cJSON,
-        )
-    };
-    if result == 1 {
-        true
-    } else {
-        false
+pub fn cjson_map_strings<F: Fn(&str) -> String>(root: *mut Json, f: F) -> Result<(), JsonError> {
+    if root.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    let mut pending = vec![root];
+    while let Some(item) = pending.pop() {
+        if item.is_type_string() {
+            let value = unsafe { (*item).valuestring };
+            if value.is_null() {
+                continue;
+            }
+            let mapped = f(&unsafe { CStr::from_ptr(value) }.to_string_lossy());
+            let c_str = match CString::new(mapped) {
+                Ok(c_str) => c_str,
+                Err(err) => return Err(JsonError::CStringError(err)),
+            };
+            if unsafe { cJSON_SetValuestring(item as *mut cJSON, c_str.as_ptr()) }.is_null() {
+                return Err(JsonError::SetStringFailed);
+            }
+        } else {
+            pending.extend(child_items(item).into_iter().rev());
+        }
     }
+    Ok(())
 }
 
-/// Create a copy of a Json item.
+/// Delete every object member whose key (case-sensitive) is one of `keys`, at any depth.
+///
+/// Handy for scrubbing secrets before logging a document.
 ///
 /// Args:
-/// - `item: *mut Json` - Mutable pointer to the Json item to be duplicated.
-/// - `recurse: bool` - Boolean value specifying whether or not to duplicate nested structures as well.
+/// - `root: *mut Json` - Mutable pointer to the root of the tree.
+/// - `keys: &[&str]` - Keys of the members to remove.
 ///
 /// Returns:
-/// - `*mut Json` - a mutable pointer to the newly created duplicate Json item.
+/// - `Ok(usize)` - the number of members removed (members nested in a removed member are not
+/// counted).
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let original = cjson_create_string("Nemuel".to_string()).unwrap();
-///
-///     let copy = cjson_duplicate(original, false);
-///
-///     let result = cjson_compare(original, copy, true);
-///     assert_eq!(result, true);
+///     let json = cjson_parse_json(
+///         r#"{"user":"nemuel","password":"x","db":{"password":"y","host":"h"},"replicas":[{"password":"z"}]}"#.to_string(),
+///     )
+///     .unwrap();
+///     assert_eq!(cjson_redact_keys(json, &["password"]).unwrap(), 3);
+///     assert_eq!(
+///         json.print_unformatted().unwrap(),
+///         r#"{"user":"nemuel","db":{"host":"h"},"replicas":[{}]}"#
+///     );
+///     assert_eq!(cjson_redact_keys(json, &["Password"]).unwrap(), 0);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_duplicate(item: *mut Json, recurse: bool) -> *mut Json {
-    unsafe { cJSON_Duplicate(item as *const cJSON, if recurse { 1 } else { 0 }) as *mut Json }
+pub fn cjson_redact_keys(root: *mut Json, keys: &[&str]) -> Result<usize, JsonError> {
+    if root.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    let mut removed = 0;
+    let mut pending = vec![root];
+    while let Some(item) = pending.pop() {
+        let is_object = item.is_type_object();
+        for child in child_items(item) {
+            let key = unsafe { (*child).string };
+            let redacted = is_object
+                && !key.is_null()
+                && keys
+                    .iter()
+                    .any(|k| k.as_bytes() == unsafe { CStr::from_ptr(key) }.to_bytes());
+            if redacted {
+                unsafe {
+                    cJSON_DetachItemViaPointer(item as *mut cJSON, child as *mut cJSON);
+                    cJSON_Delete(child as *mut cJSON);
+                }
+                removed += 1;
+            } else {
+                pending.push(child);
+            }
+        }
+    }
+    Ok(removed)
 }
 
-/// Check whether 2 Json items are equivalent in structure and value.
+/// Replace repeated arrays and objects in a tree with references to their first occurrence, to
+/// save memory.
+///
+/// Subtrees are considered identical when their compact string representations are equal (so
+/// objects with the same members in a different order are kept apart). Each duplicate keeps its
+/// key and position but becomes a reference item sharing the children of the first occurrence, the
+/// same way `cjson_add_item_reference_to_object` items do.
+///
+/// NOTE: The duplicates are aliases afterwards: changing the children of either item changes both,
+/// and deleting or detaching the first occurrence (without deleting the whole tree) leaves the
+/// references dangling. Only use this on trees that are done being modified.
 ///
 /// Args:
-/// - `a: *mut Json` - Mutable pointer to the first Json item.
-/// - `b: *mut Json` - Mutable pointer to the second Json item.
-/// - `case_sensitive: bool` - Boolean value specifying whether or not to do case-sensitive comparison
-/// for string values.
+/// - `root: *mut Json` - Mutable pointer to the root of the tree.
 ///
 /// Returns:
-/// - `bool` - a boolean value (true or false) indicating whether or not the 2 Json items are equivalent.
+/// - `Ok(usize)` - the number of subtrees replaced by references (subtrees nested in a replaced
+/// one are not counted).
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
+/// - `Err(JsonError::PrintError)` - if a subtree cannot be printed.
 ///
 /// Example:
 /// ```rust
 /// use cjson_rs::*;
 ///
 /// fn main() {
-///     let item1 = cjson_create_string("Nemuel".to_string()).unwrap();
-///     let item2 = cjson_create_string("Nemuel".to_string()).unwrap();
-///     let result = cjson_compare(item1, item2, true);
-///     assert_eq!(result, true);
+///     let document = r#"{"home":{"city":"Nairobi","tags":[1,2]},"work":{"city":"Nairobi","tags":[1,2]},"other":{"tags":[1,2],"city":"Nairobi"}}"#;
+///     let mut json = cjson_parse_json(document.to_string()).unwrap();
+///
+///     assert_eq!(cjson_dedup_refs(json).unwrap(), 2);
+///     assert_eq!(json.print_unformatted().unwrap(), document);
+///
+///     // "work" now shares its members with "home"
+///     let home = cjson_get_object_item(json, "home").unwrap();
+///     cjson_add_item_to_array(cjson_get_object_item(home, "tags").unwrap(), cjson_create_number(3.0));
+///     let work = cjson_get_object_item(json, "work").unwrap();
+///     assert_eq!(work.print_unformatted().unwrap(), r#"{"city":"Nairobi","tags":[1,2,3]}"#);
+///
+///     cjson_delete(&mut json);
 ///     println!("Test passed"); // output: Test passed
 /// }
 /// ```
-pub fn cjson_compare(a: *mut Json, b: *mut Json, case_sensitive: bool) -> bool {
-    let result = unsafe {
-        cJSON_Compare(
-            a as *const cJSON,
-            b as *const cJSON,
-            if case_sensitive { 1 } else { 0 },
-        )
-    };
-    if result == 1 {
-        true
-    } else {
-        false
+pub fn cjson_dedup_refs(root: *mut Json) -> Result<usize, JsonError> {
+    if root.is_null() {
+        return Err(JsonError::NullPointer);
+    }
+
+    let mut seen: std::collections::HashMap<String, *mut Json> = std::collections::HashMap::new();
+    let mut deduplicated = 0;
+    let mut pending = vec![root];
+    while let Some(item) = pending.pop() {
+        let is_reference = unsafe { (*item).type_ } & cJSON_IsReference as i32 != 0;
+        let is_container = item.is_type_array() || item.is_type_object();
+        if is_reference || !is_container {
+            continue;
+        }
+
+        if item != root && !unsafe { (*item).child }.is_null() {
+            let printed = item.print_unformatted()?;
+            if let Some(&original) = seen.get(&printed) {
+                // turn the duplicate into a reference item in place so it keeps its key and
+                // siblings; cJSON_Delete does not free the children of reference items
+                unsafe {
+                    cJSON_Delete((*item).child as *mut cJSON);
+                    (*item).child = (*original).child;
+                    (*item).type_ |= cJSON_IsReference as i32;
+                }
+                deduplicated += 1;
+                continue;
+            }
+            seen.insert(printed, item);
+        }
+        pending.extend(child_items(item).into_iter().rev());
     }
+    Ok(deduplicated)
 }
 
 /// Deallocate/free the memory allocated for a Json item along with all its nested structures if any.
 ///
 /// NOTE: The pointers to the parent item and all its nested structures (if any) are themselves not
 /// set to NULL, raising a dangling pointers issue.
+/// Use `cjson_delete_and_null` to have `item` set to NULL as well.
 ///
 /// Args:
 /// - `item: *mut Json` - Mutable pointer to the Json item whose memory is to be deallocated/freed.
@@ -2903,8 +10752,74 @@ pub fn cjson_delete(item: &mut *mut Json) {
     }
 }
 
+/// Deallocate/free the memory allocated for a Json item along with all its nested structures if any,
+/// then set the pointer to NULL.
+///
+/// A later use of the pointer then shows up as a null check failure instead of a use after free.
+/// Other pointers into the deleted tree (e.g. to nested items) are still left dangling.
+///
+/// Args:
+/// - `item: &mut *mut Json` - Mutable reference to the pointer to the Json item to be deleted.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let mut object = cjson_create_object();
+///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
+///
+///     cjson_delete_and_null(&mut object);
+///     assert!(object.is_null());
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_delete_and_null(item: &mut *mut Json) {
+    cjson_delete(item);
+    *item = std::ptr::null_mut();
+}
+
+/// Generate the string representation of a Json item, then deallocate/free the item along with all
+/// its nested structures and set the pointer to NULL.
+///
+/// If printing fails the item is neither deleted nor is the pointer changed.
+///
+/// Args:
+/// - `item: &mut *mut Json` - Mutable reference to the pointer to the Json item to be consumed.
+/// - `pretty: bool` - Whether or not to have the output formatted/pretty-printed.
+///
+/// Returns:
+/// - `Ok(String)` - the string representation of the item.
+/// - `Err(JsonError::NullPointer)` - if the pointer is null.
+/// - `Err(JsonError::PrintError)` - if the string generation fails.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let mut object = cjson_create_object();
+///     cjson_add_string_to_object(object, "name", "Nemuel").unwrap();
+///
+///     assert_eq!(cjson_into_string(&mut object, false).unwrap(), r#"{"name":"Nemuel"}"#);
+///     assert!(object.is_null());
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_into_string(item: &mut *mut Json, pretty: bool) -> Result<String, JsonError> {
+    let printed = if pretty {
+        item.print()?
+    } else {
+        item.print_unformatted()?
+    };
+    cjson_delete_and_null(item);
+    Ok(printed)
+}
+
 /// Allocate a specified amount of memory.
 ///
+/// NOTE: The memory has to be freed with `cjson_free`. `CjsonBuffer` does that automatically.
+///
 /// Args:
 /// - `size: usize` - Amount of memory to allocate.
 ///
@@ -2917,6 +10832,7 @@ pub fn cjson_malloc(size: usize) -> *mut c_void {
 /// Deallocate/free the memory at the specified location.
 ///
 /// NOTE: The pointer to the memory location is itself not set to NULL, raising a dangling pointer issue.
+/// Use `cjson_free_and_null` to have it set to NULL as well.
 ///
 /// Args:
 /// - `item: *mut c_void` - Mutable pointer to the memory which is to be deallocated/freed.
@@ -2925,3 +10841,27 @@ pub fn cjson_free(item: *mut c_void) {
         cJSON_free(item);
     }
 }
+
+/// Deallocate/free the memory at the specified location, then set the pointer to NULL.
+///
+/// Args:
+/// - `item: &mut *mut c_void` - Mutable reference to the pointer to the memory which is to be
+/// deallocated/freed.
+///
+/// Example:
+/// ```rust
+/// use cjson_rs::*;
+///
+/// fn main() {
+///     let mut memory = cjson_malloc(16);
+///     assert!(!memory.is_null());
+///
+///     cjson_free_and_null(&mut memory);
+///     assert!(memory.is_null());
+///     println!("Test passed"); // output: Test passed
+/// }
+/// ```
+pub fn cjson_free_and_null(item: &mut *mut c_void) {
+    cjson_free(*item);
+    *item = std::ptr::null_mut();
+}